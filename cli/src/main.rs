@@ -0,0 +1,161 @@
+//! Off-chain tooling for airdrop distributors.
+//!
+//! - `airdrop tree` builds the Merkle tree of a claim airdrop from a CSV of `recipient,item`
+//!   rows and writes one JSON file of claims (with proofs) per recipient. A row's leaf index
+//!   is its position in the CSV, starting at 0.
+//! - `airdrop plan` splits a CSV of recipients into `push_airdrop` transactions that fit the
+//!   transaction size and compute limits.
+
+mod plan;
+mod tree;
+
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Parser, Subcommand};
+use mint_nft::utils::airdrop_leaf;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{plan::DistributorKind, tree::MerkleTree};
+
+#[derive(Parser)]
+#[command(name = "airdrop", about = "Airdrop distributor tooling")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build a claim airdrop Merkle tree and per-recipient proofs
+    Tree {
+        /// CSV with a `recipient,item` header
+        #[arg(long)]
+        input: PathBuf,
+
+        /// Directory for `tree.json` and `<recipient>.json` proof files
+        #[arg(long)]
+        output_dir: PathBuf,
+    },
+    /// Split recipients into push airdrop transactions
+    Plan {
+        /// CSV with a `recipient` header
+        #[arg(long)]
+        input: PathBuf,
+
+        /// JSON file receiving the batches, in submission order
+        #[arg(long)]
+        output: PathBuf,
+
+        #[arg(long, value_enum)]
+        kind: DistributorKind,
+
+        /// Item pushed to the first recipient; later recipients get the following items
+        #[arg(long, default_value_t = 1)]
+        first_item: u64,
+
+        /// Compute units budgeted for each recipient
+        #[arg(long, default_value_t = 130_000)]
+        compute_per_recipient: u32,
+    },
+}
+
+#[derive(Deserialize)]
+struct TreeRow {
+    recipient: String,
+    item: u64,
+}
+
+#[derive(Deserialize)]
+struct PlanRow {
+    recipient: String,
+}
+
+#[derive(Serialize)]
+struct Claim {
+    index: u64,
+    recipient: String,
+    item: u64,
+    proof: Vec<[u8; 32]>,
+}
+
+#[derive(Serialize)]
+struct TreeSummary {
+    merkle_root: [u8; 32],
+    max_recipients: u64,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    match Args::parse().command {
+        Command::Tree { input, output_dir } => build_tree(&input, &output_dir),
+        Command::Plan { input, output, kind, first_item, compute_per_recipient } => {
+            let recipients = read_rows::<PlanRow>(&input)?
+                .into_iter()
+                .enumerate()
+                .map(|(index, row)| parse_recipient(index, &row.recipient))
+                .collect::<Result<Vec<_>, _>>()?;
+            let batches = plan::plan(&recipients, first_item, kind, compute_per_recipient)?;
+
+            fs::write(&output, serde_json::to_string_pretty(&batches)?)?;
+            println!("Planned {} transactions for {} recipients", batches.len(), recipients.len());
+            Ok(())
+        }
+    }
+}
+
+fn build_tree(input: &Path, output_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let rows = read_rows::<TreeRow>(input)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, row)| Ok((index as u64, parse_recipient(index, &row.recipient)?, row.item)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let tree = MerkleTree::new(
+        rows.iter()
+            .map(|(index, recipient, item)| airdrop_leaf(*index, recipient, *item))
+            .collect(),
+    );
+
+    let mut claims: BTreeMap<Pubkey, Vec<Claim>> = BTreeMap::new();
+    for (index, recipient, item) in &rows {
+        claims.entry(*recipient).or_default().push(Claim {
+            index: *index,
+            recipient: recipient.to_string(),
+            item: *item,
+            proof: tree.proof(*index as usize),
+        });
+    }
+
+    fs::create_dir_all(output_dir)?;
+    let summary = TreeSummary {
+        merkle_root: tree.root(),
+        max_recipients: rows.len() as u64,
+    };
+    fs::write(output_dir.join("tree.json"), serde_json::to_string_pretty(&summary)?)?;
+    for (recipient, recipient_claims) in &claims {
+        fs::write(
+            output_dir.join(format!("{}.json", recipient)),
+            serde_json::to_string_pretty(recipient_claims)?,
+        )?;
+    }
+
+    let root_hex: String = tree.root().iter().map(|byte| format!("{:02x}", byte)).collect();
+    println!("Merkle root: {}", root_hex);
+    println!("Wrote {} claims for {} recipients to {}", rows.len(), claims.len(), output_dir.display());
+    Ok(())
+}
+
+fn read_rows<T: DeserializeOwned>(input: &Path) -> Result<Vec<T>, Box<dyn std::error::Error>> {
+    let rows = csv::Reader::from_path(input)?
+        .deserialize()
+        .collect::<Result<Vec<T>, _>>()?;
+    if rows.is_empty() {
+        return Err("the CSV has no rows".into());
+    }
+    Ok(rows)
+}
+
+fn parse_recipient(index: usize, recipient: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(recipient.trim())
+        .map_err(|err| format!("row {}: invalid recipient {}: {}", index, recipient, err))
+}
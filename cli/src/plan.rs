@@ -0,0 +1,128 @@
+use anchor_lang::prelude::Pubkey;
+use mint_nft::constant::{PUSH_AIRDROP_NFT_ACCOUNTS, PUSH_AIRDROP_PRINT_ACCOUNTS};
+use serde::Serialize;
+
+// Legacy transaction limit, and the compute ceiling settable with SetComputeUnitLimit
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+pub const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+
+// Program ids, authority, distributor, claim bitmap and rent sysvar referenced by every push
+// transaction: authority, distributor, claim bitmap, rent, mint-nft, Metaplex, token,
+// associated token, system, compute budget
+const FIXED_KEYS: usize = 10;
+// Master mint, master edition, master metadata and vault of print distributors
+const PRINT_KEYS: usize = 4;
+// Accounts of `PushAirdropContext`, with unused optional accounts passed as the program id
+const CONTEXT_ACCOUNTS: usize = 12;
+// Compute spent outside the per-recipient loop
+const BASE_COMPUTE_UNITS: u32 = 20_000;
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum DistributorKind {
+    Print,
+    Nft,
+}
+
+impl DistributorKind {
+    fn accounts_per_recipient(self) -> usize {
+        match self {
+            DistributorKind::Print => PUSH_AIRDROP_PRINT_ACCOUNTS,
+            DistributorKind::Nft => PUSH_AIRDROP_NFT_ACCOUNTS,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Batch {
+    pub first_item: u64,
+    pub compute_unit_limit: u32,
+    pub recipients: Vec<String>,
+}
+
+/// Serialized size of a push transaction for `recipients` recipients: one signature, a
+/// SetComputeUnitLimit instruction and the `push_airdrop` instruction, without lookup tables.
+/// Every recipient account is counted as a distinct key, so the estimate is an upper bound.
+pub fn transaction_size(kind: DistributorKind, recipients: usize) -> usize {
+    let recipient_accounts = kind.accounts_per_recipient() * recipients;
+    let keys = FIXED_KEYS + recipient_accounts + match kind {
+        DistributorKind::Print => PRINT_KEYS,
+        DistributorKind::Nft => 0,
+    };
+    let signatures = 1 + 64;
+    let header = 3;
+    let account_keys = compact_len(keys) + 32 * keys;
+    let blockhash = 32;
+    let compute_budget_instruction = 1 + 1 + 1 + 5;
+    let push_metas = CONTEXT_ACCOUNTS + recipient_accounts;
+    // Discriminator and `first_item`
+    let push_instruction = 1 + compact_len(push_metas) + push_metas + 1 + 8 + 8;
+
+    signatures + header + account_keys + blockhash + 1 + compute_budget_instruction + push_instruction
+}
+
+/// Splits `recipients` into push transactions that stay within both the size and compute limits.
+/// Items are assigned in order starting from `first_item`.
+pub fn plan(
+    recipients: &[Pubkey],
+    first_item: u64,
+    kind: DistributorKind,
+    compute_per_recipient: u32,
+) -> Result<Vec<Batch>, String> {
+    let by_compute = ((MAX_COMPUTE_UNITS - BASE_COMPUTE_UNITS) / compute_per_recipient.max(1)) as usize;
+    let by_size = (1..)
+        .take_while(|count| transaction_size(kind, *count) <= MAX_TRANSACTION_SIZE)
+        .last()
+        .unwrap_or(0);
+    let per_batch = by_compute.min(by_size);
+    if per_batch == 0 {
+        return Err("a single recipient does not fit in one transaction".into());
+    }
+
+    Ok(recipients
+        .chunks(per_batch)
+        .enumerate()
+        .map(|(index, chunk)| Batch {
+            first_item: first_item + (index * per_batch) as u64,
+            compute_unit_limit: BASE_COMPUTE_UNITS + compute_per_recipient * chunk.len() as u32,
+            recipients: chunk.iter().map(Pubkey::to_string).collect(),
+        })
+        .collect())
+}
+
+fn compact_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_fit_limits_and_cover_every_item_once() {
+        let recipients: Vec<Pubkey> = (0..23).map(|_| Pubkey::new_unique()).collect();
+        for kind in [DistributorKind::Print, DistributorKind::Nft] {
+            let batches = plan(&recipients, 5, kind, 130_000).unwrap();
+
+            let mut next_item = 5;
+            for batch in &batches {
+                assert_eq!(batch.first_item, next_item);
+                assert!(transaction_size(kind, batch.recipients.len()) <= MAX_TRANSACTION_SIZE);
+                assert!(batch.compute_unit_limit <= MAX_COMPUTE_UNITS);
+                next_item += batch.recipients.len() as u64;
+            }
+            assert_eq!(next_item, 5 + recipients.len() as u64);
+        }
+    }
+
+    #[test]
+    fn compute_limit_caps_batches() {
+        let recipients: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let batches = plan(&recipients, 0, DistributorKind::Nft, 600_000).unwrap();
+
+        assert_eq!(batches.iter().map(|batch| batch.recipients.len()).collect::<Vec<_>>(), vec![2, 2]);
+    }
+}
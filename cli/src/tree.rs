@@ -0,0 +1,79 @@
+use mint_nft::utils::merkle_node;
+
+/// Merkle tree over `mint_nft::utils::merkle_leaf` leaves, with inner nodes from `mint_nft::utils::merkle_node`.
+/// An odd node at the end of a layer is carried up unchanged.
+pub struct MerkleTree {
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        assert!(!leaves.is_empty(), "a Merkle tree needs at least one leaf");
+
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => merkle_node(left, right),
+                    [node] => *node,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    pub fn proof(&self, mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use mint_nft::utils::{airdrop_leaf, verify_merkle_proof};
+
+    #[test]
+    fn every_proof_verifies_against_the_root() {
+        for count in 1..=9u64 {
+            let recipients: Vec<Pubkey> = (0..count).map(|_| Pubkey::new_unique()).collect();
+            let leaves = recipients
+                .iter()
+                .enumerate()
+                .map(|(index, recipient)| airdrop_leaf(index as u64, recipient, 100 + index as u64))
+                .collect::<Vec<_>>();
+            let tree = MerkleTree::new(leaves.clone());
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                assert!(verify_merkle_proof(&tree.proof(index), &tree.root(), *leaf));
+            }
+        }
+    }
+
+    #[test]
+    fn proof_does_not_verify_another_item() {
+        let recipient = Pubkey::new_unique();
+        let tree = MerkleTree::new(vec![
+            airdrop_leaf(0, &recipient, 1),
+            airdrop_leaf(1, &Pubkey::new_unique(), 2),
+        ]);
+
+        assert!(!verify_merkle_proof(&tree.proof(0), &tree.root(), airdrop_leaf(0, &recipient, 2)));
+    }
+}
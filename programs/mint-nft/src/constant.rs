@@ -1,4 +1,161 @@
 use anchor_lang::prelude::*;
 
 #[constant]
-pub const CREATOR_SEED: &[u8] = b"CREATOR";
\ No newline at end of file
+pub const CREATOR_SEED: &[u8] = b"CREATOR";
+
+#[constant]
+pub const MULTISIG_SIGNER_SEED: &[u8] = b"MULTISIG_SIGNER";
+
+#[constant]
+pub const MULTISIG_PROPOSAL_SEED: &[u8] = b"MULTISIG_PROPOSAL";
+
+pub const MAX_MULTISIG_MEMBERS: usize = 10;
+
+#[constant]
+pub const TREASURY_SEED: &[u8] = b"TREASURY";
+
+pub const MAX_BASIS_POINTS: u16 = 10000;
+
+#[constant]
+pub const LISTING_SEED: &[u8] = b"LISTING";
+
+#[constant]
+pub const LISTING_ESCROW_SEED: &[u8] = b"LISTING_ESCROW";
+
+#[constant]
+pub const BID_SEED: &[u8] = b"BID";
+
+#[constant]
+pub const AUCTION_SEED: &[u8] = b"AUCTION";
+
+#[constant]
+pub const AUCTION_ESCROW_SEED: &[u8] = b"AUCTION_ESCROW";
+
+#[constant]
+pub const SWAP_SEED: &[u8] = b"SWAP";
+
+#[constant]
+pub const SWAP_ESCROW_SEED: &[u8] = b"SWAP_ESCROW";
+
+pub const MAX_SWAP_ITEMS: usize = 4;
+
+#[constant]
+pub const RENTAL_SEED: &[u8] = b"RENTAL";
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+#[constant]
+pub const STAKE_POOL_SEED: &[u8] = b"STAKE_POOL";
+
+#[constant]
+pub const STAKE_VAULT_SEED: &[u8] = b"STAKE_VAULT";
+
+#[constant]
+pub const STAKE_ENTRY_SEED: &[u8] = b"STAKE_ENTRY";
+
+#[constant]
+pub const FRACTION_VAULT_SEED: &[u8] = b"FRACTION_VAULT";
+
+#[constant]
+pub const FRACTION_ESCROW_SEED: &[u8] = b"FRACTION_ESCROW";
+
+#[constant]
+pub const FRACTION_SHARE_MINT_SEED: &[u8] = b"FRACTION_SHARE_MINT";
+
+#[constant]
+pub const LOAN_SEED: &[u8] = b"LOAN";
+
+pub const SECONDS_PER_YEAR: i64 = 365 * SECONDS_PER_DAY;
+
+#[constant]
+pub const SOULBOUND_SEED: &[u8] = b"SOULBOUND";
+
+#[constant]
+pub const TICKET_EVENT_SEED: &[u8] = b"TICKET_EVENT";
+
+#[constant]
+pub const TICKET_TIER_VAULT_SEED: &[u8] = b"TICKET_TIER_VAULT";
+
+#[constant]
+pub const TICKET_SEED: &[u8] = b"TICKET";
+
+pub const MAX_GATE_STAFF: usize = 10;
+
+pub const MAX_TICKET_TIERS: usize = 5;
+
+#[constant]
+pub const MEMBERSHIP_SEED: &[u8] = b"MEMBERSHIP";
+
+#[constant]
+pub const VOUCHER_NONCE_SEED: &[u8] = b"VOUCHER_NONCE";
+
+#[constant]
+pub const LAZY_MINT_AUTHORITY_SEED: &[u8] = b"LAZY_MINT_AUTHORITY";
+
+// Nonces tracked by one bitmap page account
+pub const NONCES_PER_PAGE: u64 = 2048;
+
+#[constant]
+pub const ETH_DROP_SEED: &[u8] = b"ETH_DROP";
+
+#[constant]
+pub const ETH_DROP_VAULT_SEED: &[u8] = b"ETH_DROP_VAULT";
+
+#[constant]
+pub const ETH_CLAIM_SEED: &[u8] = b"ETH_CLAIM";
+
+// EIP-191 prefix for a 64-byte message (drop + recipient), as added by `personal_sign`
+pub const ETH_CLAIM_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n64";
+
+#[constant]
+pub const AIRDROP_DISTRIBUTOR_SEED: &[u8] = b"AIRDROP_DISTRIBUTOR";
+
+#[constant]
+pub const AIRDROP_BITMAP_SEED: &[u8] = b"AIRDROP_BITMAP";
+
+#[constant]
+pub const AIRDROP_VAULT_SEED: &[u8] = b"AIRDROP_VAULT";
+
+// Claimed bitmap size, bounding the recipients of one distributor to 65536
+pub const AIRDROP_BITMAP_BYTES: usize = 8192;
+
+#[constant]
+pub const AIRDROP_MINT_SEED: &[u8] = b"AIRDROP_MINT";
+
+// Remaining accounts per recipient of `push_airdrop`:
+// (recipient, recipient ATA, mint, metadata, edition) plus the edition marker for prints
+pub const PUSH_AIRDROP_NFT_ACCOUNTS: usize = 5;
+pub const PUSH_AIRDROP_PRINT_ACCOUNTS: usize = 6;
+
+#[constant]
+pub const DROP_CONFIG_SEED: &[u8] = b"DROP_CONFIG";
+
+#[constant]
+pub const DROP_ITEM_SEED: &[u8] = b"DROP_ITEM";
+
+#[constant]
+pub const DROP_BITMAP_SEED: &[u8] = b"DROP_BITMAP";
+
+// Minted-index bitmap size, bounding one drop to 65536 items
+pub const DROP_BITMAP_BYTES: usize = 8192;
+
+// Extra programs a drop may allow alongside the mint's own dependencies
+pub const MAX_ALLOWED_PROGRAMS: usize = 5;
+
+// Domain separation for Merkle trees, so a leaf can never be passed off as an inner node
+pub const MERKLE_LEAF_PREFIX: u8 = 0x00;
+pub const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+// ComputeBudget111111111111111111111111111111
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    3, 6, 70, 111, 229, 33, 23, 50, 255, 236, 173, 186, 114, 195, 155, 231, 188, 140, 229, 187, 197, 247, 18, 107, 44, 67, 155, 58, 64, 0, 0, 0
+]);
+
+#[constant]
+pub const REDEMPTION_SEED: &[u8] = b"REDEMPTION";
+
+#[constant]
+pub const REDEMPTION_VAULT_SEED: &[u8] = b"REDEMPTION_VAULT";
+
+#[constant]
+pub const REDEMPTION_PROGRESS_SEED: &[u8] = b"REDEMPTION_PROGRESS";
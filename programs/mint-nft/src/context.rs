@@ -3,6 +3,37 @@ use anchor_lang::{
     system_program::System,
 };
 
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{
+        Mint,
+        Token,
+        TokenAccount,
+    },
+};
+
+use mpl_token_metadata::state::{
+    EDITION,
+    EDITION_MARKER_BIT_SIZE,
+    PREFIX,
+};
+
+use crate::{
+    constant::*,
+    error::*,
+    state::*,
+};
+
+/// Metaplex Token Metadata program, for `Program<'info, TokenMetadata>` fields
+#[derive(Clone)]
+pub struct TokenMetadata;
+
+impl anchor_lang::Id for TokenMetadata {
+    fn id() -> Pubkey {
+        mpl_token_metadata::ID
+    }
+}
+
 #[derive(Accounts)]
 pub struct CreateMintAccountContext<'info> {
     
@@ -12,8 +43,7 @@ pub struct CreateMintAccountContext<'info> {
     #[account(mut)]
     pub mint_authority: Signer<'info>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
 
     pub system_program: Program<'info, System>,
 }
@@ -28,8 +58,7 @@ pub struct InitializeMintContext<'info> {
 
     pub rent: Sysvar<'info, Rent>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
     
 }
 
@@ -38,23 +67,24 @@ pub struct CreateAssociatedTokenAccountContext<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    /// CHECK: Associated token account init before
-    #[account(mut)]
-    pub associated_token: AccountInfo<'info>,
+    /// CHECK: Associated token account of `authority` for `mint`, created if missing
+    #[account(
+        mut,
+        address = anchor_spl::associated_token::get_associated_token_address(&authority.key(), &mint.key())
+            @ MintNftError::InvalidAssociatedTokenAccount
+    )]
+    pub associated_token: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// CHECK: Wallet owning the associated token account, does not need to sign
+    pub authority: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub mint: Signer<'info>,
+    pub mint: Account<'info, Mint>,
 
     pub system_program: Program<'info, System>,
 
-    /// CHECK: Token program ID of Associated token program
-    pub associated_token_program: AccountInfo<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
@@ -63,29 +93,36 @@ pub struct MintToContext<'info> {
     pub payer: Signer<'info>,
 
     #[account(mut)]
-    pub mint: Signer<'info>,
+    pub mint: Account<'info, Mint>,
 
-    /// CHECK: Associated token account of user
-    #[account(mut)]
-    pub token_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = mint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
 
     #[account(mut)]
     pub authority: Signer<'info>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+
+    pub fee: FeeAccounts<'info>,
 }
 
 #[derive(Accounts)]
 pub struct CreateMetadataAccountsContext<'info> {
 
-    /// CHECK: Metadata account
-    #[account(mut)]
-    pub metadata_account: AccountInfo<'info>,
+    /// CHECK: Metadata of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
 
-    /// CHECK: Mint account according to user
     #[account(mut)]
-    pub mint: AccountInfo<'info>,
+    pub mint: Account<'info, Mint>,
 
     #[account(mut)]
     pub mint_authority: Signer<'info>,
@@ -100,25 +137,33 @@ pub struct CreateMetadataAccountsContext<'info> {
 
     pub rent: Sysvar<'info, Rent>,
 
-    /// CHECK: Metaplex will check this
-    pub token_metadata_program: UncheckedAccount<'info>,
+    pub token_metadata_program: Program<'info, TokenMetadata>,
 
 }
 
 #[derive(Accounts)]
 pub struct CreateMasterEditionAccountContext<'info> {
     
-    /// CHECK: Master edition account
-    #[account(mut)]
-    pub master_edition_account: AccountInfo<'info>,
+    /// CHECK: Master edition of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
 
-    /// CHECK: Metadata account
-    #[account(mut)]
-    pub metadata_account: AccountInfo<'info>,
+    /// CHECK: Metadata of `mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
 
-    /// CHECK: Mint account according to user
     #[account(mut)]
-    pub mint: AccountInfo<'info>,
+    pub mint: Account<'info, Mint>,
 
     #[account(mut)]
     pub mint_authority: Signer<'info>,
@@ -133,38 +178,63 @@ pub struct CreateMasterEditionAccountContext<'info> {
 
     pub rent: Sysvar<'info, Rent>,
 
-    /// CHECK: Metaplex will check this
-    pub token_metadata_program: UncheckedAccount<'info>,
+    pub token_metadata_program: Program<'info, TokenMetadata>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+
+    pub fee: FeeAccounts<'info>,
 }
 
 #[derive(Accounts)]
+#[instruction(edition: u64)]
 pub struct CreatePrintEditionContext<'info> {
 
-    /// CHECK: Metadata account of edition (pda of ['metadata', program id, mint id])
-    #[account(mut)]
-    pub edition_metadata_account: AccountInfo<'info>,
+    /// CHECK: Metadata of `edition_mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_metadata_account: UncheckedAccount<'info>,
 
-    /// CHECK: Edition account (pda of ['metadata', program id, mint id, 'edition'])
-    #[account(mut)]
-    pub edition_account: AccountInfo<'info>,
+    /// CHECK: Print edition of `edition_mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
 
-    /// CHECK: Master edition account (pda of ['metadata', program id, mint id, 'edition'])
-    #[account(mut)]
-    pub master_edition_account: AccountInfo<'info>,
+    /// CHECK: Master edition of `metadata_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), metadata_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
 
-    /// CHECK: Mint of new edition token 
+    /// Mint of the new print, with one token already minted
     #[account(mut)]
-    pub edition_mint: AccountInfo<'info>,
+    pub edition_mint: Account<'info, Mint>,
 
-    /// CHECK: Edition pda to mark creation - will be checked for pre-existence.
-    /// (pda of ['metadata', program id, master metadata mint id, 'edition', edition_number])
-    /// edition_number is NOT the edition number you pass in args but actually 
-    /// edition_number = floor(edition/EDITION_MARKER_BIT_SIZE).
-    #[account(mut)]
-    pub edition_mark_pda: AccountInfo<'info>,
+    /// CHECK: Edition marker of the master edition covering `edition`
+    /// (pda of ['metadata', program id, master mint, 'edition', floor(edition / EDITION_MARKER_BIT_SIZE)])
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            mpl_token_metadata::ID.as_ref(),
+            metadata_mint.key().as_ref(),
+            EDITION.as_bytes(),
+            (edition / EDITION_MARKER_BIT_SIZE).to_string().as_bytes()
+        ],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_mark_pda: UncheckedAccount<'info>,
 
     /// CHECK: Mint authority of new edition mint
     #[account(mut)]
@@ -177,117 +247,164 @@ pub struct CreatePrintEditionContext<'info> {
     #[account(mut)]
     pub token_account_owner: Signer<'info>,
 
-    /// CHECK: Token account containing token from master metadata mint
-    #[account(mut)]
-    pub token_account: AccountInfo<'info>,
+    /// Token account holding the master edition token
+    #[account(
+        mut,
+        token::mint = metadata_mint,
+        token::authority = token_account_owner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
 
     /// CHECK: Update authority of edition account
     #[account(mut)]
     pub edition_update_authority: Signer<'info>,
 
-    /// CHECK: Master record metadata account
-    #[account(mut)]
-    pub metadata_account: AccountInfo<'info>,
+    /// CHECK: Metadata of `metadata_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), metadata_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
 
-    /// CHECK: I don't know what this is
+    /// Mint of the master edition printed from
     #[account(mut)]
-    pub metadata_mint: AccountInfo<'info>,
+    pub metadata_mint: Account<'info, Mint>,
 
-    /// CHECK: Metaplex will check this
-    pub token_metadata_program: UncheckedAccount<'info>,
+    pub token_metadata_program: Program<'info, TokenMetadata>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
 
     pub system_program: Program<'info, System>,
     
     pub rent: Sysvar<'info, Rent>,
+
+    pub fee: FeeAccounts<'info>,
 }
 
 #[derive(Accounts)]
 pub struct BurnEditionContext<'info> {
 
-    /// CHECK: Metadata account of edition (pda of ['metadata', program id, mint id])
-    #[account(mut)]
-    pub edition_metadata_account: AccountInfo<'info>,
+    /// CHECK: Metadata of `edition_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_metadata_account: UncheckedAccount<'info>,
 
     #[account(mut)]
     pub nft_owner: Signer<'info>,
 
-    /// CHECK: Mint of new edition token 
     #[account(mut)]
-    pub edition_mint: AccountInfo<'info>,
+    pub edition_mint: Account<'info, Mint>,
 
-    /// CHECK: Mint of master edition token
     #[account(mut)]
-    pub master_edition_mint: AccountInfo<'info>,
+    pub master_edition_mint: Account<'info, Mint>,
 
-    /// CHECK: Token account of edition mint token
-    #[account(mut)]
-    pub edition_token_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = edition_mint,
+        token::authority = nft_owner
+    )]
+    pub edition_token_account: Account<'info, TokenAccount>,
 
-    /// CHECK: Token account of master edition mint token
-    #[account(mut)]
-    pub master_edition_token_account: AccountInfo<'info>,
+    /// Any token account of the master edition mint
+    #[account(
+        mut,
+        token::mint = master_edition_mint
+    )]
+    pub master_edition_token_account: Account<'info, TokenAccount>,
 
-    /// CHECK: Master edition account (pda of ['metadata', program id, mint id, 'edition'])
-    #[account(mut)]
-    pub master_edition_account: AccountInfo<'info>,
+    /// CHECK: Master edition of `master_edition_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
 
-    /// CHECK: Edition account (pda of ['metadata', program id, mint id, 'edition'])
-    #[account(mut)]
-    pub edition_account: AccountInfo<'info>,
+    /// CHECK: Print edition of `edition_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
 
-    /// CHECK: Edition pda to mark creation - will be checked for pre-existence.
-    /// (pda of ['metadata', program id, master metadata mint id, 'edition', edition_number])
-    /// edition_number is NOT the edition number you pass in args but actually 
-    /// edition_number = floor(edition/EDITION_MARKER_BIT_SIZE).
+    /// CHECK: Edition marker holding the print's number, which only the print edition
+    /// account records; checked by Metaplex
     #[account(mut)]
-    pub edition_mark_pda: AccountInfo<'info>,
+    pub edition_mark_pda: UncheckedAccount<'info>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
 
-    /// CHECK: Metaplex will check this
-    pub token_metadata_program: UncheckedAccount<'info>,
-    
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    /// CHECK: Receives the rent reclaimed by the burn instead of `nft_owner`
+    #[account(mut)]
+    pub rent_recipient: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct BurnMasterEditionContext<'info> {
 
-    /// CHECK: Metadata account of master edition 
-    #[account(mut)]
-    pub master_edition_metadata: AccountInfo<'info>,
+    /// CHECK: Metadata of `master_edition_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_edition_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_metadata: UncheckedAccount<'info>,
 
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    /// CHECK: Mint account (ERC20) of master edition
     #[account(mut)]
-    pub master_edition_mint: AccountInfo<'info>,
+    pub master_edition_mint: Account<'info, Mint>,
 
-    /// CHECK: Associated token account to store master edition
-    #[account(mut)]
-    pub master_edition_token_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = master_edition_mint,
+        token::authority = owner
+    )]
+    pub master_edition_token_account: Account<'info, TokenAccount>,
 
-    /// CHECK: Master edition account (pda of ['metadata', program id, mint id, 'edition'])
-    #[account(mut)]
-    pub master_edition_account: AccountInfo<'info>,
+    /// CHECK: Master edition of `master_edition_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_metadata_program: Program<'info, TokenMetadata>,
 
-    /// CHECK: Metaplex will check this
-    pub token_metadata_program: UncheckedAccount<'info>,
+    /// CHECK: Receives the rent reclaimed by the burn instead of `owner`
+    #[account(mut)]
+    pub rent_recipient: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct DelegateNftContext<'info> {
 
-    /// CHECK: NFT associated token account
-    #[account(mut)]
-    pub source_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::authority = signer
+    )]
+    pub source_account: Account<'info, TokenAccount>,
 
     /// CHECK: Delegate account can get NFT from your account
     #[account(
@@ -300,21 +417,21 @@ pub struct DelegateNftContext<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
 
 }
 
 #[derive(Accounts)]
 pub struct TransferFromDelegateAccountContext<'info> {
 
-    /// CHECK: NFT associated token account
     #[account(mut)]
-    pub source_account: AccountInfo<'info>,
+    pub source_account: Account<'info, TokenAccount>,
 
-    /// CHECK: Destination account
-    #[account(mut)]
-    pub destination_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = source_account.mint
+    )]
+    pub destination_account: Account<'info, TokenAccount>,
 
     /// CHECK: Delegate account can get NFT from your account
     #[account(
@@ -324,7 +441,3195 @@ pub struct TransferFromDelegateAccountContext<'info> {
     )]
     pub delegate_account: UncheckedAccount<'info>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+
+}
+
+#[derive(Accounts)]
+#[instruction(members: Vec<Pubkey>)]
+pub struct CreateMultisigContext<'info> {
+
+    #[account(
+        init,
+        payer = payer,
+        space = Multisig::space(members.len()),
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: PDA acting as mint/update authority for everything the multisig controls
+    #[account(
+        seeds = [MULTISIG_SIGNER_SEED, multisig.key().as_ref()],
+        bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(action: MultisigAction)]
+pub struct CreateMultisigProposalContext<'info> {
+
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = MultisigProposal::space(&action, multisig.members.len()),
+        seeds = [MULTISIG_PROPOSAL_SEED, multisig.key().as_ref(), &multisig.proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+
+    #[account(
+        mut,
+        constraint = multisig.member_index(proposer.key).is_some() @ MintNftError::NotMultisigMember
+    )]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveMultisigProposalContext<'info> {
+
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = !proposal.executed @ MintNftError::ProposalAlreadyExecuted
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+
+    #[account(
+        constraint = multisig.member_index(member.key).is_some() @ MintNftError::NotMultisigMember
+    )]
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCreateMetadataProposalContext<'info> {
+
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = !proposal.executed @ MintNftError::ProposalAlreadyExecuted
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+
+    /// CHECK: Mint and update authority of the new metadata
+    #[account(
+        seeds = [MULTISIG_SIGNER_SEED, multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// Mint named in the proposal
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCreateMasterEditionProposalContext<'info> {
+
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = !proposal.executed @ MintNftError::ProposalAlreadyExecuted
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+
+    /// CHECK: Mint and update authority of the master edition
+    #[account(
+        seeds = [MULTISIG_SIGNER_SEED, multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Master edition of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata of `mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// Mint named in the proposal
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMintTokenProposalContext<'info> {
+
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = !proposal.executed @ MintNftError::ProposalAlreadyExecuted
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+
+    /// CHECK: Mint authority of `mint`
+    #[account(
+        seeds = [MULTISIG_SIGNER_SEED, multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    /// Mint named in the proposal
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    /// Token account named in the proposal
+    #[account(
+        mut,
+        token::mint = mint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteUpdateMetadataProposalContext<'info> {
+
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = !proposal.executed @ MintNftError::ProposalAlreadyExecuted
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
 
-}
\ No newline at end of file
+    /// CHECK: Update authority of the metadata
+    #[account(
+        seeds = [MULTISIG_SIGNER_SEED, multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata of the mint named in the proposal, checked against it
+    #[account(mut)]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+}
+
+/// Accounts charged a protocol fee. Nested into every context that collects one.
+#[derive(Accounts)]
+pub struct FeeAccounts<'info> {
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Payer's token account, required when fees are charged in an SPL token
+    #[account(mut)]
+    pub payer_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Treasury's token account, required when fees are charged in an SPL token
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Optional referrer receiving the referral share of a lamport fee
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    /// Optional referrer token account receiving the referral share of an SPL fee
+    #[account(mut)]
+    pub referrer_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasuryContext<'info> {
+
+    #[account(
+        init,
+        payer = admin,
+        space = Treasury::LEN,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::MintNft>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ MintNftError::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTreasuryContext<'info> {
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump,
+        has_one = admin
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryLamportsContext<'info> {
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump,
+        has_one = admin
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub admin: Signer<'info>,
+
+    /// CHECK: Any account chosen by the admin to receive the lamports
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryTokensContext<'info> {
+
+    #[account(
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump,
+        has_one = admin
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = treasury_token_account.owner == treasury.key() @ MintNftError::InvalidFeeAccounts
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ListNftContext<'info> {
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = Listing::LEN,
+        seeds = [LISTING_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        init,
+        payer = seller,
+        seeds = [LISTING_ESCROW_SEED, nft_mint.key().as_ref()],
+        bump,
+        token::mint = nft_mint,
+        token::authority = listing
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct BuyNftContext<'info> {
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Seller recorded in the listing, receives proceeds and rent
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, nft_mint.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        has_one = nft_mint,
+        close = seller
+    )]
+    pub listing: Account<'info, Listing>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_ESCROW_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`, read for its creators
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// Buyer's token account of the price mint, for SPL listings
+    #[account(mut)]
+    pub buyer_payment_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Seller's token account of the price mint, for SPL listings
+    #[account(mut)]
+    pub seller_payment_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelListingContext<'info> {
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, nft_mint.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller,
+        has_one = nft_mint,
+        close = seller
+    )]
+    pub listing: Account<'info, Listing>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_ESCROW_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(target: BidTarget)]
+pub struct PlaceBidContext<'info> {
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = Bid::LEN,
+        seeds = [BID_SEED, bidder.key().as_ref(), target.kind_seed().as_ref(), target.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelBidContext<'info> {
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = bidder,
+        close = bidder
+    )]
+    pub bid: Account<'info, Bid>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredBidContext<'info> {
+
+    /// CHECK: Bidder recorded in the bid, refunded on close
+    #[account(mut)]
+    pub bidder: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = bidder,
+        close = bidder
+    )]
+    pub bid: Account<'info, Bid>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptBidContext<'info> {
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Bidder recorded in the bid, refunded the remainder on close
+    #[account(mut)]
+    pub bidder: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = bidder,
+        close = bidder
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = bidder
+    )]
+    pub bidder_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`, read for its creators and collection
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAuctionContext<'info> {
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Edition of `nft_mint`. Prints share this PDA, so the key byte must mark a master edition
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump,
+        owner = mpl_token_metadata::ID,
+        constraint = master_edition_account.try_borrow_data()?.first() == Some(&(mpl_token_metadata::state::Key::MasterEditionV2 as u8)) @ MintNftError::NotMasterEdition
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = Auction::LEN,
+        seeds = [AUCTION_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        init,
+        payer = seller,
+        seeds = [AUCTION_ESCROW_SEED, nft_mint.key().as_ref()],
+        bump,
+        token::mint = nft_mint,
+        token::authority = auction
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceAuctionBidContext<'info> {
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.nft_mint.as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// CHECK: Current highest bidder, refunded when outbid. Must match the auction
+    #[account(mut)]
+    pub previous_bidder: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleAuctionContext<'info> {
+
+    /// CHECK: Seller recorded in the auction, receives proceeds and rent
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, nft_mint.key().as_ref()],
+        bump = auction.bump,
+        has_one = seller,
+        has_one = nft_mint,
+        close = seller
+    )]
+    pub auction: Account<'info, Auction>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_ESCROW_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    /// Token account of the winner, or of the seller when there were no bids
+    #[account(
+        mut,
+        token::mint = nft_mint
+    )]
+    pub destination_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`, read for its creators
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BuyDutchAuctionContext<'info> {
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Seller recorded in the auction, receives proceeds and rent
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, nft_mint.key().as_ref()],
+        bump = auction.bump,
+        has_one = seller,
+        has_one = nft_mint,
+        close = seller
+    )]
+    pub auction: Account<'info, Auction>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_ESCROW_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`, read for its creators
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuctionContext<'info> {
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, nft_mint.key().as_ref()],
+        bump = auction.bump,
+        has_one = seller,
+        has_one = nft_mint,
+        close = seller
+    )]
+    pub auction: Account<'info, Auction>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_ESCROW_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateSwapOfferContext<'info> {
+
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = SwapOffer::LEN,
+        seeds = [SWAP_SEED, maker.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, SwapOffer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositSwapItemContext<'info> {
+
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker
+    )]
+    pub offer: Account<'info, SwapOffer>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = maker
+    )]
+    pub maker_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = maker,
+        seeds = [SWAP_ESCROW_SEED, offer.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = offer
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptSwapContext<'info> {
+
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// CHECK: Maker recorded in the offer, receives requested items and rent
+    #[account(mut)]
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        close = maker
+    )]
+    pub offer: Account<'info, SwapOffer>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSwapOfferContext<'info> {
+
+    /// Maker, or anyone once the offer has expired
+    pub authority: Signer<'info>,
+
+    /// CHECK: Maker recorded in the offer, refunded on close
+    #[account(mut)]
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        close = maker
+    )]
+    pub offer: Account<'info, SwapOffer>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ListRentalContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Rental::LEN,
+        seeds = [RENTAL_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub rental: Account<'info, Rental>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RentNftContext<'info> {
+
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RENTAL_SEED, nft_mint.key().as_ref()],
+        bump = rental.bump,
+        has_one = nft_mint,
+        has_one = owner_token_account
+    )]
+    pub rental: Account<'info, Rental>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = renter
+    )]
+    pub renter_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Edition of `nft_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EndRentalContext<'info> {
+
+    /// CHECK: Owner recorded in the rental, receives the NFT, payment and rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [RENTAL_SEED, nft_mint.key().as_ref()],
+        bump = rental.bump,
+        has_one = owner,
+        has_one = nft_mint,
+        has_one = owner_token_account,
+        has_one = renter_token_account,
+        close = owner
+    )]
+    pub rental: Account<'info, Rental>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub renter_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Edition of `nft_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRentalContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RENTAL_SEED, rental.nft_mint.as_ref()],
+        bump = rental.bump,
+        has_one = owner,
+        has_one = owner_token_account,
+        constraint = rental.renter.is_none() @ MintNftError::AlreadyRented,
+        close = owner
+    )]
+    pub rental: Account<'info, Rental>,
+
+    #[account(mut)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateStakePoolContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = StakePool::LEN,
+        seeds = [STAKE_POOL_SEED, collection_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, StakePool>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [STAKE_VAULT_SEED, pool.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = pool
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetStakeRewardRateContext<'info> {
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub pool: Account<'info, StakePool>,
+}
+
+#[derive(Accounts)]
+pub struct FundStakePoolContext<'info> {
+
+    pub funder: Signer<'info>,
+
+    #[account(has_one = reward_vault)]
+    pub pool: Account<'info, StakePool>,
+
+    #[account(mut)]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStakeRewardsContext<'info> {
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_POOL_SEED, pool.collection_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority,
+        has_one = reward_vault
+    )]
+    pub pool: Account<'info, StakePool>,
+
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = pool.reward_mint
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct StakeNftContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_POOL_SEED, pool.collection_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, StakePool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = StakeEntry::LEN,
+        seeds = [STAKE_ENTRY_SEED, pool.key().as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub stake_entry: Account<'info, StakeEntry>,
+
+    #[account(
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner
+    )]
+    pub nft_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`, read for its collection
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Edition of `nft_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakeRewardsContext<'info> {
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_POOL_SEED, pool.collection_mint.as_ref()],
+        bump = pool.bump,
+        has_one = reward_vault
+    )]
+    pub pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        has_one = pool,
+        has_one = owner
+    )]
+    pub stake_entry: Account<'info, StakeEntry>,
+
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = pool.reward_mint
+    )]
+    pub owner_reward_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeNftContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_POOL_SEED, pool.collection_mint.as_ref()],
+        bump = pool.bump,
+        has_one = reward_vault
+    )]
+    pub pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        has_one = pool,
+        has_one = owner,
+        has_one = nft_mint,
+        constraint = stake_entry.token_account == nft_token_account.key() @ MintNftError::InvalidDestinationAccount,
+        close = owner
+    )]
+    pub stake_entry: Account<'info, StakeEntry>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub nft_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = pool.reward_mint
+    )]
+    pub owner_reward_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Edition of `nft_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(share_supply: u64, share_decimals: u8)]
+pub struct FractionalizeNftContext<'info> {
+
+    #[account(mut)]
+    pub curator: Signer<'info>,
+
+    #[account(
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Only master editions can be fractionalized
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump,
+        owner = mpl_token_metadata::ID
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = curator
+    )]
+    pub curator_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = curator,
+        space = FractionVault::LEN,
+        seeds = [FRACTION_VAULT_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, FractionVault>,
+
+    #[account(
+        init,
+        payer = curator,
+        seeds = [FRACTION_ESCROW_SEED, nft_mint.key().as_ref()],
+        bump,
+        token::mint = nft_mint,
+        token::authority = vault
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = curator,
+        seeds = [FRACTION_SHARE_MINT_SEED, vault.key().as_ref()],
+        bump,
+        mint::decimals = share_decimals,
+        mint::authority = vault
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = curator,
+        associated_token::mint = share_mint,
+        associated_token::authority = curator
+    )]
+    pub curator_share_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct BuyoutFractionVaultContext<'info> {
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Curator recorded in the vault, receives the escrow rent
+    #[account(mut)]
+    pub curator: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [FRACTION_VAULT_SEED, vault.nft_mint.as_ref()],
+        bump = vault.bump,
+        has_one = curator
+    )]
+    pub vault: Account<'info, FractionVault>,
+
+    #[account(
+        mut,
+        seeds = [FRACTION_ESCROW_SEED, vault.nft_mint.as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = vault.nft_mint
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemFractionSharesContext<'info> {
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [FRACTION_VAULT_SEED, vault.nft_mint.as_ref()],
+        bump = vault.bump,
+        has_one = share_mint
+    )]
+    pub vault: Account<'info, FractionVault>,
+
+    #[account(mut)]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = holder
+    )]
+    pub holder_share_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateLoanOfferContext<'info> {
+
+    #[account(mut)]
+    pub lender: Signer<'info>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = lender,
+        space = Loan::LEN,
+        seeds = [LOAN_SEED, lender.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub loan: Account<'info, Loan>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLoanOfferContext<'info> {
+
+    #[account(mut)]
+    pub lender: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LOAN_SEED, lender.key().as_ref(), &loan.id.to_le_bytes()],
+        bump = loan.bump,
+        has_one = lender,
+        constraint = loan.borrower.is_none() @ MintNftError::LoanAlreadyTaken,
+        close = lender
+    )]
+    pub loan: Account<'info, Loan>,
+}
+
+#[derive(Accounts)]
+pub struct TakeLoanContext<'info> {
+
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LOAN_SEED, loan.lender.as_ref(), &loan.id.to_le_bytes()],
+        bump = loan.bump
+    )]
+    pub loan: Account<'info, Loan>,
+
+    #[account(
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = borrower
+    )]
+    pub borrower_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`, read for its collection
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Edition of `nft_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RepayLoanContext<'info> {
+
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    /// CHECK: Lender recorded in the loan, receives the repayment and rent
+    #[account(mut)]
+    pub lender: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [LOAN_SEED, lender.key().as_ref(), &loan.id.to_le_bytes()],
+        bump = loan.bump,
+        has_one = lender,
+        has_one = nft_mint,
+        constraint = loan.borrower == Some(borrower.key()) @ MintNftError::LoanNotTaken,
+        constraint = loan.collateral_token_account == borrower_token_account.key() @ MintNftError::InvalidDestinationAccount,
+        close = lender
+    )]
+    pub loan: Account<'info, Loan>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub borrower_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Edition of `nft_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ForecloseLoanContext<'info> {
+
+    #[account(mut)]
+    pub lender: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LOAN_SEED, lender.key().as_ref(), &loan.id.to_le_bytes()],
+        bump = loan.bump,
+        has_one = lender,
+        has_one = nft_mint,
+        constraint = loan.collateral_token_account == borrower_token_account.key() @ MintNftError::InvalidDestinationAccount,
+        close = lender
+    )]
+    pub loan: Account<'info, Loan>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub borrower_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = lender
+    )]
+    pub lender_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Edition of `nft_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSoulboundMintContext<'info> {
+
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    #[account(mut)]
+    pub mint: Signer<'info>,
+
+    #[account(
+        init,
+        payer = issuer,
+        space = SoulboundMint::LEN,
+        seeds = [SOULBOUND_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub soulbound: Account<'info, SoulboundMint>,
+
+    /// CHECK: SPL Token or Token-2022
+    #[account(
+        constraint = token_program.key() == anchor_spl::token::ID
+            || token_program.key() == spl_token_2022::ID @ MintNftError::InvalidTokenProgram
+    )]
+    pub token_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintSoulboundTokenContext<'info> {
+
+    pub issuer: Signer<'info>,
+
+    pub recipient: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SOULBOUND_SEED, mint.key().as_ref()],
+        bump = soulbound.bump,
+        has_one = issuer,
+        has_one = mint,
+        has_one = token_program,
+        constraint = soulbound.holder.is_none() @ MintNftError::SoulboundAlreadyMinted
+    )]
+    pub soulbound: Account<'info, SoulboundMint>,
+
+    /// CHECK: Mint recorded in `soulbound`, owned by `token_program`
+    #[account(mut)]
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: Token account of `mint` owned by `recipient`, unpacked by the handler
+    #[account(
+        mut,
+        owner = token_program.key()
+    )]
+    pub recipient_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Token program recorded in `soulbound`
+    pub token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSoulboundTokenContext<'info> {
+
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SOULBOUND_SEED, mint.key().as_ref()],
+        bump = soulbound.bump,
+        has_one = issuer,
+        has_one = mint,
+        has_one = token_account,
+        has_one = token_program,
+        close = issuer
+    )]
+    pub soulbound: Account<'info, SoulboundMint>,
+
+    /// CHECK: Mint recorded in `soulbound`
+    #[account(mut)]
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: Holder token account recorded in `soulbound`
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Token program recorded in `soulbound`
+    pub token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UseNftContext<'info> {
+
+    #[account(mut)]
+    pub use_authority: Signer<'info>,
+
+    /// CHECK: Owner of `token_account`
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex use authority record, required when `use_authority` is not the owner
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_use_authority_account(&nft_mint.key(), &use_authority.key()).0
+    )]
+    pub use_authority_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex program-as-burner PDA, required with a use authority record on Burn NFTs
+    #[account(address = mpl_token_metadata::pda::find_program_as_burner_account().0)]
+    pub burner: Option<UncheckedAccount<'info>>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveNftUseAuthorityContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Wallet allowed to use the NFT
+    pub user: UncheckedAccount<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex use authority record, created by Metaplex
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_use_authority_account(&nft_mint.key(), &user.key()).0
+    )]
+    pub use_authority_record: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex program-as-burner PDA
+    #[account(address = mpl_token_metadata::pda::find_program_as_burner_account().0)]
+    pub burner: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeNftUseAuthorityContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Wallet whose use authority is revoked
+    pub user: UncheckedAccount<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex use authority record, closed by Metaplex
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_use_authority_account(&nft_mint.key(), &user.key()).0
+    )]
+    pub use_authority_record: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateTicketEventContext<'info> {
+
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = TicketEvent::LEN,
+        seeds = [TICKET_EVENT_SEED, organizer.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub event: Account<'info, TicketEvent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTicketEventContext<'info> {
+
+    pub organizer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [TICKET_EVENT_SEED, organizer.key().as_ref(), &event.id.to_le_bytes()],
+        bump = event.bump,
+        has_one = organizer
+    )]
+    pub event: Account<'info, TicketEvent>,
+}
+
+#[derive(Accounts)]
+pub struct AddTicketTierContext<'info> {
+
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [TICKET_EVENT_SEED, organizer.key().as_ref(), &event.id.to_le_bytes()],
+        bump = event.bump,
+        has_one = organizer
+    )]
+    pub event: Account<'info, TicketEvent>,
+
+    #[account(
+        constraint = master_mint.supply == 1 && master_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub master_mint: Account<'info, Mint>,
+
+    /// CHECK: Tiers must be backed by a master edition
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump,
+        owner = mpl_token_metadata::ID
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = master_mint,
+        token::authority = organizer
+    )]
+    pub organizer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = organizer,
+        seeds = [TICKET_TIER_VAULT_SEED, event.key().as_ref(), master_mint.key().as_ref()],
+        bump,
+        token::mint = master_mint,
+        token::authority = event
+    )]
+    pub tier_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTicketTierContext<'info> {
+
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        seeds = [TICKET_EVENT_SEED, organizer.key().as_ref(), &event.id.to_le_bytes()],
+        bump = event.bump,
+        has_one = organizer
+    )]
+    pub event: Account<'info, TicketEvent>,
+
+    pub master_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = master_mint,
+        token::authority = organizer
+    )]
+    pub organizer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [TICKET_TIER_VAULT_SEED, event.key().as_ref(), master_mint.key().as_ref()],
+        bump
+    )]
+    pub tier_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(tier: u8, edition: u64)]
+pub struct PurchaseTicketContext<'info> {
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Organizer recorded in the event, receives the ticket price
+    #[account(mut)]
+    pub organizer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [TICKET_EVENT_SEED, organizer.key().as_ref(), &event.id.to_le_bytes()],
+        bump = event.bump,
+        has_one = organizer
+    )]
+    pub event: Account<'info, TicketEvent>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = Ticket::LEN,
+        seeds = [TICKET_SEED, edition_mint.key().as_ref()],
+        bump
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    /// CHECK: Metadata of the new print, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Edition of the new print, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Edition marker of the master edition covering `edition`
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            mpl_token_metadata::ID.as_ref(),
+            master_mint.key().as_ref(),
+            EDITION.as_bytes(),
+            (edition / EDITION_MARKER_BIT_SIZE).to_string().as_bytes()
+        ],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_mark_pda: UncheckedAccount<'info>,
+
+    /// Fresh mint with one token already minted to the buyer and the buyer as mint authority
+    #[account(mut)]
+    pub edition_mint: Account<'info, Mint>,
+
+    pub master_mint: Account<'info, Mint>,
+
+    /// CHECK: Master edition of `master_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata of `master_mint`
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_metadata_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [TICKET_TIER_VAULT_SEED, event.key().as_ref(), master_mint.key().as_ref()],
+        bump
+    )]
+    pub tier_vault: Account<'info, TokenAccount>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CheckInTicketContext<'info> {
+
+    pub gate_staff: Signer<'info>,
+
+    pub holder: Signer<'info>,
+
+    #[account(
+        seeds = [TICKET_EVENT_SEED, event.organizer.as_ref(), &event.id.to_le_bytes()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, TicketEvent>,
+
+    #[account(
+        mut,
+        seeds = [TICKET_SEED, edition_mint.key().as_ref()],
+        bump = ticket.bump,
+        has_one = event,
+        has_one = edition_mint
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    pub edition_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = edition_mint,
+        token::authority = holder,
+        constraint = holder_token_account.amount == 1 @ MintNftError::InvalidDestinationAccount
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Print edition of `edition_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseTicketContext<'info> {
+
+    pub holder: Signer<'info>,
+
+    #[account(
+        seeds = [TICKET_EVENT_SEED, event.organizer.as_ref(), &event.id.to_le_bytes()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, TicketEvent>,
+
+    #[account(
+        mut,
+        seeds = [TICKET_SEED, edition_mint.key().as_ref()],
+        bump = ticket.bump,
+        has_one = event,
+        has_one = edition_mint,
+        constraint = ticket.frozen_token_account == Some(holder_token_account.key()) @ MintNftError::InvalidDestinationAccount
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    pub edition_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Print edition of `edition_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMembershipContext<'info> {
+
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    /// Co-signs so the membership PDA can be approved as delegate for auto-freeze
+    pub holder: Signer<'info>,
+
+    #[account(
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of `nft_mint`, read for its update authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = issuer,
+        space = Membership::LEN,
+        seeds = [MEMBERSHIP_SEED, nft_mint.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, Membership>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RenewMembershipContext<'info> {
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Issuer recorded in the membership, receives the renewal price
+    #[account(mut)]
+    pub issuer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [MEMBERSHIP_SEED, membership.mint.as_ref()],
+        bump = membership.bump,
+        has_one = issuer
+    )]
+    pub membership: Account<'info, Membership>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct IsMembershipActiveContext<'info> {
+
+    #[account(
+        seeds = [MEMBERSHIP_SEED, membership.mint.as_ref()],
+        bump = membership.bump
+    )]
+    pub membership: Account<'info, Membership>,
+}
+
+#[derive(Accounts)]
+pub struct ToggleMembershipFreezeContext<'info> {
+
+    #[account(
+        mut,
+        seeds = [MEMBERSHIP_SEED, nft_mint.key().as_ref()],
+        bump = membership.bump,
+        constraint = membership.mint == nft_mint.key() @ MintNftError::InvalidDestinationAccount
+    )]
+    pub membership: Account<'info, Membership>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// Token account currently holding the NFT, wherever it has moved since creation
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        constraint = token_account.amount == 1 @ MintNftError::NotAnNft
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Edition of `nft_mint`, which is its freeze authority
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), nft_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u64)]
+pub struct InitializeVoucherNoncePageContext<'info> {
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Voucher creator the page tracks nonces for
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = VoucherNoncePage::LEN,
+        seeds = [VOUCHER_NONCE_SEED, creator.key().as_ref(), &page.to_le_bytes()],
+        bump
+    )]
+    pub nonce_page: Account<'info, VoucherNoncePage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(voucher: MintVoucher)]
+pub struct RedeemMintVoucherContext<'info> {
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Voucher creator, receives the price and the update authority
+    #[account(
+        mut,
+        address = voucher.creator
+    )]
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VOUCHER_NONCE_SEED, creator.key().as_ref(), &VoucherNoncePage::page_of(voucher.nonce).to_le_bytes()],
+        bump = nonce_page.bump
+    )]
+    pub nonce_page: Account<'info, VoucherNoncePage>,
+
+    /// CHECK: PDA acting as update authority until the master edition exists
+    #[account(
+        seeds = [LAZY_MINT_AUTHORITY_SEED, creator.key().as_ref()],
+        bump
+    )]
+    pub lazy_mint_authority: UncheckedAccount<'info>,
+
+    /// Fresh mint with one token minted to the buyer and the buyer as mint authority
+    #[account(
+        mut,
+        constraint = mint.supply == 1 && mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Metadata of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Master edition of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, read to find the ed25519 signature check
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateEthDropContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = EthDrop::LEN,
+        seeds = [ETH_DROP_SEED, authority.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub drop: Account<'info, EthDrop>,
+
+    #[account(
+        constraint = master_mint.supply == 1 && master_mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub master_mint: Account<'info, Mint>,
+
+    /// CHECK: Drops must be backed by a master edition
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump,
+        owner = mpl_token_metadata::ID
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = master_mint,
+        token::authority = authority
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [ETH_DROP_VAULT_SEED, drop.key().as_ref()],
+        bump,
+        token::mint = master_mint,
+        token::authority = drop
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(eth_address: [u8; 20], edition: u64)]
+pub struct ClaimEthDropContext<'info> {
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Solana wallet named in the signed message, receives the print
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Drop authority, set as update authority of the print
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ETH_DROP_SEED, authority.key().as_ref(), &drop.id.to_le_bytes()],
+        bump = drop.bump,
+        has_one = authority,
+        has_one = master_mint
+    )]
+    pub drop: Account<'info, EthDrop>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = EthClaim::LEN,
+        seeds = [ETH_CLAIM_SEED, drop.key().as_ref(), eth_address.as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, EthClaim>,
+
+    /// CHECK: Metadata of the new print, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Edition of the new print, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Edition marker of the master edition covering `edition`
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            mpl_token_metadata::ID.as_ref(),
+            master_mint.key().as_ref(),
+            EDITION.as_bytes(),
+            (edition / EDITION_MARKER_BIT_SIZE).to_string().as_bytes()
+        ],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_mark_pda: UncheckedAccount<'info>,
+
+    /// Fresh mint with one token already minted to the recipient and the payer as mint authority
+    #[account(mut)]
+    pub edition_mint: Account<'info, Mint>,
+
+    #[account(
+        token::mint = edition_mint,
+        token::authority = recipient,
+        constraint = recipient_token_account.amount == 1 @ MintNftError::NotAnNft
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    pub master_mint: Account<'info, Mint>,
+
+    /// CHECK: Master edition of `master_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata of `master_mint`
+    #[account(
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_metadata_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [ETH_DROP_VAULT_SEED, drop.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Instructions sysvar, read to find the secp256k1 signature check
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEthDropContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ETH_DROP_SEED, authority.key().as_ref(), &drop.id.to_le_bytes()],
+        bump = drop.bump,
+        has_one = authority,
+        has_one = master_mint,
+        close = authority
+    )]
+    pub drop: Account<'info, EthDrop>,
+
+    pub master_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [ETH_DROP_VAULT_SEED, drop.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = master_mint,
+        token::authority = authority
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateAirdropDistributorContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AirdropDistributor::LEN,
+        seeds = [AIRDROP_DISTRIBUTOR_SEED, authority.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub distributor: Account<'info, AirdropDistributor>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AirdropClaimBitmap::LEN,
+        seeds = [AIRDROP_BITMAP_SEED, distributor.key().as_ref()],
+        bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, AirdropClaimBitmap>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundAirdropDistributorContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [AIRDROP_DISTRIBUTOR_SEED, authority.key().as_ref(), &distributor.id.to_le_bytes()],
+        bump = distributor.bump,
+        has_one = authority,
+        constraint = distributor.kind == AirdropKind::PrintEdition { master_mint: master_mint.key() }
+            @ MintNftError::InvalidAirdropDistributor
+    )]
+    pub distributor: Account<'info, AirdropDistributor>,
+
+    pub master_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = master_mint,
+        token::authority = authority
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [AIRDROP_VAULT_SEED, distributor.key().as_ref()],
+        bump,
+        token::mint = master_mint,
+        token::authority = distributor
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawAirdropVaultContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [AIRDROP_DISTRIBUTOR_SEED, authority.key().as_ref(), &distributor.id.to_le_bytes()],
+        bump = distributor.bump,
+        has_one = authority,
+        constraint = distributor.kind == AirdropKind::PrintEdition { master_mint: master_mint.key() }
+            @ MintNftError::InvalidAirdropDistributor
+    )]
+    pub distributor: Account<'info, AirdropDistributor>,
+
+    pub master_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = master_mint,
+        token::authority = authority
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [AIRDROP_VAULT_SEED, distributor.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdropContext<'info> {
+
+    /// Anyone may submit a claim; the NFT always goes to the recipient in the leaf
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Recipient named in the leaf
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Distributor authority, set as update authority of claimed NFTs
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [AIRDROP_DISTRIBUTOR_SEED, authority.key().as_ref(), &distributor.id.to_le_bytes()],
+        bump = distributor.bump,
+        has_one = authority
+    )]
+    pub distributor: Account<'info, AirdropDistributor>,
+
+    #[account(
+        mut,
+        seeds = [AIRDROP_BITMAP_SEED, distributor.key().as_ref()],
+        bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, AirdropClaimBitmap>,
+
+    /// Fresh mint with one token already minted to the recipient and the payer as mint authority
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        token::mint = mint,
+        token::authority = recipient,
+        constraint = recipient_token_account.amount == 1 @ MintNftError::NotAnNft
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metadata of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Master or print edition of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Edition marker of the master edition, print distributors only
+    #[account(mut)]
+    pub edition_mark_pda: Option<UncheckedAccount<'info>>,
+
+    pub master_mint: Option<Account<'info, Mint>>,
+
+    /// CHECK: Master edition of `master_mint`, print distributors only
+    #[account(mut)]
+    pub master_edition_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metadata of `master_mint`, print distributors only
+    pub master_metadata_account: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        seeds = [AIRDROP_VAULT_SEED, distributor.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PushAirdropContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [AIRDROP_DISTRIBUTOR_SEED, authority.key().as_ref(), &distributor.id.to_le_bytes()],
+        bump = distributor.bump,
+        has_one = authority
+    )]
+    pub distributor: Account<'info, AirdropDistributor>,
+
+    #[account(
+        mut,
+        seeds = [AIRDROP_BITMAP_SEED, distributor.key().as_ref()],
+        bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, AirdropClaimBitmap>,
+
+    pub master_mint: Option<Account<'info, Mint>>,
+
+    /// CHECK: Master edition of `master_mint`, print distributors only
+    #[account(mut)]
+    pub master_edition_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metadata of `master_mint`, print distributors only
+    pub master_metadata_account: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        seeds = [AIRDROP_VAULT_SEED, distributor.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateDropContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = DropConfig::LEN,
+        seeds = [DROP_CONFIG_SEED, authority.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub drop: Account<'info, DropConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = DropMintBitmap::LEN,
+        seeds = [DROP_BITMAP_SEED, drop.key().as_ref()],
+        bump
+    )]
+    pub mint_bitmap: AccountLoader<'info, DropMintBitmap>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintFromDropContext<'info> {
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Drop authority, receives the price
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [DROP_CONFIG_SEED, authority.key().as_ref(), &drop.id.to_le_bytes()],
+        bump = drop.bump,
+        has_one = authority
+    )]
+    pub drop: Account<'info, DropConfig>,
+
+    #[account(
+        mut,
+        seeds = [DROP_BITMAP_SEED, drop.key().as_ref()],
+        bump
+    )]
+    pub mint_bitmap: AccountLoader<'info, DropMintBitmap>,
+
+    /// Fresh mint with one token minted to the buyer and the buyer as mint authority
+    #[account(
+        mut,
+        constraint = mint.supply == 1 && mint.decimals == 0 @ MintNftError::NotAnNft
+    )]
+    pub mint: Account<'info, Mint>,
+
+    // Keyed by mint since the item index is only picked inside the instruction
+    #[account(
+        init,
+        payer = buyer,
+        space = DropItem::LEN,
+        seeds = [DROP_ITEM_SEED, drop.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub drop_item: Account<'info, DropItem>,
+
+    /// CHECK: Metadata of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Master edition of `mint`, created by Metaplex
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: SlotHashes sysvar, read raw to seed the item pick
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, read for the drop's bot protection
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDropContext<'info> {
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [DROP_CONFIG_SEED, authority.key().as_ref(), &drop.id.to_le_bytes()],
+        bump = drop.bump,
+        has_one = authority
+    )]
+    pub drop: Account<'info, DropConfig>,
+}
+
+#[derive(Accounts)]
+pub struct RevealDropContext<'info> {
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [DROP_CONFIG_SEED, authority.key().as_ref(), &drop.id.to_le_bytes()],
+        bump = drop.bump,
+        has_one = authority
+    )]
+    pub drop: Account<'info, DropConfig>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRedemptionContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Redemption::LEN,
+        seeds = [REDEMPTION_SEED, authority.key().as_ref(), collection_mint.key().as_ref()],
+        bump
+    )]
+    pub redemption: Account<'info, Redemption>,
+
+    /// Reward token mint, or the master edition mint printed from
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = authority
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [REDEMPTION_VAULT_SEED, redemption.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = redemption
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RestockRedemptionContext<'info> {
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_SEED, authority.key().as_ref(), redemption.collection_mint.as_ref()],
+        bump = redemption.bump,
+        has_one = authority
+    )]
+    pub redemption: Account<'info, Redemption>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = authority
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_VAULT_SEED, redemption.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRedemptionContext<'info> {
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_SEED, authority.key().as_ref(), redemption.collection_mint.as_ref()],
+        bump = redemption.bump,
+        has_one = authority,
+        close = authority
+    )]
+    pub redemption: Account<'info, Redemption>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = authority
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_VAULT_SEED, redemption.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRedemptionProgressContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub redemption: Account<'info, Redemption>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = RedemptionProgress::LEN,
+        seeds = [REDEMPTION_PROGRESS_SEED, redemption.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub progress: Account<'info, RedemptionProgress>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRedemptionProgressContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_PROGRESS_SEED, progress.redemption.as_ref(), owner.key().as_ref()],
+        bump = progress.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub progress: Account<'info, RedemptionProgress>,
+}
+
+/// Accounts receiving the reward of a completed redemption. Nested into both
+/// burn-to-redeem contexts; only the burn that completes a redemption needs them.
+#[derive(Accounts)]
+pub struct RedemptionPayoutAccounts<'info> {
+
+    /// Owner's token account of the reward mint, token rewards only
+    #[account(mut)]
+    pub reward_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Fresh mint with one token minted to the owner and the owner as mint authority,
+    /// print rewards only
+    #[account(mut)]
+    pub new_mint: Option<Account<'info, Mint>>,
+
+    /// CHECK: Metadata of `new_mint`, created by Metaplex
+    #[account(mut)]
+    pub new_metadata_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Print edition of `new_mint`, created by Metaplex
+    #[account(mut)]
+    pub new_edition_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Edition marker of the reward master edition, checked by Metaplex
+    #[account(mut)]
+    pub edition_mark_pda: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Master edition of the reward master mint, checked by Metaplex
+    #[account(mut)]
+    pub master_edition_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metadata of the reward master mint, checked by Metaplex
+    pub master_metadata_account: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemEditionBurnContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Redemption authority, update authority of the reward master edition
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_SEED, authority.key().as_ref(), redemption.collection_mint.as_ref()],
+        bump = redemption.bump,
+        has_one = authority
+    )]
+    pub redemption: Account<'info, Redemption>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_PROGRESS_SEED, redemption.key().as_ref(), owner.key().as_ref()],
+        bump = progress.bump
+    )]
+    pub progress: Account<'info, RedemptionProgress>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_VAULT_SEED, redemption.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Metadata of the burned edition, read for its collection
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_metadata_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub edition_mint: Account<'info, Mint>,
+
+    /// Mint of the master edition the burned edition was printed from
+    #[account(mut)]
+    pub master_edition_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = edition_mint,
+        token::authority = owner
+    )]
+    pub edition_token_account: Account<'info, TokenAccount>,
+
+    /// Any token account of the master edition mint
+    #[account(
+        mut,
+        token::mint = master_edition_mint
+    )]
+    pub master_edition_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Master edition of `master_edition_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Print edition of `edition_mint`
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Edition marker holding the burned edition's number, checked by Metaplex
+    #[account(mut)]
+    pub edition_mark_pda: UncheckedAccount<'info>,
+
+    pub payout: RedemptionPayoutAccounts<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemMasterEditionBurnContext<'info> {
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Redemption authority, update authority of the reward master edition
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_SEED, authority.key().as_ref(), redemption.collection_mint.as_ref()],
+        bump = redemption.bump,
+        has_one = authority
+    )]
+    pub redemption: Account<'info, Redemption>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_PROGRESS_SEED, redemption.key().as_ref(), owner.key().as_ref()],
+        bump = progress.bump
+    )]
+    pub progress: Account<'info, RedemptionProgress>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_VAULT_SEED, redemption.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Metadata of the burned NFT, read for its collection
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_edition_mint.key().as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_metadata: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub master_edition_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = master_edition_mint,
+        token::authority = owner
+    )]
+    pub master_edition_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Master edition of the burned NFT
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), master_edition_mint.key().as_ref(), EDITION.as_bytes()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub master_edition_account: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata of the collection, required by Metaplex to burn verified members
+    #[account(
+        mut,
+        seeds = [PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), redemption.collection_mint.as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub collection_metadata_account: UncheckedAccount<'info>,
+
+    pub payout: RedemptionPayoutAccounts<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEmptyTokenAccountsContext<'info> {
+
+    /// Owner of every token account passed in remaining accounts
+    pub owner: Signer<'info>,
+
+    /// CHECK: Receives the reclaimed rent, may be `owner` itself
+    #[account(mut)]
+    pub rent_recipient: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum MintNftError {
+    #[msg("Multisig must have between 1 and 10 unique members")]
+    InvalidMultisigMembers,
+    #[msg("Threshold must be between 1 and the number of members")]
+    InvalidMultisigThreshold,
+    #[msg("Signer is not a member of the multisig")]
+    NotMultisigMember,
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    #[msg("Proposal has not reached the approval threshold")]
+    ProposalThresholdNotMet,
+    #[msg("Proposal action does not match this instruction")]
+    ProposalActionMismatch,
+    #[msg("Account does not match the one named in the proposal")]
+    ProposalAccountMismatch,
+    #[msg("Basis points must not exceed 10000")]
+    InvalidBasisPoints,
+    #[msg("Fee token accounts are missing or do not match the treasury fee mint")]
+    InvalidFeeAccounts,
+    #[msg("Withdrawal would leave the treasury below rent exemption")]
+    InsufficientTreasuryBalance,
+    #[msg("Price must be greater than zero")]
+    InvalidPrice,
+    #[msg("Price does not match the listing")]
+    PriceMismatch,
+    #[msg("Payment token accounts are missing or do not match the price mint")]
+    InvalidPaymentAccounts,
+    #[msg("Metadata account does not belong to the NFT mint")]
+    InvalidMetadataAccount,
+    #[msg("Creator accounts must match the metadata creators, in order")]
+    InvalidCreatorAccounts,
+    #[msg("Arithmetic overflow")]
+    NumericalOverflow,
+    #[msg("Mint is not an NFT (supply 1, 0 decimals)")]
+    NotAnNft,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Bid has expired")]
+    BidExpired,
+    #[msg("Bid has not expired yet")]
+    BidNotExpired,
+    #[msg("NFT does not match the bid target")]
+    BidTargetMismatch,
+    #[msg("Auction parameters are invalid")]
+    InvalidAuctionParams,
+    #[msg("Auction is not accepting bids or purchases right now")]
+    AuctionNotActive,
+    #[msg("Auction has not ended yet")]
+    AuctionNotEnded,
+    #[msg("Instruction does not apply to this kind of auction")]
+    AuctionKindMismatch,
+    #[msg("Bid is below the reserve price or minimum increment")]
+    BidTooLow,
+    #[msg("Outbid bidder account does not match the current highest bidder")]
+    InvalidPreviousBidder,
+    #[msg("Auction already has bids")]
+    AuctionHasBids,
+    #[msg("Current price is above the buyer's maximum")]
+    PriceAboveMaximum,
+    #[msg("Destination token account is not owned by the auction winner")]
+    InvalidDestinationAccount,
+    #[msg("Swap must offer and request between 0 and 4 items each, and something on both sides")]
+    InvalidSwapItems,
+    #[msg("Item is not part of the swap offer")]
+    SwapItemNotOffered,
+    #[msg("Not every offered item has been deposited yet")]
+    SwapNotFunded,
+    #[msg("Swap offer is reserved for another taker")]
+    InvalidSwapTaker,
+    #[msg("Swap offer has expired")]
+    SwapExpired,
+    #[msg("Only the maker can close a swap offer before it expires")]
+    SwapNotExpired,
+    #[msg("Remaining accounts do not match the swap items")]
+    InvalidSwapAccounts,
+    #[msg("Rental duration must be between 1 day and the listed maximum")]
+    InvalidRentalDuration,
+    #[msg("NFT is already rented")]
+    AlreadyRented,
+    #[msg("Rental period has not ended yet")]
+    RentalNotExpired,
+    #[msg("NFT is not a verified member of the pool collection")]
+    CollectionNotVerified,
+    #[msg("Share supply must be greater than zero")]
+    InvalidShareSupply,
+    #[msg("Vault has already been bought out")]
+    VaultBoughtOut,
+    #[msg("Vault has not been bought out yet")]
+    VaultNotBoughtOut,
+    #[msg("Invalid loan terms")]
+    InvalidLoanTerms,
+    #[msg("Loan has already been taken")]
+    LoanAlreadyTaken,
+    #[msg("Loan has not been taken")]
+    LoanNotTaken,
+    #[msg("Loan is past its due time")]
+    LoanOverdue,
+    #[msg("Loan is not past its due time yet")]
+    LoanNotOverdue,
+    #[msg("Token program must be SPL Token or Token-2022")]
+    InvalidTokenProgram,
+    #[msg("Soulbound NFT has already been minted")]
+    SoulboundAlreadyMinted,
+    #[msg("Uses must be positive, and exactly one for the Single use method")]
+    InvalidUses,
+    #[msg("NFT has no uses configured")]
+    NftHasNoUses,
+    #[msg("Invalid ticket event parameters")]
+    InvalidTicketEvent,
+    #[msg("Too many ticket tiers")]
+    TooManyTicketTiers,
+    #[msg("Ticket tier does not exist")]
+    InvalidTicketTier,
+    #[msg("Ticket sales are closed")]
+    TicketSalesClosed,
+    #[msg("Event is sold out")]
+    EventSoldOut,
+    #[msg("Signer is not gate staff of this event")]
+    NotGateStaff,
+    #[msg("Ticket has already been checked in")]
+    TicketAlreadyUsed,
+    #[msg("Event has ended")]
+    TicketEventEnded,
+    #[msg("Event has not ended yet")]
+    TicketEventNotEnded,
+    #[msg("Invalid membership terms")]
+    InvalidMembershipTerms,
+    #[msg("Signer is not the metadata update authority")]
+    NotUpdateAuthority,
+    #[msg("Membership has not expired")]
+    MembershipNotExpired,
+    #[msg("Membership has expired")]
+    MembershipExpired,
+    #[msg("Auto-freeze is not enabled for this membership")]
+    AutoFreezeDisabled,
+    #[msg("Missing or invalid ed25519 signature instruction")]
+    InvalidVoucherSignature,
+    #[msg("Voucher has expired")]
+    VoucherExpired,
+    #[msg("Voucher is reserved for another recipient")]
+    VoucherRecipientMismatch,
+    #[msg("Voucher has already been redeemed")]
+    VoucherAlreadyRedeemed,
+    #[msg("Nonce does not belong to this nonce page")]
+    InvalidNoncePage,
+    #[msg("Missing or invalid secp256k1 signature instruction")]
+    InvalidEthSignature,
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Invalid airdrop distributor configuration")]
+    InvalidAirdropDistributor,
+    #[msg("Airdrop index is out of range")]
+    InvalidAirdropIndex,
+    #[msg("Airdrop leaf has already been claimed")]
+    AirdropAlreadyClaimed,
+    #[msg("Push airdrop accounts do not match the recipients")]
+    InvalidPushAirdropAccounts,
+    #[msg("Invalid drop configuration")]
+    InvalidDropConfig,
+    #[msg("All drop items have been minted")]
+    DropSoldOut,
+    #[msg("Reveal accounts do not match the entries")]
+    InvalidRevealAccounts,
+    #[msg("Drop item has already been revealed")]
+    DropItemAlreadyRevealed,
+    #[msg("SlotHashes sysvar has no entries")]
+    InvalidSlotHashes,
+    #[msg("Mint must be a top-level instruction")]
+    MintNotTopLevel,
+    #[msg("Transaction calls a program not allowed alongside the mint")]
+    DisallowedProgram,
+    #[msg("Only one mint instruction is allowed per transaction")]
+    MultipleMintsInTransaction,
+    #[msg("Invalid redemption configuration")]
+    InvalidRedemption,
+    #[msg("Redemption has no rewards left")]
+    RedemptionOutOfStock,
+    #[msg("Missing or invalid reward accounts for a completed redemption")]
+    InvalidRedemptionPayout,
+    #[msg("No token accounts to close")]
+    NoAccountsToClose,
+    #[msg("Token account is not owned by the caller")]
+    InvalidTokenAccountOwner,
+    #[msg("Token account still holds tokens")]
+    TokenAccountNotEmpty,
+    #[msg("Account is not the associated token account of the authority and mint")]
+    InvalidAssociatedTokenAccount,
+    #[msg("Token account is not an account of the soulbound mint")]
+    InvalidSoulboundTokenAccount,
+    #[msg("Reward vault cannot pay the pending stake rewards")]
+    StakeRewardsUnpaid,
+    #[msg("Amount exceeds the rewards not yet accrued to stakers")]
+    InsufficientUnallocatedRewards,
+    #[msg("Holder token account no longer delegates to the membership")]
+    MembershipDelegateRevoked,
+    #[msg("Claim distributors cannot push and push-only distributors cannot be claimed from")]
+    AirdropMethodNotSupported,
+    #[msg("Only the program upgrade authority can initialize the treasury")]
+    NotUpgradeAuthority,
+    #[msg("Referrer cannot be the fee payer")]
+    SelfReferral,
+    #[msg("Edition account is not a master edition")]
+    NotMasterEdition,
+}
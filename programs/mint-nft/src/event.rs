@@ -0,0 +1,498 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    AuctionKind,
+    BidTarget,
+    FeeKind,
+    SwapItem,
+};
+
+#[event]
+pub struct FeeCollected {
+    pub kind: FeeKind,
+    pub payer: Pubkey,
+    pub fee_mint: Option<Pubkey>,
+    pub treasury_amount: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_amount: u64,
+}
+
+#[event]
+pub struct NftListed {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub price: u64,
+    pub price_mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct NftSold {
+    pub nft_mint: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub price: u64,
+    pub price_mint: Option<Pubkey>,
+    pub royalty_amount: u64,
+}
+
+#[event]
+pub struct ListingCancelled {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+}
+
+#[event]
+pub struct BidPlaced {
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub target: BidTarget,
+    pub amount: u64,
+    pub expires_at: Option<i64>,
+}
+
+#[event]
+pub struct BidAccepted {
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub royalty_amount: u64,
+}
+
+#[event]
+pub struct BidClosed {
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub expired: bool,
+}
+
+#[event]
+pub struct AuctionCreated {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub kind: AuctionKind,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct AuctionBidPlaced {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub refunded_bidder: Option<Pubkey>,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub winner: Option<Pubkey>,
+    pub price: u64,
+    pub royalty_amount: u64,
+}
+
+#[event]
+pub struct AuctionCancelled {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+}
+
+#[event]
+pub struct SwapOfferCreated {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Option<Pubkey>,
+    pub offered: Vec<SwapItem>,
+    pub offered_lamports: u64,
+    pub requested: Vec<SwapItem>,
+    pub requested_lamports: u64,
+}
+
+#[event]
+pub struct SwapCompleted {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+}
+
+#[event]
+pub struct SwapOfferClosed {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub expired: bool,
+}
+
+#[event]
+pub struct RentalListed {
+    pub rental: Pubkey,
+    pub owner: Pubkey,
+    pub nft_mint: Pubkey,
+    pub daily_price: u64,
+    pub max_days: u16,
+}
+
+#[event]
+pub struct NftRented {
+    pub rental: Pubkey,
+    pub renter: Pubkey,
+    pub days: u16,
+    pub paid: u64,
+    pub rented_until: i64,
+}
+
+#[event]
+pub struct RentalEnded {
+    pub rental: Pubkey,
+    pub owner: Pubkey,
+    pub renter: Option<Pubkey>,
+    pub paid: u64,
+}
+
+#[event]
+pub struct NftStaked {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub nft_mint: Pubkey,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct NftUnstaked {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub nft_mint: Pubkey,
+    pub total_staked: u64,
+    pub forfeited_rewards: u64,
+}
+
+#[event]
+pub struct StakeRewardsClaimed {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub pending_rewards: u64,
+}
+
+#[event]
+pub struct StakeRewardRateChanged {
+    pub pool: Pubkey,
+    pub old_rate: u64,
+    pub new_rate: u64,
+}
+
+#[event]
+pub struct StakeRewardsWithdrawn {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct NftFractionalized {
+    pub vault: Pubkey,
+    pub curator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub share_mint: Pubkey,
+    pub share_supply: u64,
+    pub reserve_price: u64,
+}
+
+#[event]
+pub struct FractionVaultBoughtOut {
+    pub vault: Pubkey,
+    pub buyer: Pubkey,
+    pub price: u64,
+}
+
+#[event]
+pub struct FractionSharesRedeemed {
+    pub vault: Pubkey,
+    pub holder: Pubkey,
+    pub shares: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LoanOfferCreated {
+    pub loan: Pubkey,
+    pub lender: Pubkey,
+    pub collection_mint: Pubkey,
+    pub principal: u64,
+    pub interest_rate_basis_points: u16,
+    pub duration: i64,
+}
+
+#[event]
+pub struct LoanTaken {
+    pub loan: Pubkey,
+    pub borrower: Pubkey,
+    pub nft_mint: Pubkey,
+    pub due_time: i64,
+    pub repayment_amount: u64,
+}
+
+#[event]
+pub struct LoanClosed {
+    pub loan: Pubkey,
+    pub lender: Pubkey,
+    pub borrower: Option<Pubkey>,
+    // True when the lender took the NFT after the deadline
+    pub foreclosed: bool,
+    pub amount_repaid: u64,
+}
+
+#[event]
+pub struct SoulboundMinted {
+    pub mint: Pubkey,
+    pub issuer: Pubkey,
+    pub holder: Pubkey,
+    pub token_program: Pubkey,
+}
+
+#[event]
+pub struct SoulboundRevoked {
+    pub mint: Pubkey,
+    pub issuer: Pubkey,
+    pub holder: Option<Pubkey>,
+}
+
+#[event]
+pub struct NftUsed {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+    pub use_authority: Pubkey,
+    pub number_of_uses: u64,
+    pub remaining: u64,
+    pub total: u64,
+    // Burn use method ran out of uses and Metaplex burned the NFT
+    pub burned: bool,
+}
+
+#[event]
+pub struct NftUseAuthorityApproved {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+    pub user: Pubkey,
+    pub number_of_uses: u64,
+}
+
+#[event]
+pub struct NftUseAuthorityRevoked {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct TicketEventCreated {
+    pub event: Pubkey,
+    pub organizer: Pubkey,
+    pub capacity: u32,
+    pub start_time: i64,
+}
+
+#[event]
+pub struct TicketTierAdded {
+    pub event: Pubkey,
+    pub tier: u8,
+    pub master_mint: Pubkey,
+    pub price: u64,
+}
+
+#[event]
+pub struct TicketTierWithdrawn {
+    pub event: Pubkey,
+    pub tier: u8,
+    pub master_mint: Pubkey,
+    pub sold: u32,
+}
+
+#[event]
+pub struct TicketPurchased {
+    pub event: Pubkey,
+    pub tier: u8,
+    pub buyer: Pubkey,
+    pub edition_mint: Pubkey,
+    pub edition: u64,
+    pub price: u64,
+}
+
+#[event]
+pub struct TicketCheckedIn {
+    pub event: Pubkey,
+    pub edition_mint: Pubkey,
+    pub holder: Pubkey,
+    pub gate_staff: Pubkey,
+    pub frozen: bool,
+}
+
+#[event]
+pub struct TicketEventEnded {
+    pub event: Pubkey,
+    pub tickets_sold: u32,
+}
+
+#[event]
+pub struct MembershipCreated {
+    pub membership: Pubkey,
+    pub issuer: Pubkey,
+    pub mint: Pubkey,
+    pub expires_at: i64,
+    pub auto_freeze: bool,
+}
+
+#[event]
+pub struct MembershipRenewed {
+    pub membership: Pubkey,
+    pub payer: Pubkey,
+    pub periods: u16,
+    pub paid: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct MembershipFreezeChanged {
+    pub membership: Pubkey,
+    pub token_account: Pubkey,
+    pub frozen: bool,
+}
+
+#[event]
+pub struct MintVoucherRedeemed {
+    pub creator: Pubkey,
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub nonce: u64,
+    pub price: u64,
+}
+
+#[event]
+pub struct EthDropCreated {
+    pub drop: Pubkey,
+    pub authority: Pubkey,
+    pub master_mint: Pubkey,
+    pub merkle_root: [u8; 32],
+}
+
+#[event]
+pub struct EthDropClaimed {
+    pub drop: Pubkey,
+    pub eth_address: [u8; 20],
+    pub recipient: Pubkey,
+    pub edition_mint: Pubkey,
+    pub edition: u64,
+}
+
+#[event]
+pub struct EthDropClosed {
+    pub drop: Pubkey,
+    pub authority: Pubkey,
+    pub claimed_count: u64,
+}
+
+#[event]
+pub struct AirdropDistributorCreated {
+    pub distributor: Pubkey,
+    pub authority: Pubkey,
+    pub merkle_root: Option<[u8; 32]>,
+    pub max_recipients: u64,
+}
+
+#[event]
+pub struct AirdropClaimed {
+    pub distributor: Pubkey,
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub item: u64,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct AirdropPushed {
+    pub distributor: Pubkey,
+    pub recipient: Pubkey,
+    pub item: u64,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct AirdropVaultWithdrawn {
+    pub distributor: Pubkey,
+    pub authority: Pubkey,
+    pub master_mint: Pubkey,
+    pub claimed_count: u64,
+}
+
+#[event]
+pub struct DropCreated {
+    pub drop: Pubkey,
+    pub authority: Pubkey,
+    pub items_available: u32,
+    pub provenance_hash: [u8; 32],
+}
+
+#[event]
+pub struct DropItemMinted {
+    pub drop: Pubkey,
+    pub index: u32,
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+}
+
+#[event]
+pub struct DropItemRevealed {
+    pub drop: Pubkey,
+    pub index: u32,
+    pub mint: Pubkey,
+    pub uri: String,
+}
+
+#[event]
+pub struct RedemptionCreated {
+    pub redemption: Pubkey,
+    pub authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub burns_required: u16,
+    pub inventory: u64,
+}
+
+#[event]
+pub struct RedemptionBurned {
+    pub redemption: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub burned: u16,
+}
+
+#[event]
+pub struct RedemptionPaid {
+    pub redemption: Pubkey,
+    pub owner: Pubkey,
+    // Reward token mint, or the mint of the printed edition
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RedemptionClosed {
+    pub redemption: Pubkey,
+    pub authority: Pubkey,
+    pub reward_mint: Pubkey,
+    // Reward tokens, or the master edition, returned from the vault
+    pub returned: u64,
+}
+
+#[event]
+pub struct TokenAccountsClosed {
+    pub owner: Pubkey,
+    pub rent_recipient: Pubkey,
+    pub count: u32,
+    pub lamports: u64,
+}
@@ -1,7 +1,10 @@
+#![allow(clippy::result_large_err)]
+
 use anchor_lang::{
     prelude::*,
     solana_program::{
         program::invoke,
+        program_option::COption,
         instruction:: {
             Instruction
         },
@@ -10,21 +13,36 @@ use anchor_lang::{
 };
 
 use anchor_spl::{
-    token,
+    token::{
+        self,
+        TokenAccount,
+    },
     associated_token,
 };
 
 use mpl_token_metadata::{
     instruction as mpl_instruction,
+    state::{UseMethod, EDITION, EDITION_MARKER_BIT_SIZE, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH, PREFIX},
     ID as TOKEN_METADATA_ID
 };
 
+use spl_token_2022::extension::{
+    ExtensionType,
+    StateWithExtensions,
+};
+
 pub mod constant;
 pub mod context;
+pub mod error;
+pub mod event;
 pub mod state;
+pub mod utils;
 
 use crate::{
+    constant::*,
     context::*,
+    error::*,
+    event::*,
     state::*,
 };
 
@@ -88,16 +106,18 @@ pub mod mint_nft {
         )
     }
 
+    /// Creates the associated token account of `authority` for `mint`, succeeding without
+    /// changes when it already exists.
     pub fn create_associated_token_account(ctx: Context<CreateAssociatedTokenAccountContext>) -> Result<()> {
-        associated_token::create( 
-            CpiContext::new(
-                ctx.accounts.associated_token_program.to_account_info(), 
-                associated_token::Create { 
-                    payer: ctx.accounts.payer.to_account_info(), 
-                    associated_token: ctx.accounts.associated_token.to_account_info(), 
-                    authority: ctx.accounts.authority.to_account_info(), 
-                    mint: ctx.accounts.mint.to_account_info(), 
-                    system_program: ctx.accounts.system_program.to_account_info(), 
+        associated_token::create_idempotent(
+            CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: ctx.accounts.payer.to_account_info(),
+                    associated_token: ctx.accounts.associated_token.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
                     token_program: ctx.accounts.token_program.to_account_info(),
                 }
             )
@@ -111,6 +131,8 @@ pub mod mint_nft {
         let authority = &ctx.accounts.authority;
         let token_program = &ctx.accounts.token_program;
 
+        utils::collect_fee(&ctx.accounts.fee, FeeKind::Mint, &payer.to_account_info())?;
+
         let data = MintToTokenParams {
             instruction: 7,
             amount,
@@ -123,7 +145,7 @@ pub mod mint_nft {
         let instruction = Instruction {
             program_id: token_program.key(),
             accounts: vec![
-                AccountMeta::new(mint.key(), true),
+                AccountMeta::new(mint.key(), false),
                 AccountMeta::new(token_account.key(), false),
                 AccountMeta::new(authority.key(), true),
             ],
@@ -143,103 +165,43 @@ pub mod mint_nft {
         Ok(())
     }
 
-    pub fn create_token_metadata_account(ctx: Context<CreateMetadataAccountsContext>, creators: Vec<Creator>, name: String, symbol: String, uri: String) -> Result<()> {
-        let metadata_account = &ctx.accounts.metadata_account;
-        let mint_authority = &ctx.accounts.mint_authority;
-        let payer = &ctx.accounts.payer;
-        let update_authority = &ctx.accounts.update_authority;
-        let mint = &ctx.accounts.mint;
-        let system_program = &ctx.accounts.system_program;
-        let rent = &ctx.accounts.rent;
-        let token_metadata_program = &ctx.accounts.token_metadata_program;
-        let mut token_creators: Vec<mpl_token_metadata::state::Creator> = Vec::new();
-
-        for creator in creators.iter() {
-            token_creators.push(
-                mpl_token_metadata::state::Creator {
-                    address: creator.address,
-                    verified: creator.verified,
-                    share: creator.share,
-                }
-            )
+    pub fn create_token_metadata_account(ctx: Context<CreateMetadataAccountsContext>, creators: Vec<Creator>, name: String, symbol: String, uri: String, uses: Option<NftUses>) -> Result<()> {
+        if let Some(uses) = &uses {
+            uses.validate()?;
         }
 
-        let instruction = mpl_instruction::create_metadata_accounts_v3(
-            TOKEN_METADATA_ID, 
-            metadata_account.key(), 
-            mint.key(), 
-            mint_authority.key(), 
-            payer.key(), 
-            update_authority.key(), 
-            name, 
-            symbol, 
-            uri, 
-            Some(token_creators), 
-            0, 
-            true, 
-            false, 
-            None, 
-            None,
-            None,
-        );
-        msg!("DEBUG: create metadata {:?}", instruction);
-
-        invoke(
-            &instruction, 
-            &[
-                metadata_account.to_account_info(),
-                mint.to_account_info(),
-                mint_authority.to_account_info(),
-                payer.to_account_info(),
-                update_authority.to_account_info(),
-                system_program.to_account_info(),
-                rent.to_account_info(),
-                token_metadata_program.to_account_info(),
-            ])
-        .expect("CPI failed");
-        Ok(())
+        utils::create_metadata_account(
+            &ctx.accounts.metadata_account,
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.payer,
+            &ctx.accounts.update_authority,
+            &ctx.accounts.system_program,
+            &ctx.accounts.rent.to_account_info(),
+            &ctx.accounts.token_metadata_program,
+            utils::metadata_data(creators, name, symbol, uri, uses),
+            false,
+            &[],
+        )
     }
 
     pub fn create_master_edition_account(ctx: Context<CreateMasterEditionAccountContext>, max_supply: u64) -> Result<()> {
-        let metadata_account = &ctx.accounts.metadata_account;
-        let mint_authority = &ctx.accounts.mint_authority;
-        let payer = &ctx.accounts.payer;
-        let update_authority = &ctx.accounts.update_authority;
-        let mint = &ctx.accounts.mint;
-        let system_program = &ctx.accounts.system_program;
-        let rent = &ctx.accounts.rent;
-        let token_metadata_program = &ctx.accounts.token_metadata_program;
-        let master_edition_account = &ctx.accounts.master_edition_account;
-        let token_program = &ctx.accounts.token_program;
-
-        let instruction = mpl_instruction::create_master_edition_v3(
-            TOKEN_METADATA_ID, 
-            master_edition_account.key(), 
-            mint.key(), 
-            update_authority.key(), 
-            mint_authority.key(), 
-            metadata_account.key(), 
-            payer.key(), 
-            Some(max_supply),
-        );
-
-        msg!("DEBUG: Create master edition instruction {:?}", instruction);
-
-        invoke(&instruction, &[
-            metadata_account.to_account_info(),
-            mint.to_account_info(),
-            mint_authority.to_account_info(),
-            payer.to_account_info(),
-            update_authority.to_account_info(),
-            system_program.to_account_info(),
-            rent.to_account_info(),
-            token_metadata_program.to_account_info(),
-            master_edition_account.to_account_info(),
-            token_program.to_account_info(),
-        ])
-        .expect("CPI failed");
+        utils::collect_fee(&ctx.accounts.fee, FeeKind::MasterEdition, &ctx.accounts.payer.to_account_info())?;
 
-        Ok(())
+        utils::create_master_edition_account(
+            &ctx.accounts.master_edition_account,
+            &ctx.accounts.metadata_account,
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.payer,
+            &ctx.accounts.update_authority,
+            &ctx.accounts.system_program,
+            &ctx.accounts.rent.to_account_info(),
+            &ctx.accounts.token_metadata_program,
+            &ctx.accounts.token_program,
+            max_supply,
+            &[],
+        )
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -261,6 +223,8 @@ pub mod mint_nft {
         let token_metadata_program = &ctx.accounts.token_metadata_program;
         let rent = &ctx.accounts.rent;
 
+        utils::collect_fee(&ctx.accounts.fee, FeeKind::Print, &payer.to_account_info())?;
+
         let instruction = mpl_token_metadata::instruction::mint_new_edition_from_master_edition_via_token(
             TOKEN_METADATA_ID,
             edition_metadata_account.key(),
@@ -313,6 +277,7 @@ pub mod mint_nft {
         let edition_mark_pda = &ctx.accounts.edition_mark_pda;
         let token_program = &ctx.accounts.token_program;
         let token_metadata_program = &ctx.accounts.token_metadata_program;
+        let lamports_before = nft_owner.lamports();
 
         let instruction = mpl_instruction::burn_edition_nft(
             TOKEN_METADATA_ID, 
@@ -342,7 +307,12 @@ pub mod mint_nft {
             token_metadata_program.to_account_info(),
         ]).expect("CPI failed");
 
-        Ok(())
+        utils::forward_reclaimed_rent(
+            &nft_owner.to_account_info(),
+            ctx.accounts.rent_recipient.as_ref(),
+            lamports_before,
+            &ctx.accounts.system_program.to_account_info(),
+        )
     }
 
     pub fn burn_master_edition_nft(ctx: Context<BurnMasterEditionContext>) -> Result<()> {
@@ -353,6 +323,7 @@ pub mod mint_nft {
         let master_edition_account = &ctx.accounts.master_edition_account;
         let token_program = &ctx.accounts.token_program;
         let token_metadata_program = &ctx.accounts.token_metadata_program;
+        let lamports_before = owner.lamports();
 
         let instruction = mpl_instruction::burn_nft(
             TOKEN_METADATA_ID, 
@@ -375,6 +346,45 @@ pub mod mint_nft {
             token_metadata_program.to_account_info(),
         ]).expect("CPI failed");
 
+        utils::forward_reclaimed_rent(
+            &owner.to_account_info(),
+            ctx.accounts.rent_recipient.as_ref(),
+            lamports_before,
+            &ctx.accounts.system_program.to_account_info(),
+        )
+    }
+
+    /// Closes empty token accounts of the caller, passed as writable remaining accounts,
+    /// sending their rent to `rent_recipient`. Metaplex burns close the token account
+    /// themselves; this sweeps what plain SPL burns and transfers leave behind.
+    pub fn close_empty_token_accounts<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseEmptyTokenAccountsContext<'info>>,
+    ) -> Result<()> {
+        require!(!ctx.remaining_accounts.is_empty(), MintNftError::NoAccountsToClose);
+        let owner = ctx.accounts.owner.key();
+        let mut lamports = 0u64;
+        for account in ctx.remaining_accounts {
+            let token_account = Account::<TokenAccount>::try_from(account)?;
+            require_keys_eq!(token_account.owner, owner, MintNftError::InvalidTokenAccountOwner);
+            require!(token_account.amount == 0, MintNftError::TokenAccountNotEmpty);
+
+            lamports = lamports.checked_add(account.lamports()).ok_or(MintNftError::NumericalOverflow)?;
+            token::close_account(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::CloseAccount {
+                    account: account.clone(),
+                    destination: ctx.accounts.rent_recipient.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ))?;
+        }
+
+        emit!(TokenAccountsClosed {
+            owner,
+            rent_recipient: ctx.accounts.rent_recipient.key(),
+            count: ctx.remaining_accounts.len() as u32,
+            lamports,
+        });
         Ok(())
     }
 
@@ -393,7 +403,7 @@ pub mod mint_nft {
         let data = data.try_to_vec().unwrap();
 
         let accounts = vec![
-            AccountMeta::new(*source_account.key, false),
+            AccountMeta::new(source_account.key(), false),
             AccountMeta::new(*delegate_account.key, false),
             AccountMeta::new_readonly(*signer.key, true),
         ];
@@ -407,10 +417,10 @@ pub mod mint_nft {
         msg!("DEBUG: Delegate account instruction {:?}", instruction);
 
         invoke(&instruction, &[
-            source_account.clone(),
+            source_account.to_account_info(),
             delegate_account.to_account_info().clone(),
             signer.to_account_info().clone(),
-            token_program.clone()
+            token_program.to_account_info()
         ]).expect("CPI failed");
 
        Ok(())
@@ -437,8 +447,8 @@ pub mod mint_nft {
         let data = data.try_to_vec().unwrap();
 
         let accounts = vec![
-            AccountMeta::new(*source_account.key, false),
-            AccountMeta::new(*destination_account.key, false),
+            AccountMeta::new(source_account.key(), false),
+            AccountMeta::new(destination_account.key(), false),
             AccountMeta::new(*delegate_account.key, true),
         ];
 
@@ -451,14 +461,3678 @@ pub mod mint_nft {
         msg!("DEBUG: Transfer from delegate account instruction {:?}", instruction);
 
         invoke_signed(&instruction, &[
-            source_account.clone(),
-            destination_account.clone(),
+            source_account.to_account_info(),
+            destination_account.to_account_info(),
             delegate_account.to_account_info().clone(),
-            token_program.clone()
+            token_program.to_account_info()
         ],
-            &[&seed],
+            &[seed],
         ).expect("CPI failed");
         Ok(())
     }
+
+    /// Create the singleton treasury. Only the program upgrade authority may call this and becomes its admin.
+    pub fn initialize_treasury(ctx: Context<InitializeTreasuryContext>, config: FeeConfig) -> Result<()> {
+        require!(config.referral_basis_points <= MAX_BASIS_POINTS, MintNftError::InvalidBasisPoints);
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.admin = ctx.accounts.admin.key();
+        treasury.config = config;
+        treasury.bump = *ctx.bumps.get("treasury").unwrap();
+
+        msg!("Treasury: {}", treasury.key());
+        Ok(())
+    }
+
+    pub fn update_treasury(ctx: Context<UpdateTreasuryContext>, config: FeeConfig, new_admin: Option<Pubkey>) -> Result<()> {
+        require!(config.referral_basis_points <= MAX_BASIS_POINTS, MintNftError::InvalidBasisPoints);
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.config = config;
+        if let Some(new_admin) = new_admin {
+            treasury.admin = new_admin;
+        }
+        Ok(())
+    }
+
+    pub fn withdraw_treasury_lamports(ctx: Context<WithdrawTreasuryLamportsContext>, amount: u64) -> Result<()> {
+        let treasury = ctx.accounts.treasury.to_account_info();
+        let recipient = ctx.accounts.recipient.to_account_info();
+
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(treasury.data_len());
+        let available = treasury.lamports().saturating_sub(rent_exempt_minimum);
+        require!(amount <= available, MintNftError::InsufficientTreasuryBalance);
+
+        **treasury.try_borrow_mut_lamports()? -= amount;
+        **recipient.try_borrow_mut_lamports()? += amount;
+
+        msg!("Withdrew {} lamports to {}", amount, recipient.key());
+        Ok(())
+    }
+
+    pub fn withdraw_treasury_tokens(ctx: Context<WithdrawTreasuryTokensContext>, amount: u64) -> Result<()> {
+        let seeds: &[&[u8]] = &[
+            TREASURY_SEED,
+            &[ctx.accounts.treasury.bump],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.treasury_token_account.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.treasury.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )
+    }
+
+    pub fn list_nft(ctx: Context<ListNftContext>, price: u64, price_mint: Option<Pubkey>) -> Result<()> {
+        require!(price > 0, MintNftError::InvalidPrice);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.seller_token_account.to_account_info(),
+                    to: ctx.accounts.escrow_token_account.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let listing = &mut ctx.accounts.listing;
+        listing.seller = ctx.accounts.seller.key();
+        listing.nft_mint = ctx.accounts.nft_mint.key();
+        listing.price = price;
+        listing.price_mint = price_mint;
+        listing.bump = *ctx.bumps.get("listing").unwrap();
+
+        emit!(NftListed {
+            listing: listing.key(),
+            seller: listing.seller,
+            nft_mint: listing.nft_mint,
+            price,
+            price_mint,
+        });
+        Ok(())
+    }
+
+    /// Remaining accounts: one account per metadata creator, in order
+    /// (creator wallets for lamport listings, creator token accounts for SPL listings).
+    pub fn buy_nft<'info>(ctx: Context<'_, '_, '_, 'info, BuyNftContext<'info>>, price: u64) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+        require!(price == listing.price, MintNftError::PriceMismatch);
+
+        let metadata = utils::read_metadata(&ctx.accounts.metadata_account, &listing.nft_mint)?;
+
+        let buyer = ctx.accounts.buyer.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let token_program = ctx.accounts.token_program.to_account_info();
+
+        let royalty_amount = match listing.price_mint {
+            None => {
+                let source = utils::PaymentSource::Wallet {
+                    from: &buyer,
+                    system_program: &system_program,
+                };
+                let royalty_amount = utils::pay_royalties(&source, &metadata, price, None, ctx.remaining_accounts)?;
+                source.pay(&ctx.accounts.seller.to_account_info(), price - royalty_amount)?;
+                royalty_amount
+            }
+            Some(price_mint) => {
+                let (buyer_payment_token_account, seller_payment_token_account) = match (
+                    &ctx.accounts.buyer_payment_token_account,
+                    &ctx.accounts.seller_payment_token_account,
+                ) {
+                    (Some(buyer_account), Some(seller_account)) => (buyer_account, seller_account),
+                    _ => return err!(MintNftError::InvalidPaymentAccounts),
+                };
+                require_keys_eq!(buyer_payment_token_account.mint, price_mint, MintNftError::InvalidPaymentAccounts);
+                require_keys_eq!(seller_payment_token_account.mint, price_mint, MintNftError::InvalidPaymentAccounts);
+                require_keys_eq!(seller_payment_token_account.owner, listing.seller, MintNftError::InvalidPaymentAccounts);
+
+                let from = buyer_payment_token_account.to_account_info();
+                let source = utils::PaymentSource::TokenAccount {
+                    from: &from,
+                    authority: &buyer,
+                    token_program: &token_program,
+                    signer_seeds: &[],
+                };
+                let royalty_amount = utils::pay_royalties(&source, &metadata, price, Some(price_mint), ctx.remaining_accounts)?;
+                source.pay(&seller_payment_token_account.to_account_info(), price - royalty_amount)?;
+                royalty_amount
+            }
+        };
+
+        let nft_mint = listing.nft_mint;
+        let seeds: &[&[u8]] = &[
+            LISTING_SEED,
+            nft_mint.as_ref(),
+            &[listing.bump],
+        ];
+        utils::release_escrow(
+            &token_program,
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.buyer_token_account.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &listing.to_account_info(),
+            &[seeds],
+        )?;
+
+        emit!(NftSold {
+            nft_mint,
+            seller: listing.seller,
+            buyer: buyer.key(),
+            price,
+            price_mint: listing.price_mint,
+            royalty_amount,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_listing(ctx: Context<CancelListingContext>) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+
+        let nft_mint = listing.nft_mint;
+        let seeds: &[&[u8]] = &[
+            LISTING_SEED,
+            nft_mint.as_ref(),
+            &[listing.bump],
+        ];
+        utils::release_escrow(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.seller_token_account.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &listing.to_account_info(),
+            &[seeds],
+        )?;
+
+        emit!(ListingCancelled {
+            listing: listing.key(),
+            seller: listing.seller,
+            nft_mint,
+        });
+        Ok(())
+    }
+
+    pub fn place_bid(ctx: Context<PlaceBidContext>, target: BidTarget, amount: u64, expires_at: Option<i64>) -> Result<()> {
+        require!(amount > 0, MintNftError::InvalidPrice);
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > Clock::get()?.unix_timestamp, MintNftError::InvalidExpiry);
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.bidder.to_account_info(),
+                    to: ctx.accounts.bid.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let bid = &mut ctx.accounts.bid;
+        bid.bidder = ctx.accounts.bidder.key();
+        bid.target = target;
+        bid.amount = amount;
+        bid.expires_at = expires_at;
+        bid.bump = *ctx.bumps.get("bid").unwrap();
+
+        emit!(BidPlaced {
+            bid: bid.key(),
+            bidder: bid.bidder,
+            target,
+            amount,
+            expires_at,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_bid(ctx: Context<CancelBidContext>) -> Result<()> {
+        emit!(BidClosed {
+            bid: ctx.accounts.bid.key(),
+            bidder: ctx.accounts.bidder.key(),
+            expired: false,
+        });
+        Ok(())
+    }
+
+    pub fn close_expired_bid(ctx: Context<CloseExpiredBidContext>) -> Result<()> {
+        require!(
+            ctx.accounts.bid.is_expired(Clock::get()?.unix_timestamp),
+            MintNftError::BidNotExpired
+        );
+
+        emit!(BidClosed {
+            bid: ctx.accounts.bid.key(),
+            bidder: ctx.accounts.bidder.key(),
+            expired: true,
+        });
+        Ok(())
+    }
+
+    /// Remaining accounts: the metadata creator wallets, in order.
+    pub fn accept_bid<'info>(ctx: Context<'_, '_, '_, 'info, AcceptBidContext<'info>>, amount: u64) -> Result<()> {
+        let bid = &ctx.accounts.bid;
+        let nft_mint = ctx.accounts.nft_mint.key();
+
+        require!(amount == bid.amount, MintNftError::PriceMismatch);
+        require!(!bid.is_expired(Clock::get()?.unix_timestamp), MintNftError::BidExpired);
+
+        let metadata = utils::read_metadata(&ctx.accounts.metadata_account, &nft_mint)?;
+        match bid.target {
+            BidTarget::Mint(mint) => {
+                require_keys_eq!(mint, nft_mint, MintNftError::BidTargetMismatch);
+            }
+            BidTarget::Collection(collection_mint) => {
+                require!(
+                    matches!(&metadata.collection, Some(collection) if collection.verified && collection.key == collection_mint),
+                    MintNftError::BidTargetMismatch
+                );
+            }
+        }
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.seller_token_account.to_account_info(),
+                    to: ctx.accounts.bidder_token_account.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let bid_info = bid.to_account_info();
+        let source = utils::PaymentSource::ProgramAccount { from: &bid_info };
+        let royalty_amount = utils::pay_royalties(&source, &metadata, amount, None, ctx.remaining_accounts)?;
+        source.pay(&ctx.accounts.seller.to_account_info(), amount - royalty_amount)?;
+
+        // Closing the bid refunds its rent and anything above `amount` to the bidder
+        emit!(BidAccepted {
+            bid: bid.key(),
+            bidder: bid.bidder,
+            seller: ctx.accounts.seller.key(),
+            nft_mint,
+            amount,
+            royalty_amount,
+        });
+        Ok(())
+    }
+
+    pub fn create_auction(ctx: Context<CreateAuctionContext>, kind: AuctionKind, start_time: i64, end_time: i64) -> Result<()> {
+        require!(start_time < end_time, MintNftError::InvalidAuctionParams);
+        require!(end_time > Clock::get()?.unix_timestamp, MintNftError::InvalidAuctionParams);
+        match kind {
+            AuctionKind::English { min_bid_increment, extension_seconds, .. } => {
+                require!(min_bid_increment > 0 && extension_seconds >= 0, MintNftError::InvalidAuctionParams);
+            }
+            AuctionKind::Dutch { start_price, end_price, step_seconds } => {
+                require!(start_price >= end_price && end_price > 0 && step_seconds >= 0, MintNftError::InvalidAuctionParams);
+            }
+        }
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.seller_token_account.to_account_info(),
+                    to: ctx.accounts.escrow_token_account.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.seller = ctx.accounts.seller.key();
+        auction.nft_mint = ctx.accounts.nft_mint.key();
+        auction.kind = kind;
+        auction.start_time = start_time;
+        auction.end_time = end_time;
+        auction.highest_bidder = None;
+        auction.highest_bid = 0;
+        auction.bump = *ctx.bumps.get("auction").unwrap();
+
+        emit!(AuctionCreated {
+            auction: auction.key(),
+            seller: auction.seller,
+            nft_mint: auction.nft_mint,
+            kind,
+            start_time,
+            end_time,
+        });
+        Ok(())
+    }
+
+    pub fn place_auction_bid(ctx: Context<PlaceAuctionBidContext>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let auction = &ctx.accounts.auction;
+
+        let (reserve_price, min_bid_increment, extension_seconds) = match auction.kind {
+            AuctionKind::English { reserve_price, min_bid_increment, extension_seconds } => (reserve_price, min_bid_increment, extension_seconds),
+            _ => return err!(MintNftError::AuctionKindMismatch),
+        };
+        require!(now >= auction.start_time && now < auction.end_time, MintNftError::AuctionNotActive);
+
+        let minimum = match auction.highest_bidder {
+            Some(_) => auction.highest_bid.checked_add(min_bid_increment).ok_or(MintNftError::NumericalOverflow)?,
+            None => reserve_price,
+        };
+        require!(amount >= minimum, MintNftError::BidTooLow);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.bidder.to_account_info(),
+                    to: auction.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        // Refund the outbid bidder from escrow
+        if let Some(highest_bidder) = auction.highest_bidder {
+            let previous_bidder = match &ctx.accounts.previous_bidder {
+                Some(previous_bidder) if previous_bidder.key() == highest_bidder => previous_bidder,
+                _ => return err!(MintNftError::InvalidPreviousBidder),
+            };
+            let auction_info = auction.to_account_info();
+            utils::PaymentSource::ProgramAccount { from: &auction_info }
+                .pay(&previous_bidder.to_account_info(), auction.highest_bid)?;
+        }
+
+        let auction = &mut ctx.accounts.auction;
+        let refunded_bidder = auction.highest_bidder;
+        auction.highest_bidder = Some(ctx.accounts.bidder.key());
+        auction.highest_bid = amount;
+
+        // Anti-sniping: a late bid keeps the auction open for another extension window
+        if auction.end_time - now < extension_seconds {
+            auction.end_time = now + extension_seconds;
+        }
+
+        emit!(AuctionBidPlaced {
+            auction: auction.key(),
+            bidder: ctx.accounts.bidder.key(),
+            amount,
+            refunded_bidder,
+            end_time: auction.end_time,
+        });
+        Ok(())
+    }
+
+    /// Remaining accounts: the metadata creator wallets, in order.
+    pub fn settle_auction<'info>(ctx: Context<'_, '_, '_, 'info, SettleAuctionContext<'info>>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+
+        require!(
+            matches!(auction.kind, AuctionKind::English { .. }),
+            MintNftError::AuctionKindMismatch
+        );
+        require!(Clock::get()?.unix_timestamp >= auction.end_time, MintNftError::AuctionNotEnded);
+
+        let recipient = auction.highest_bidder.unwrap_or(auction.seller);
+        require_keys_eq!(
+            ctx.accounts.destination_token_account.owner,
+            recipient,
+            MintNftError::InvalidDestinationAccount
+        );
+
+        let royalty_amount = match auction.highest_bidder {
+            Some(_) => {
+                let metadata = utils::read_metadata(&ctx.accounts.metadata_account, &auction.nft_mint)?;
+                let auction_info = auction.to_account_info();
+                let source = utils::PaymentSource::ProgramAccount { from: &auction_info };
+                let royalty_amount = utils::pay_royalties(&source, &metadata, auction.highest_bid, None, ctx.remaining_accounts)?;
+                source.pay(&ctx.accounts.seller.to_account_info(), auction.highest_bid - royalty_amount)?;
+                royalty_amount
+            }
+            None => 0,
+        };
+
+        let nft_mint = auction.nft_mint;
+        let seeds: &[&[u8]] = &[
+            AUCTION_SEED,
+            nft_mint.as_ref(),
+            &[auction.bump],
+        ];
+        utils::release_escrow(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.destination_token_account.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &auction.to_account_info(),
+            &[seeds],
+        )?;
+
+        emit!(AuctionSettled {
+            auction: auction.key(),
+            seller: auction.seller,
+            winner: auction.highest_bidder,
+            price: auction.highest_bid,
+            royalty_amount,
+        });
+        Ok(())
+    }
+
+    /// Remaining accounts: the metadata creator wallets, in order.
+    pub fn buy_dutch_auction<'info>(ctx: Context<'_, '_, '_, 'info, BuyDutchAuctionContext<'info>>, max_price: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let auction = &ctx.accounts.auction;
+
+        let price = auction.dutch_price(now).ok_or(MintNftError::AuctionKindMismatch)?;
+        require!(now >= auction.start_time, MintNftError::AuctionNotActive);
+        require!(price <= max_price, MintNftError::PriceAboveMaximum);
+
+        let metadata = utils::read_metadata(&ctx.accounts.metadata_account, &auction.nft_mint)?;
+        let buyer = ctx.accounts.buyer.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let source = utils::PaymentSource::Wallet {
+            from: &buyer,
+            system_program: &system_program,
+        };
+        let royalty_amount = utils::pay_royalties(&source, &metadata, price, None, ctx.remaining_accounts)?;
+        source.pay(&ctx.accounts.seller.to_account_info(), price - royalty_amount)?;
+
+        let nft_mint = auction.nft_mint;
+        let seeds: &[&[u8]] = &[
+            AUCTION_SEED,
+            nft_mint.as_ref(),
+            &[auction.bump],
+        ];
+        utils::release_escrow(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.buyer_token_account.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &auction.to_account_info(),
+            &[seeds],
+        )?;
+
+        emit!(AuctionSettled {
+            auction: auction.key(),
+            seller: auction.seller,
+            winner: Some(buyer.key()),
+            price,
+            royalty_amount,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_auction(ctx: Context<CancelAuctionContext>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        require!(auction.highest_bidder.is_none(), MintNftError::AuctionHasBids);
+
+        let nft_mint = auction.nft_mint;
+        let seeds: &[&[u8]] = &[
+            AUCTION_SEED,
+            nft_mint.as_ref(),
+            &[auction.bump],
+        ];
+        utils::release_escrow(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.seller_token_account.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &auction.to_account_info(),
+            &[seeds],
+        )?;
+
+        emit!(AuctionCancelled {
+            auction: auction.key(),
+            seller: auction.seller,
+        });
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_swap_offer(
+        ctx: Context<CreateSwapOfferContext>,
+        id: u64,
+        offered: Vec<SwapItem>,
+        offered_lamports: u64,
+        requested: Vec<SwapItem>,
+        requested_lamports: u64,
+        taker: Option<Pubkey>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        require!(
+            offered.len() <= MAX_SWAP_ITEMS && requested.len() <= MAX_SWAP_ITEMS,
+            MintNftError::InvalidSwapItems
+        );
+        require!(
+            (!offered.is_empty() || offered_lamports > 0) && (!requested.is_empty() || requested_lamports > 0),
+            MintNftError::InvalidSwapItems
+        );
+        for items in [&offered, &requested] {
+            for (i, item) in items.iter().enumerate() {
+                require!(item.amount > 0, MintNftError::InvalidSwapItems);
+                require!(items[..i].iter().all(|other| other.mint != item.mint), MintNftError::InvalidSwapItems);
+            }
+        }
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > Clock::get()?.unix_timestamp, MintNftError::InvalidExpiry);
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.maker.to_account_info(),
+                    to: ctx.accounts.offer.to_account_info(),
+                },
+            ),
+            offered_lamports,
+        )?;
+
+        let offer = &mut ctx.accounts.offer;
+        offer.maker = ctx.accounts.maker.key();
+        offer.taker = taker;
+        offer.id = id;
+        offer.offered = offered;
+        offer.offered_lamports = offered_lamports;
+        offer.requested = requested;
+        offer.requested_lamports = requested_lamports;
+        offer.deposited = 0;
+        offer.expires_at = expires_at;
+        offer.bump = *ctx.bumps.get("offer").unwrap();
+
+        emit!(SwapOfferCreated {
+            offer: offer.key(),
+            maker: offer.maker,
+            taker,
+            offered: offer.offered.clone(),
+            offered_lamports,
+            requested: offer.requested.clone(),
+            requested_lamports,
+        });
+        Ok(())
+    }
+
+    pub fn deposit_swap_item(ctx: Context<DepositSwapItemContext>) -> Result<()> {
+        let offer = &mut ctx.accounts.offer;
+        let mint = ctx.accounts.mint.key();
+
+        let item = *offer.offered.iter()
+            .find(|item| item.mint == mint)
+            .ok_or(MintNftError::SwapItemNotOffered)?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.maker_token_account.to_account_info(),
+                    to: ctx.accounts.escrow_token_account.to_account_info(),
+                    authority: ctx.accounts.maker.to_account_info(),
+                },
+            ),
+            item.amount,
+        )?;
+
+        offer.deposited += 1;
+        Ok(())
+    }
+
+    /// Remaining accounts, in item order: (escrow, taker token account) for every
+    /// offered item, then (taker token account, maker token account) for every requested item.
+    pub fn accept_swap<'info>(ctx: Context<'_, '_, '_, 'info, AcceptSwapContext<'info>>) -> Result<()> {
+        let offer = &ctx.accounts.offer;
+        let maker = ctx.accounts.maker.to_account_info();
+        let taker = ctx.accounts.taker.to_account_info();
+        let token_program = ctx.accounts.token_program.to_account_info();
+
+        require!(offer.deposited as usize == offer.offered.len(), MintNftError::SwapNotFunded);
+        require!(!offer.is_expired(Clock::get()?.unix_timestamp), MintNftError::SwapExpired);
+        if let Some(expected_taker) = offer.taker {
+            require_keys_eq!(expected_taker, taker.key(), MintNftError::InvalidSwapTaker);
+        }
+        require!(
+            ctx.remaining_accounts.len() == 2 * (offer.offered.len() + offer.requested.len()),
+            MintNftError::InvalidSwapAccounts
+        );
+        let (offered_accounts, requested_accounts) = ctx.remaining_accounts.split_at(2 * offer.offered.len());
+
+        let id = offer.id.to_le_bytes();
+        let seeds: &[&[u8]] = &[
+            SWAP_SEED,
+            offer.maker.as_ref(),
+            &id,
+            &[offer.bump],
+        ];
+
+        for (item, accounts) in offer.offered.iter().zip(offered_accounts.chunks(2)) {
+            let escrow = Account::<TokenAccount>::try_from(&accounts[0])?;
+            let destination = Account::<TokenAccount>::try_from(&accounts[1])?;
+            require_keys_eq!(escrow.key(), utils::swap_escrow_address(&offer.key(), &item.mint), MintNftError::InvalidSwapAccounts);
+            require!(destination.mint == item.mint && destination.owner == taker.key(), MintNftError::InvalidSwapAccounts);
+
+            utils::release_escrow(
+                &token_program,
+                &escrow,
+                &accounts[1],
+                &maker,
+                &offer.to_account_info(),
+                &[seeds],
+            )?;
+        }
+
+        for (item, accounts) in offer.requested.iter().zip(requested_accounts.chunks(2)) {
+            let source = Account::<TokenAccount>::try_from(&accounts[0])?;
+            let destination = Account::<TokenAccount>::try_from(&accounts[1])?;
+            require!(source.mint == item.mint && source.owner == taker.key(), MintNftError::InvalidSwapAccounts);
+            require!(destination.mint == item.mint && destination.owner == offer.maker, MintNftError::InvalidSwapAccounts);
+
+            token::transfer(
+                CpiContext::new(
+                    token_program.clone(),
+                    token::Transfer {
+                        from: accounts[0].clone(),
+                        to: accounts[1].clone(),
+                        authority: taker.clone(),
+                    },
+                ),
+                item.amount,
+            )?;
+        }
+
+        let offer_info = offer.to_account_info();
+        utils::PaymentSource::ProgramAccount { from: &offer_info }
+            .pay(&taker, offer.offered_lamports)?;
+        utils::PaymentSource::Wallet {
+            from: &taker,
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        }.pay(&maker, offer.requested_lamports)?;
+
+        emit!(SwapCompleted {
+            offer: offer.key(),
+            maker: offer.maker,
+            taker: taker.key(),
+        });
+        Ok(())
+    }
+
+    /// Remaining accounts: (escrow, maker token account) for every offered item, in order.
+    /// Escrows that were never deposited are skipped.
+    pub fn cancel_swap_offer<'info>(ctx: Context<'_, '_, '_, 'info, CancelSwapOfferContext<'info>>) -> Result<()> {
+        let offer = &ctx.accounts.offer;
+        let maker = ctx.accounts.maker.to_account_info();
+        let expired = offer.is_expired(Clock::get()?.unix_timestamp);
+
+        require!(
+            expired || ctx.accounts.authority.key() == offer.maker,
+            MintNftError::SwapNotExpired
+        );
+        require!(
+            ctx.remaining_accounts.len() == 2 * offer.offered.len(),
+            MintNftError::InvalidSwapAccounts
+        );
+
+        let id = offer.id.to_le_bytes();
+        let seeds: &[&[u8]] = &[
+            SWAP_SEED,
+            offer.maker.as_ref(),
+            &id,
+            &[offer.bump],
+        ];
+
+        for (item, accounts) in offer.offered.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            require_keys_eq!(accounts[0].key(), utils::swap_escrow_address(&offer.key(), &item.mint), MintNftError::InvalidSwapAccounts);
+            if accounts[0].data_is_empty() {
+                continue;
+            }
+            let escrow = Account::<TokenAccount>::try_from(&accounts[0])?;
+            let destination = Account::<TokenAccount>::try_from(&accounts[1])?;
+            require!(destination.mint == item.mint && destination.owner == offer.maker, MintNftError::InvalidSwapAccounts);
+
+            utils::release_escrow(
+                &ctx.accounts.token_program.to_account_info(),
+                &escrow,
+                &accounts[1],
+                &maker,
+                &offer.to_account_info(),
+                &[seeds],
+            )?;
+        }
+
+        // Closing the offer returns the escrowed lamports to the maker
+        emit!(SwapOfferClosed {
+            offer: offer.key(),
+            maker: offer.maker,
+            expired,
+        });
+        Ok(())
+    }
+
+    pub fn list_rental(ctx: Context<ListRentalContext>, daily_price: u64, max_days: u16) -> Result<()> {
+        require!(daily_price > 0, MintNftError::InvalidPrice);
+        require!(max_days > 0, MintNftError::InvalidRentalDuration);
+
+        // The rental PDA only becomes delegate here; the NFT stays in the owner's wallet until rented
+        token::approve(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Approve {
+                    to: ctx.accounts.owner_token_account.to_account_info(),
+                    delegate: ctx.accounts.rental.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let rental = &mut ctx.accounts.rental;
+        rental.owner = ctx.accounts.owner.key();
+        rental.nft_mint = ctx.accounts.nft_mint.key();
+        rental.owner_token_account = ctx.accounts.owner_token_account.key();
+        rental.daily_price = daily_price;
+        rental.max_days = max_days;
+        rental.renter = None;
+        rental.renter_token_account = Pubkey::default();
+        rental.rented_until = 0;
+        rental.paid = 0;
+        rental.bump = *ctx.bumps.get("rental").unwrap();
+
+        emit!(RentalListed {
+            rental: rental.key(),
+            owner: rental.owner,
+            nft_mint: rental.nft_mint,
+            daily_price,
+            max_days,
+        });
+        Ok(())
+    }
+
+    pub fn rent_nft(ctx: Context<RentNftContext>, days: u16) -> Result<()> {
+        let rental = &ctx.accounts.rental;
+        require!(rental.renter.is_none(), MintNftError::AlreadyRented);
+        require!(days > 0 && days <= rental.max_days, MintNftError::InvalidRentalDuration);
+
+        let paid = rental.daily_price
+            .checked_mul(days as u64)
+            .ok_or(MintNftError::NumericalOverflow)?;
+        let rented_until = Clock::get()?.unix_timestamp + days as i64 * SECONDS_PER_DAY;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.renter.to_account_info(),
+                    to: rental.to_account_info(),
+                },
+            ),
+            paid,
+        )?;
+
+        let nft_mint = rental.nft_mint;
+        let seeds: &[&[u8]] = &[
+            RENTAL_SEED,
+            nft_mint.as_ref(),
+            &[rental.bump],
+        ];
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let rental_info = rental.to_account_info();
+        let renter_token_account = ctx.accounts.renter_token_account.to_account_info();
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                token::Transfer {
+                    from: ctx.accounts.owner_token_account.to_account_info(),
+                    to: renter_token_account.clone(),
+                    authority: rental_info.clone(),
+                },
+                &[seeds],
+            ),
+            1,
+        )?;
+
+        // Delegate + freeze keeps the NFT in the renter's wallet without letting it move
+        token::approve(
+            CpiContext::new(
+                token_program.clone(),
+                token::Approve {
+                    to: renter_token_account.clone(),
+                    delegate: rental_info.clone(),
+                    authority: ctx.accounts.renter.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+        utils::freeze_delegated_account(
+            &rental_info,
+            &renter_token_account,
+            &ctx.accounts.edition_account,
+            &ctx.accounts.nft_mint.to_account_info(),
+            &token_program,
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+
+        let rental = &mut ctx.accounts.rental;
+        rental.renter = Some(ctx.accounts.renter.key());
+        rental.renter_token_account = renter_token_account.key();
+        rental.rented_until = rented_until;
+        rental.paid = paid;
+
+        emit!(NftRented {
+            rental: rental.key(),
+            renter: ctx.accounts.renter.key(),
+            days,
+            paid,
+            rented_until,
+        });
+        Ok(())
+    }
+
+    pub fn end_rental(ctx: Context<EndRentalContext>) -> Result<()> {
+        let rental = &ctx.accounts.rental;
+        require!(rental.renter.is_some(), MintNftError::RentalNotExpired);
+        require!(Clock::get()?.unix_timestamp >= rental.rented_until, MintNftError::RentalNotExpired);
+
+        let nft_mint = rental.nft_mint;
+        let seeds: &[&[u8]] = &[
+            RENTAL_SEED,
+            nft_mint.as_ref(),
+            &[rental.bump],
+        ];
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let rental_info = rental.to_account_info();
+        let renter_token_account = ctx.accounts.renter_token_account.to_account_info();
+
+        utils::thaw_delegated_account(
+            &rental_info,
+            &renter_token_account,
+            &ctx.accounts.edition_account,
+            &ctx.accounts.nft_mint.to_account_info(),
+            &token_program,
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program,
+                token::Transfer {
+                    from: renter_token_account,
+                    to: ctx.accounts.owner_token_account.to_account_info(),
+                    authority: rental_info,
+                },
+                &[seeds],
+            ),
+            1,
+        )?;
+
+        // Closing the rental releases the escrowed payment to the owner
+        emit!(RentalEnded {
+            rental: rental.key(),
+            owner: rental.owner,
+            renter: rental.renter,
+            paid: rental.paid,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_rental(ctx: Context<CancelRentalContext>) -> Result<()> {
+        token::revoke(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Revoke {
+                    source: ctx.accounts.owner_token_account.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+        )?;
+
+        let rental = &ctx.accounts.rental;
+        emit!(RentalEnded {
+            rental: rental.key(),
+            owner: rental.owner,
+            renter: None,
+            paid: 0,
+        });
+        Ok(())
+    }
+
+    pub fn create_stake_pool(ctx: Context<CreateStakePoolContext>, reward_rate: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.collection_mint = ctx.accounts.collection_mint.key();
+        pool.reward_mint = ctx.accounts.reward_mint.key();
+        pool.reward_vault = ctx.accounts.reward_vault.key();
+        pool.reward_rate = reward_rate;
+        pool.total_staked = 0;
+        pool.reward_per_nft = 0;
+        pool.unpaid_rewards = 0;
+        pool.last_update_time = Clock::get()?.unix_timestamp;
+        pool.bump = *ctx.bumps.get("pool").unwrap();
+
+        emit!(StakeRewardRateChanged {
+            pool: pool.key(),
+            old_rate: 0,
+            new_rate: reward_rate,
+        });
+        Ok(())
+    }
+
+    pub fn set_stake_reward_rate(ctx: Context<SetStakeRewardRateContext>, reward_rate: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        // Settle accrual at the old rate before switching
+        pool.update(Clock::get()?.unix_timestamp)?;
+
+        let old_rate = pool.reward_rate;
+        pool.reward_rate = reward_rate;
+
+        emit!(StakeRewardRateChanged {
+            pool: pool.key(),
+            old_rate,
+            new_rate: reward_rate,
+        });
+        Ok(())
+    }
+
+    pub fn fund_stake_pool(ctx: Context<FundStakePoolContext>, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.funder_token_account.to_account_info(),
+                    to: ctx.accounts.reward_vault.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+
+    /// Returns reward tokens no entry has accrued yet to the pool authority.
+    pub fn withdraw_stake_rewards(ctx: Context<WithdrawStakeRewardsContext>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.update(Clock::get()?.unix_timestamp)?;
+        require!(
+            amount > 0 && amount <= pool.unallocated_rewards(ctx.accounts.reward_vault.amount),
+            MintNftError::InsufficientUnallocatedRewards
+        );
+
+        let seeds: &[&[u8]] = &[
+            STAKE_POOL_SEED,
+            pool.collection_mint.as_ref(),
+            &[pool.bump],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    to: ctx.accounts.authority_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        emit!(StakeRewardsWithdrawn {
+            pool: pool.key(),
+            authority: pool.authority,
+            amount,
+        });
+        Ok(())
+    }
+
+    pub fn stake_nft(ctx: Context<StakeNftContext>) -> Result<()> {
+        let nft_mint = ctx.accounts.nft_mint.key();
+        let metadata = utils::read_metadata(&ctx.accounts.metadata_account, &nft_mint)?;
+        let collection_mint = ctx.accounts.pool.collection_mint;
+        require!(
+            matches!(&metadata.collection, Some(collection) if collection.verified && collection.key == collection_mint),
+            MintNftError::CollectionNotVerified
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.pool.update(now)?;
+
+        // The NFT never leaves the owner's wallet; the pool PDA freezes it as delegate
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let pool_info = ctx.accounts.pool.to_account_info();
+        let nft_token_account = ctx.accounts.nft_token_account.to_account_info();
+        token::approve(
+            CpiContext::new(
+                token_program.clone(),
+                token::Approve {
+                    to: nft_token_account.clone(),
+                    delegate: pool_info.clone(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+        let seeds: &[&[u8]] = &[
+            STAKE_POOL_SEED,
+            collection_mint.as_ref(),
+            &[ctx.accounts.pool.bump],
+        ];
+        utils::freeze_delegated_account(
+            &pool_info,
+            &nft_token_account,
+            &ctx.accounts.edition_account,
+            &ctx.accounts.nft_mint.to_account_info(),
+            &token_program,
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked = pool.total_staked
+            .checked_add(1)
+            .ok_or(MintNftError::NumericalOverflow)?;
+
+        let stake_entry = &mut ctx.accounts.stake_entry;
+        stake_entry.pool = pool.key();
+        stake_entry.owner = ctx.accounts.owner.key();
+        stake_entry.nft_mint = nft_mint;
+        stake_entry.token_account = nft_token_account.key();
+        stake_entry.staked_at = now;
+        stake_entry.reward_per_nft_paid = pool.reward_per_nft;
+        stake_entry.pending_rewards = 0;
+        stake_entry.bump = *ctx.bumps.get("stake_entry").unwrap();
+
+        emit!(NftStaked {
+            pool: pool.key(),
+            owner: stake_entry.owner,
+            nft_mint,
+            total_staked: pool.total_staked,
+        });
+        Ok(())
+    }
+
+    pub fn claim_stake_rewards(ctx: Context<ClaimStakeRewardsContext>) -> Result<()> {
+        ctx.accounts.pool.update(Clock::get()?.unix_timestamp)?;
+        ctx.accounts.stake_entry.checkpoint(&ctx.accounts.pool)?;
+
+        utils::pay_stake_rewards(
+            &mut ctx.accounts.pool,
+            &mut ctx.accounts.stake_entry,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.owner_reward_account.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+        )?;
+        Ok(())
+    }
+
+    /// Pays out what the vault can cover. If rewards are still pending the unstake fails
+    /// unless `forfeit_unpaid` is set, in which case they are given up with the closed entry.
+    pub fn unstake_nft(ctx: Context<UnstakeNftContext>, forfeit_unpaid: bool) -> Result<()> {
+        ctx.accounts.pool.update(Clock::get()?.unix_timestamp)?;
+        ctx.accounts.stake_entry.checkpoint(&ctx.accounts.pool)?;
+
+        let token_program = ctx.accounts.token_program.to_account_info();
+        utils::pay_stake_rewards(
+            &mut ctx.accounts.pool,
+            &mut ctx.accounts.stake_entry,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.owner_reward_account.to_account_info(),
+            &token_program,
+        )?;
+        let forfeited_rewards = ctx.accounts.stake_entry.pending_rewards;
+        require!(forfeited_rewards == 0 || forfeit_unpaid, MintNftError::StakeRewardsUnpaid);
+
+        let collection_mint = ctx.accounts.pool.collection_mint;
+        let seeds: &[&[u8]] = &[
+            STAKE_POOL_SEED,
+            collection_mint.as_ref(),
+            &[ctx.accounts.pool.bump],
+        ];
+        let nft_token_account = ctx.accounts.nft_token_account.to_account_info();
+        utils::thaw_delegated_account(
+            &ctx.accounts.pool.to_account_info(),
+            &nft_token_account,
+            &ctx.accounts.edition_account,
+            &ctx.accounts.nft_mint.to_account_info(),
+            &token_program,
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+        token::revoke(
+            CpiContext::new(
+                token_program,
+                token::Revoke {
+                    source: nft_token_account,
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked -= 1;
+        pool.unpaid_rewards -= forfeited_rewards as u128;
+
+        emit!(NftUnstaked {
+            pool: pool.key(),
+            owner: ctx.accounts.owner.key(),
+            nft_mint: ctx.accounts.nft_mint.key(),
+            total_staked: pool.total_staked,
+            forfeited_rewards,
+        });
+        Ok(())
+    }
+
+    pub fn fractionalize_nft(
+        ctx: Context<FractionalizeNftContext>,
+        share_supply: u64,
+        _share_decimals: u8,
+        reserve_price: u64,
+    ) -> Result<()> {
+        require!(share_supply > 0, MintNftError::InvalidShareSupply);
+        require!(reserve_price > 0, MintNftError::InvalidPrice);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.curator_token_account.to_account_info(),
+                    to: ctx.accounts.escrow_token_account.to_account_info(),
+                    authority: ctx.accounts.curator.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let nft_mint = ctx.accounts.nft_mint.key();
+        let bump = *ctx.bumps.get("vault").unwrap();
+        let seeds: &[&[u8]] = &[
+            FRACTION_VAULT_SEED,
+            nft_mint.as_ref(),
+            &[bump],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    to: ctx.accounts.curator_share_account.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            share_supply,
+        )?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.curator = ctx.accounts.curator.key();
+        vault.nft_mint = nft_mint;
+        vault.share_mint = ctx.accounts.share_mint.key();
+        vault.share_supply = share_supply;
+        vault.reserve_price = reserve_price;
+        vault.buyer = None;
+        vault.outstanding_shares = share_supply;
+        vault.remaining_proceeds = 0;
+        vault.bump = bump;
+
+        emit!(NftFractionalized {
+            vault: vault.key(),
+            curator: vault.curator,
+            nft_mint,
+            share_mint: vault.share_mint,
+            share_supply,
+            reserve_price,
+        });
+        Ok(())
+    }
+
+    pub fn buyout_fraction_vault(ctx: Context<BuyoutFractionVaultContext>, price: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        require!(vault.buyer.is_none(), MintNftError::VaultBoughtOut);
+        require!(price == vault.reserve_price, MintNftError::PriceMismatch);
+
+        // Proceeds stay in the vault account until share holders redeem
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: vault.to_account_info(),
+                },
+            ),
+            price,
+        )?;
+
+        let nft_mint = vault.nft_mint;
+        let seeds: &[&[u8]] = &[
+            FRACTION_VAULT_SEED,
+            nft_mint.as_ref(),
+            &[vault.bump],
+        ];
+        utils::release_escrow(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.buyer_token_account.to_account_info(),
+            &ctx.accounts.curator.to_account_info(),
+            &vault.to_account_info(),
+            &[seeds],
+        )?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.buyer = Some(ctx.accounts.buyer.key());
+        vault.remaining_proceeds = price;
+
+        emit!(FractionVaultBoughtOut {
+            vault: vault.key(),
+            buyer: ctx.accounts.buyer.key(),
+            price,
+        });
+        Ok(())
+    }
+
+    pub fn redeem_fraction_shares(ctx: Context<RedeemFractionSharesContext>, shares: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        require!(vault.buyer.is_some(), MintNftError::VaultNotBoughtOut);
+        require!(shares > 0 && shares <= vault.outstanding_shares, MintNftError::InvalidShareSupply);
+
+        let amount = vault.redemption_amount(shares)?;
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Burn {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    from: ctx.accounts.holder_share_account.to_account_info(),
+                    authority: ctx.accounts.holder.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        let vault_info = vault.to_account_info();
+        utils::PaymentSource::ProgramAccount { from: &vault_info }
+            .pay(&ctx.accounts.holder.to_account_info(), amount)?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.outstanding_shares -= shares;
+        vault.remaining_proceeds -= amount;
+
+        emit!(FractionSharesRedeemed {
+            vault: vault.key(),
+            holder: ctx.accounts.holder.key(),
+            shares,
+            amount,
+        });
+        Ok(())
+    }
+
+    pub fn create_loan_offer(
+        ctx: Context<CreateLoanOfferContext>,
+        id: u64,
+        principal: u64,
+        interest_rate_basis_points: u16,
+        duration: i64,
+    ) -> Result<()> {
+        require!(principal > 0 && duration > 0, MintNftError::InvalidLoanTerms);
+        // Rejects terms whose repayment could never be represented
+        utils::loan_repayment_amount(principal, interest_rate_basis_points, duration)?;
+
+        // The principal is escrowed in the loan account until a borrower takes it
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.lender.to_account_info(),
+                    to: ctx.accounts.loan.to_account_info(),
+                },
+            ),
+            principal,
+        )?;
+
+        let loan = &mut ctx.accounts.loan;
+        loan.lender = ctx.accounts.lender.key();
+        loan.id = id;
+        loan.collection_mint = ctx.accounts.collection_mint.key();
+        loan.principal = principal;
+        loan.interest_rate_basis_points = interest_rate_basis_points;
+        loan.duration = duration;
+        loan.borrower = None;
+        loan.nft_mint = Pubkey::default();
+        loan.collateral_token_account = Pubkey::default();
+        loan.due_time = 0;
+        loan.repayment_amount = 0;
+        loan.bump = *ctx.bumps.get("loan").unwrap();
+
+        emit!(LoanOfferCreated {
+            loan: loan.key(),
+            lender: loan.lender,
+            collection_mint: loan.collection_mint,
+            principal,
+            interest_rate_basis_points,
+            duration,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_loan_offer(ctx: Context<CancelLoanOfferContext>) -> Result<()> {
+        // Closing the loan account returns the escrowed principal to the lender
+        let loan = &ctx.accounts.loan;
+        emit!(LoanClosed {
+            loan: loan.key(),
+            lender: loan.lender,
+            borrower: None,
+            foreclosed: false,
+            amount_repaid: 0,
+        });
+        Ok(())
+    }
+
+    pub fn take_loan(ctx: Context<TakeLoanContext>) -> Result<()> {
+        let loan = &ctx.accounts.loan;
+        require!(loan.borrower.is_none(), MintNftError::LoanAlreadyTaken);
+
+        let nft_mint = ctx.accounts.nft_mint.key();
+        let metadata = utils::read_metadata(&ctx.accounts.metadata_account, &nft_mint)?;
+        require!(
+            matches!(&metadata.collection, Some(collection) if collection.verified && collection.key == loan.collection_mint),
+            MintNftError::CollectionNotVerified
+        );
+
+        let due_time = Clock::get()?.unix_timestamp
+            .checked_add(loan.duration)
+            .ok_or(MintNftError::NumericalOverflow)?;
+        let repayment_amount = utils::loan_repayment_amount(loan.principal, loan.interest_rate_basis_points, loan.duration)?;
+
+        // Collateral stays in the borrower's wallet, frozen with the loan PDA as delegate
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let loan_info = loan.to_account_info();
+        let borrower_token_account = ctx.accounts.borrower_token_account.to_account_info();
+        token::approve(
+            CpiContext::new(
+                token_program.clone(),
+                token::Approve {
+                    to: borrower_token_account.clone(),
+                    delegate: loan_info.clone(),
+                    authority: ctx.accounts.borrower.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+        let lender = loan.lender;
+        let seeds: &[&[u8]] = &[
+            LOAN_SEED,
+            lender.as_ref(),
+            &loan.id.to_le_bytes(),
+            &[loan.bump],
+        ];
+        utils::freeze_delegated_account(
+            &loan_info,
+            &borrower_token_account,
+            &ctx.accounts.edition_account,
+            &ctx.accounts.nft_mint.to_account_info(),
+            &token_program,
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+
+        utils::PaymentSource::ProgramAccount { from: &loan_info }
+            .pay(&ctx.accounts.borrower.to_account_info(), loan.principal)?;
+
+        let loan = &mut ctx.accounts.loan;
+        loan.borrower = Some(ctx.accounts.borrower.key());
+        loan.nft_mint = nft_mint;
+        loan.collateral_token_account = borrower_token_account.key();
+        loan.due_time = due_time;
+        loan.repayment_amount = repayment_amount;
+
+        emit!(LoanTaken {
+            loan: loan.key(),
+            borrower: ctx.accounts.borrower.key(),
+            nft_mint,
+            due_time,
+            repayment_amount,
+        });
+        Ok(())
+    }
+
+    pub fn repay_loan(ctx: Context<RepayLoanContext>) -> Result<()> {
+        let loan = &ctx.accounts.loan;
+        require!(Clock::get()?.unix_timestamp <= loan.due_time, MintNftError::LoanOverdue);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.borrower.to_account_info(),
+                    to: ctx.accounts.lender.to_account_info(),
+                },
+            ),
+            loan.repayment_amount,
+        )?;
+
+        let lender = loan.lender;
+        let seeds: &[&[u8]] = &[
+            LOAN_SEED,
+            lender.as_ref(),
+            &loan.id.to_le_bytes(),
+            &[loan.bump],
+        ];
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let borrower_token_account = ctx.accounts.borrower_token_account.to_account_info();
+        utils::thaw_delegated_account(
+            &loan.to_account_info(),
+            &borrower_token_account,
+            &ctx.accounts.edition_account,
+            &ctx.accounts.nft_mint.to_account_info(),
+            &token_program,
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+        token::revoke(
+            CpiContext::new(
+                token_program,
+                token::Revoke {
+                    source: borrower_token_account,
+                    authority: ctx.accounts.borrower.to_account_info(),
+                },
+            ),
+        )?;
+
+        emit!(LoanClosed {
+            loan: loan.key(),
+            lender,
+            borrower: loan.borrower,
+            foreclosed: false,
+            amount_repaid: loan.repayment_amount,
+        });
+        Ok(())
+    }
+
+    pub fn foreclose_loan(ctx: Context<ForecloseLoanContext>) -> Result<()> {
+        let loan = &ctx.accounts.loan;
+        require!(loan.borrower.is_some(), MintNftError::LoanNotTaken);
+        require!(Clock::get()?.unix_timestamp > loan.due_time, MintNftError::LoanNotOverdue);
+
+        let lender = loan.lender;
+        let seeds: &[&[u8]] = &[
+            LOAN_SEED,
+            lender.as_ref(),
+            &loan.id.to_le_bytes(),
+            &[loan.bump],
+        ];
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let loan_info = loan.to_account_info();
+        let borrower_token_account = ctx.accounts.borrower_token_account.to_account_info();
+        utils::thaw_delegated_account(
+            &loan_info,
+            &borrower_token_account,
+            &ctx.accounts.edition_account,
+            &ctx.accounts.nft_mint.to_account_info(),
+            &token_program,
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program,
+                token::Transfer {
+                    from: borrower_token_account,
+                    to: ctx.accounts.lender_token_account.to_account_info(),
+                    authority: loan_info,
+                },
+                &[seeds],
+            ),
+            1,
+        )?;
+
+        emit!(LoanClosed {
+            loan: loan.key(),
+            lender,
+            borrower: loan.borrower,
+            foreclosed: true,
+            amount_repaid: 0,
+        });
+        Ok(())
+    }
+
+    /// Creates a mint whose tokens can never move: its freeze authority (and, on
+    /// Token-2022, its permanent delegate) is the `soulbound` PDA, and Token-2022
+    /// mints also carry the NonTransferable extension. The issuer stays mint authority
+    /// only until `mint_soulbound_token` mints the single token.
+    pub fn create_soulbound_mint(ctx: Context<CreateSoulboundMintContext>) -> Result<()> {
+        let token_program_id = ctx.accounts.token_program.key();
+        let mint = ctx.accounts.mint.key();
+        let soulbound_key = ctx.accounts.soulbound.key();
+        let token_2022 = token_program_id == spl_token_2022::ID;
+
+        let extensions: &[ExtensionType] = if token_2022 {
+            &[ExtensionType::NonTransferable, ExtensionType::PermanentDelegate]
+        } else {
+            &[]
+        };
+        let space = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(extensions);
+        system_program::create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.issuer.to_account_info(),
+                    to: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &token_program_id,
+        )?;
+
+        let mint_info = ctx.accounts.mint.to_account_info();
+        if token_2022 {
+            invoke(
+                &spl_token_2022::instruction::initialize_non_transferable_mint(&token_program_id, &mint)?,
+                std::slice::from_ref(&mint_info),
+            )?;
+            invoke(
+                &spl_token_2022::instruction::initialize_permanent_delegate(&token_program_id, &mint, &soulbound_key)?,
+                std::slice::from_ref(&mint_info),
+            )?;
+        }
+        invoke(
+            &spl_token_2022::instruction::initialize_mint2(
+                &token_program_id,
+                &mint,
+                &ctx.accounts.issuer.key(),
+                Some(&soulbound_key),
+                0,
+            )?,
+            &[mint_info],
+        )?;
+
+        let soulbound = &mut ctx.accounts.soulbound;
+        soulbound.issuer = ctx.accounts.issuer.key();
+        soulbound.mint = mint;
+        soulbound.token_program = token_program_id;
+        soulbound.holder = None;
+        soulbound.token_account = Pubkey::default();
+        soulbound.bump = *ctx.bumps.get("soulbound").unwrap();
+        Ok(())
+    }
+
+    /// Mints the single token to the recipient and drops the mint authority so no more
+    /// can ever be minted. With classic SPL Token the account is then delegated to and
+    /// frozen by the `soulbound` PDA so it can neither move nor be burned except through
+    /// `revoke_soulbound_token`.
+    pub fn mint_soulbound_token(ctx: Context<MintSoulboundTokenContext>) -> Result<()> {
+        let token_program_id = ctx.accounts.token_program.key();
+        let mint = ctx.accounts.mint.key();
+        let recipient_token_account = ctx.accounts.recipient_token_account.key();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let token_account_info = ctx.accounts.recipient_token_account.to_account_info();
+        let soulbound_info = ctx.accounts.soulbound.to_account_info();
+
+        {
+            let data = token_account_info.try_borrow_data()?;
+            let token_account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+            require_keys_eq!(token_account.base.owner, ctx.accounts.recipient.key(), MintNftError::InvalidTokenAccountOwner);
+            require_keys_eq!(token_account.base.mint, mint, MintNftError::InvalidSoulboundTokenAccount);
+        }
+
+        invoke(
+            &spl_token_2022::instruction::mint_to(
+                &token_program_id,
+                &mint,
+                &recipient_token_account,
+                &ctx.accounts.issuer.key(),
+                &[],
+                1,
+            )?,
+            &[mint_info.clone(), token_account_info.clone(), ctx.accounts.issuer.to_account_info(), token_program.clone()],
+        )?;
+        invoke(
+            &spl_token_2022::instruction::set_authority(
+                &token_program_id,
+                &mint,
+                None,
+                spl_token_2022::instruction::AuthorityType::MintTokens,
+                &ctx.accounts.issuer.key(),
+                &[],
+            )?,
+            &[mint_info.clone(), ctx.accounts.issuer.to_account_info(), token_program.clone()],
+        )?;
+
+        if token_program_id == token::ID {
+            invoke(
+                &spl_token_2022::instruction::approve(
+                    &token_program_id,
+                    &recipient_token_account,
+                    &soulbound_info.key(),
+                    &ctx.accounts.recipient.key(),
+                    &[],
+                    1,
+                )?,
+                &[token_account_info.clone(), soulbound_info.clone(), ctx.accounts.recipient.to_account_info(), token_program.clone()],
+            )?;
+
+            let seeds: &[&[u8]] = &[
+                SOULBOUND_SEED,
+                mint.as_ref(),
+                &[ctx.accounts.soulbound.bump],
+            ];
+            invoke_signed(
+                &spl_token_2022::instruction::freeze_account(
+                    &token_program_id,
+                    &recipient_token_account,
+                    &mint,
+                    &soulbound_info.key(),
+                    &[],
+                )?,
+                &[token_account_info, mint_info, soulbound_info, token_program],
+                &[seeds],
+            )?;
+        }
+
+        let soulbound = &mut ctx.accounts.soulbound;
+        soulbound.holder = Some(ctx.accounts.recipient.key());
+        soulbound.token_account = recipient_token_account;
+
+        emit!(SoulboundMinted {
+            mint,
+            issuer: soulbound.issuer,
+            holder: ctx.accounts.recipient.key(),
+            token_program: token_program_id,
+        });
+        Ok(())
+    }
+
+    /// Lets the issuer burn a soulbound NFT out of the holder's wallet and close its record.
+    pub fn revoke_soulbound_token(ctx: Context<RevokeSoulboundTokenContext>) -> Result<()> {
+        let soulbound = &ctx.accounts.soulbound;
+        if soulbound.holder.is_some() {
+            let token_program_id = ctx.accounts.token_program.key();
+            let mint = ctx.accounts.mint.key();
+            let token_account = ctx.accounts.token_account.key();
+            let token_program = ctx.accounts.token_program.to_account_info();
+            let mint_info = ctx.accounts.mint.to_account_info();
+            let token_account_info = ctx.accounts.token_account.to_account_info();
+            let soulbound_info = soulbound.to_account_info();
+            let seeds: &[&[u8]] = &[
+                SOULBOUND_SEED,
+                mint.as_ref(),
+                &[soulbound.bump],
+            ];
+
+            if token_program_id == token::ID {
+                invoke_signed(
+                    &spl_token_2022::instruction::thaw_account(
+                        &token_program_id,
+                        &token_account,
+                        &mint,
+                        &soulbound_info.key(),
+                        &[],
+                    )?,
+                    &[token_account_info.clone(), mint_info.clone(), soulbound_info.clone(), token_program.clone()],
+                    &[seeds],
+                )?;
+            }
+            // Classic accounts delegated to the PDA at mint; Token-2022 mints name it permanent delegate
+            invoke_signed(
+                &spl_token_2022::instruction::burn(
+                    &token_program_id,
+                    &token_account,
+                    &mint,
+                    &soulbound_info.key(),
+                    &[],
+                    1,
+                )?,
+                &[token_account_info, mint_info, soulbound_info, token_program],
+                &[seeds],
+            )?;
+        }
+
+        emit!(SoulboundRevoked {
+            mint: soulbound.mint,
+            issuer: soulbound.issuer,
+            holder: soulbound.holder,
+        });
+        Ok(())
+    }
+
+    pub fn use_nft(ctx: Context<UseNftContext>, number_of_uses: u64) -> Result<()> {
+        let nft_mint = ctx.accounts.nft_mint.key();
+        let metadata = utils::read_metadata(&ctx.accounts.metadata_account, &nft_mint)?;
+        let uses = metadata.uses.ok_or(MintNftError::NftHasNoUses)?;
+
+        let mut accounts = vec![
+            ctx.accounts.metadata_account.to_account_info(),
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.nft_mint.to_account_info(),
+            ctx.accounts.use_authority.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.associated_token_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ];
+        if let Some(use_authority_record) = &ctx.accounts.use_authority_record {
+            accounts.push(use_authority_record.to_account_info());
+        }
+        if let Some(burner) = &ctx.accounts.burner {
+            accounts.push(burner.to_account_info());
+        }
+
+        invoke(
+            &mpl_instruction::utilize(
+                TOKEN_METADATA_ID,
+                ctx.accounts.metadata_account.key(),
+                ctx.accounts.token_account.key(),
+                nft_mint,
+                ctx.accounts.use_authority_record.as_ref().map(|record| record.key()),
+                ctx.accounts.use_authority.key(),
+                ctx.accounts.owner.key(),
+                ctx.accounts.burner.as_ref().map(|burner| burner.key()),
+                number_of_uses,
+            ),
+            &accounts,
+        )?;
+
+        // Metaplex rejects using more than what is left, so this cannot underflow
+        let remaining = uses.remaining - number_of_uses;
+        emit!(NftUsed {
+            nft_mint,
+            owner: ctx.accounts.owner.key(),
+            use_authority: ctx.accounts.use_authority.key(),
+            number_of_uses,
+            remaining,
+            total: uses.total,
+            burned: uses.use_method == UseMethod::Burn && remaining == 0,
+        });
+        Ok(())
+    }
+
+    pub fn approve_nft_use_authority(ctx: Context<ApproveNftUseAuthorityContext>, number_of_uses: u64) -> Result<()> {
+        invoke(
+            &mpl_instruction::approve_use_authority(
+                TOKEN_METADATA_ID,
+                ctx.accounts.use_authority_record.key(),
+                ctx.accounts.user.key(),
+                ctx.accounts.owner.key(),
+                ctx.accounts.owner.key(),
+                ctx.accounts.owner_token_account.key(),
+                ctx.accounts.metadata_account.key(),
+                ctx.accounts.nft_mint.key(),
+                ctx.accounts.burner.key(),
+                number_of_uses,
+            ),
+            &[
+                ctx.accounts.use_authority_record.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                ctx.accounts.owner_token_account.to_account_info(),
+                ctx.accounts.metadata_account.to_account_info(),
+                ctx.accounts.nft_mint.to_account_info(),
+                ctx.accounts.burner.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        emit!(NftUseAuthorityApproved {
+            nft_mint: ctx.accounts.nft_mint.key(),
+            owner: ctx.accounts.owner.key(),
+            user: ctx.accounts.user.key(),
+            number_of_uses,
+        });
+        Ok(())
+    }
+
+    pub fn revoke_nft_use_authority(ctx: Context<RevokeNftUseAuthorityContext>) -> Result<()> {
+        invoke(
+            &mpl_instruction::revoke_use_authority(
+                TOKEN_METADATA_ID,
+                ctx.accounts.use_authority_record.key(),
+                ctx.accounts.user.key(),
+                ctx.accounts.owner.key(),
+                ctx.accounts.owner_token_account.key(),
+                ctx.accounts.metadata_account.key(),
+                ctx.accounts.nft_mint.key(),
+            ),
+            &[
+                ctx.accounts.use_authority_record.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                ctx.accounts.owner_token_account.to_account_info(),
+                ctx.accounts.nft_mint.to_account_info(),
+                ctx.accounts.metadata_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        emit!(NftUseAuthorityRevoked {
+            nft_mint: ctx.accounts.nft_mint.key(),
+            owner: ctx.accounts.owner.key(),
+            user: ctx.accounts.user.key(),
+        });
+        Ok(())
+    }
+
+    pub fn create_ticket_event(
+        ctx: Context<CreateTicketEventContext>,
+        id: u64,
+        capacity: u32,
+        start_time: i64,
+        gate_staff: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            capacity > 0 && start_time > Clock::get()?.unix_timestamp && gate_staff.len() <= MAX_GATE_STAFF,
+            MintNftError::InvalidTicketEvent
+        );
+
+        let event = &mut ctx.accounts.event;
+        event.organizer = ctx.accounts.organizer.key();
+        event.id = id;
+        event.capacity = capacity;
+        event.tickets_sold = 0;
+        event.start_time = start_time;
+        event.ended = false;
+        event.gate_staff = gate_staff;
+        event.tiers = Vec::new();
+        event.bump = *ctx.bumps.get("event").unwrap();
+
+        emit!(TicketEventCreated {
+            event: event.key(),
+            organizer: event.organizer,
+            capacity,
+            start_time,
+        });
+        Ok(())
+    }
+
+    pub fn set_ticket_gate_staff(ctx: Context<UpdateTicketEventContext>, gate_staff: Vec<Pubkey>) -> Result<()> {
+        require!(gate_staff.len() <= MAX_GATE_STAFF, MintNftError::InvalidTicketEvent);
+        ctx.accounts.event.gate_staff = gate_staff;
+        Ok(())
+    }
+
+    /// Switches the event to post-event mode, after which tickets can no longer be checked in
+    /// and the organizer can withdraw each tier's master edition.
+    pub fn end_ticket_event(ctx: Context<UpdateTicketEventContext>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(!event.ended, MintNftError::TicketEventEnded);
+        event.ended = true;
+
+        emit!(TicketEventEnded {
+            event: event.key(),
+            tickets_sold: event.tickets_sold,
+        });
+        Ok(())
+    }
+
+    pub fn add_ticket_tier(ctx: Context<AddTicketTierContext>, price: u64) -> Result<()> {
+        require!(ctx.accounts.event.tiers.len() < MAX_TICKET_TIERS, MintNftError::TooManyTicketTiers);
+
+        // The event PDA must hold the master edition token to sign prints
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.organizer_token_account.to_account_info(),
+                    to: ctx.accounts.tier_vault.to_account_info(),
+                    authority: ctx.accounts.organizer.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let event = &mut ctx.accounts.event;
+        let master_mint = ctx.accounts.master_mint.key();
+        event.tiers.push(TicketTier {
+            master_mint,
+            price,
+            sold: 0,
+        });
+
+        emit!(TicketTierAdded {
+            event: event.key(),
+            tier: (event.tiers.len() - 1) as u8,
+            master_mint,
+            price,
+        });
+        Ok(())
+    }
+
+    /// Returns the master edition of `tier` to the organizer once the event has ended,
+    /// closing the tier vault.
+    pub fn withdraw_ticket_tier(ctx: Context<WithdrawTicketTierContext>, tier: u8) -> Result<()> {
+        let event = &ctx.accounts.event;
+        let ticket_tier = event.tiers.get(tier as usize).ok_or(MintNftError::InvalidTicketTier)?;
+        require_keys_eq!(ticket_tier.master_mint, ctx.accounts.master_mint.key(), MintNftError::InvalidTicketTier);
+        require!(event.ended, MintNftError::TicketEventNotEnded);
+
+        let organizer = event.organizer;
+        let seeds: &[&[u8]] = &[
+            TICKET_EVENT_SEED,
+            organizer.as_ref(),
+            &event.id.to_le_bytes(),
+            &[event.bump],
+        ];
+        utils::release_escrow(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.tier_vault,
+            &ctx.accounts.organizer_token_account.to_account_info(),
+            &ctx.accounts.organizer.to_account_info(),
+            &event.to_account_info(),
+            &[seeds],
+        )?;
+
+        emit!(TicketTierWithdrawn {
+            event: event.key(),
+            tier,
+            master_mint: ticket_tier.master_mint,
+            sold: ticket_tier.sold,
+        });
+        Ok(())
+    }
+
+    pub fn purchase_ticket(ctx: Context<PurchaseTicketContext>, tier: u8, edition: u64) -> Result<()> {
+        let event = &ctx.accounts.event;
+        let ticket_tier = event.tiers.get(tier as usize).ok_or(MintNftError::InvalidTicketTier)?;
+        require_keys_eq!(ticket_tier.master_mint, ctx.accounts.master_mint.key(), MintNftError::InvalidTicketTier);
+        require!(!event.ended && Clock::get()?.unix_timestamp < event.start_time, MintNftError::TicketSalesClosed);
+        require!(event.tickets_sold < event.capacity, MintNftError::EventSoldOut);
+
+        let price = ticket_tier.price;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.organizer.to_account_info(),
+                },
+            ),
+            price,
+        )?;
+
+        let organizer = event.organizer;
+        let seeds: &[&[u8]] = &[
+            TICKET_EVENT_SEED,
+            organizer.as_ref(),
+            &event.id.to_le_bytes(),
+            &[event.bump],
+        ];
+        invoke_signed(
+            &mpl_instruction::mint_new_edition_from_master_edition_via_token(
+                TOKEN_METADATA_ID,
+                ctx.accounts.edition_metadata_account.key(),
+                ctx.accounts.edition_account.key(),
+                ctx.accounts.master_edition_account.key(),
+                ctx.accounts.edition_mint.key(),
+                ctx.accounts.buyer.key(),
+                ctx.accounts.buyer.key(),
+                event.key(),
+                ctx.accounts.tier_vault.key(),
+                organizer,
+                ctx.accounts.master_metadata_account.key(),
+                ctx.accounts.master_mint.key(),
+                edition,
+            ),
+            &[
+                ctx.accounts.edition_metadata_account.to_account_info(),
+                ctx.accounts.edition_account.to_account_info(),
+                ctx.accounts.master_edition_account.to_account_info(),
+                ctx.accounts.edition_mint.to_account_info(),
+                ctx.accounts.edition_mark_pda.to_account_info(),
+                ctx.accounts.buyer.to_account_info(),
+                event.to_account_info(),
+                ctx.accounts.tier_vault.to_account_info(),
+                ctx.accounts.organizer.to_account_info(),
+                ctx.accounts.master_metadata_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+            ],
+            &[seeds],
+        )?;
+
+        let event = &mut ctx.accounts.event;
+        event.tickets_sold += 1;
+        event.tiers[tier as usize].sold += 1;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.event = event.key();
+        ticket.tier = tier;
+        ticket.edition_mint = ctx.accounts.edition_mint.key();
+        ticket.checked_in = false;
+        ticket.checked_in_at = 0;
+        ticket.checked_in_by = Pubkey::default();
+        ticket.frozen_token_account = None;
+        ticket.bump = *ctx.bumps.get("ticket").unwrap();
+
+        emit!(TicketPurchased {
+            event: event.key(),
+            tier,
+            buyer: ctx.accounts.buyer.key(),
+            edition_mint: ticket.edition_mint,
+            edition,
+            price,
+        });
+        Ok(())
+    }
+
+    /// Gate staff marks a ticket as used. The holder co-signs to prove possession and,
+    /// when `freeze` is set, to delegate the ticket to the event PDA so it can be frozen.
+    pub fn check_in_ticket(ctx: Context<CheckInTicketContext>, freeze: bool) -> Result<()> {
+        let event = &ctx.accounts.event;
+        require!(event.is_gate_staff(&ctx.accounts.gate_staff.key()), MintNftError::NotGateStaff);
+        require!(!event.ended, MintNftError::TicketEventEnded);
+        require!(!ctx.accounts.ticket.checked_in, MintNftError::TicketAlreadyUsed);
+
+        if freeze {
+            let token_program = ctx.accounts.token_program.to_account_info();
+            let event_info = event.to_account_info();
+            let holder_token_account = ctx.accounts.holder_token_account.to_account_info();
+            token::approve(
+                CpiContext::new(
+                    token_program.clone(),
+                    token::Approve {
+                        to: holder_token_account.clone(),
+                        delegate: event_info.clone(),
+                        authority: ctx.accounts.holder.to_account_info(),
+                    },
+                ),
+                1,
+            )?;
+            let organizer = event.organizer;
+            let seeds: &[&[u8]] = &[
+                TICKET_EVENT_SEED,
+                organizer.as_ref(),
+                &event.id.to_le_bytes(),
+                &[event.bump],
+            ];
+            utils::freeze_delegated_account(
+                &event_info,
+                &holder_token_account,
+                &ctx.accounts.edition_account,
+                &ctx.accounts.edition_mint.to_account_info(),
+                &token_program,
+                &ctx.accounts.token_metadata_program,
+                &[seeds],
+            )?;
+        }
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.checked_in = true;
+        ticket.checked_in_at = Clock::get()?.unix_timestamp;
+        ticket.checked_in_by = ctx.accounts.gate_staff.key();
+        if freeze {
+            ticket.frozen_token_account = Some(ctx.accounts.holder_token_account.key());
+        }
+
+        emit!(TicketCheckedIn {
+            event: ticket.event,
+            edition_mint: ticket.edition_mint,
+            holder: ctx.accounts.holder.key(),
+            gate_staff: ctx.accounts.gate_staff.key(),
+            frozen: freeze,
+        });
+        Ok(())
+    }
+
+    /// Thaws a ticket frozen at check-in once the event has ended.
+    pub fn release_ticket(ctx: Context<ReleaseTicketContext>) -> Result<()> {
+        let event = &ctx.accounts.event;
+        require!(event.ended, MintNftError::TicketEventNotEnded);
+
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let holder_token_account = ctx.accounts.holder_token_account.to_account_info();
+        let organizer = event.organizer;
+        let seeds: &[&[u8]] = &[
+            TICKET_EVENT_SEED,
+            organizer.as_ref(),
+            &event.id.to_le_bytes(),
+            &[event.bump],
+        ];
+        utils::thaw_delegated_account(
+            &event.to_account_info(),
+            &holder_token_account,
+            &ctx.accounts.edition_account,
+            &ctx.accounts.edition_mint.to_account_info(),
+            &token_program,
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+        token::revoke(
+            CpiContext::new(
+                token_program,
+                token::Revoke {
+                    source: holder_token_account,
+                    authority: ctx.accounts.holder.to_account_info(),
+                },
+            ),
+        )?;
+
+        ctx.accounts.ticket.frozen_token_account = None;
+        Ok(())
+    }
+
+    /// Registers a membership for an NFT. With `auto_freeze` the holder approves the
+    /// membership PDA as delegate so the crank can freeze the NFT once it expires. SPL
+    /// delegation is not binding: the holder can revoke it, and a transfer leaves the new
+    /// account undelegated, after which `freeze_expired_membership` fails with
+    /// `MembershipDelegateRevoked`. Integrations must rely on `is_membership_active`, not
+    /// on the freeze, to gate access.
+    pub fn create_membership(
+        ctx: Context<CreateMembershipContext>,
+        expires_at: i64,
+        renewal_price: u64,
+        renewal_period: i64,
+        auto_freeze: bool,
+    ) -> Result<()> {
+        require!(renewal_period > 0, MintNftError::InvalidMembershipTerms);
+
+        let nft_mint = ctx.accounts.nft_mint.key();
+        let metadata = utils::read_metadata(&ctx.accounts.metadata_account, &nft_mint)?;
+        require_keys_eq!(metadata.update_authority, ctx.accounts.issuer.key(), MintNftError::NotUpdateAuthority);
+
+        if auto_freeze {
+            token::approve(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Approve {
+                        to: ctx.accounts.holder_token_account.to_account_info(),
+                        delegate: ctx.accounts.membership.to_account_info(),
+                        authority: ctx.accounts.holder.to_account_info(),
+                    },
+                ),
+                1,
+            )?;
+        }
+
+        let membership = &mut ctx.accounts.membership;
+        membership.issuer = ctx.accounts.issuer.key();
+        membership.mint = nft_mint;
+        membership.token_account = ctx.accounts.holder_token_account.key();
+        membership.expires_at = expires_at;
+        membership.renewal_price = renewal_price;
+        membership.renewal_period = renewal_period;
+        membership.auto_freeze = auto_freeze;
+        membership.frozen = false;
+        membership.bump = *ctx.bumps.get("membership").unwrap();
+
+        emit!(MembershipCreated {
+            membership: membership.key(),
+            issuer: membership.issuer,
+            mint: nft_mint,
+            expires_at,
+            auto_freeze,
+        });
+        Ok(())
+    }
+
+    /// Extends the membership by `periods` renewal periods, counted from now if it already lapsed.
+    pub fn renew_membership(ctx: Context<RenewMembershipContext>, periods: u16) -> Result<()> {
+        require!(periods > 0, MintNftError::InvalidMembershipTerms);
+
+        let membership = &ctx.accounts.membership;
+        let paid = membership.renewal_price
+            .checked_mul(periods as u64)
+            .ok_or(MintNftError::NumericalOverflow)?;
+        let renew_from = membership.expires_at.max(Clock::get()?.unix_timestamp);
+        let expires_at = membership.renewal_period
+            .checked_mul(periods as i64)
+            .and_then(|extension| renew_from.checked_add(extension))
+            .ok_or(MintNftError::NumericalOverflow)?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.issuer.to_account_info(),
+                },
+            ),
+            paid,
+        )?;
+
+        let membership = &mut ctx.accounts.membership;
+        membership.expires_at = expires_at;
+
+        emit!(MembershipRenewed {
+            membership: membership.key(),
+            payer: ctx.accounts.payer.key(),
+            periods,
+            paid,
+            expires_at,
+        });
+        Ok(())
+    }
+
+    /// Returns whether the membership has not expired yet. Other programs can CPI into
+    /// this and read the result from the return data.
+    pub fn is_membership_active(ctx: Context<IsMembershipActiveContext>) -> Result<bool> {
+        Ok(ctx.accounts.membership.is_active(Clock::get()?.unix_timestamp))
+    }
+
+    /// Permissionless crank freezing the holder's NFT once an auto-freeze membership expires.
+    /// Takes whichever account holds the NFT now, which must still delegate to the membership.
+    pub fn freeze_expired_membership(ctx: Context<ToggleMembershipFreezeContext>) -> Result<()> {
+        let membership = &ctx.accounts.membership;
+        require!(membership.auto_freeze, MintNftError::AutoFreezeDisabled);
+        require!(!membership.is_active(Clock::get()?.unix_timestamp), MintNftError::MembershipNotExpired);
+        let token_account = &ctx.accounts.token_account;
+        require!(
+            token_account.delegate == COption::Some(membership.key()) && token_account.delegated_amount >= 1,
+            MintNftError::MembershipDelegateRevoked
+        );
+
+        let nft_mint = membership.mint;
+        let seeds: &[&[u8]] = &[
+            MEMBERSHIP_SEED,
+            nft_mint.as_ref(),
+            &[membership.bump],
+        ];
+        utils::freeze_delegated_account(
+            &membership.to_account_info(),
+            &ctx.accounts.token_account.to_account_info(),
+            &ctx.accounts.edition_account,
+            &ctx.accounts.nft_mint.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+
+        let membership = &mut ctx.accounts.membership;
+        membership.token_account = ctx.accounts.token_account.key();
+        membership.frozen = true;
+
+        emit!(MembershipFreezeChanged {
+            membership: membership.key(),
+            token_account: membership.token_account,
+            frozen: true,
+        });
+        Ok(())
+    }
+
+    /// Permissionless counterpart of `freeze_expired_membership` once the membership is renewed.
+    pub fn thaw_renewed_membership(ctx: Context<ToggleMembershipFreezeContext>) -> Result<()> {
+        let membership = &ctx.accounts.membership;
+        require!(membership.is_active(Clock::get()?.unix_timestamp), MintNftError::MembershipExpired);
+
+        let nft_mint = membership.mint;
+        let seeds: &[&[u8]] = &[
+            MEMBERSHIP_SEED,
+            nft_mint.as_ref(),
+            &[membership.bump],
+        ];
+        utils::thaw_delegated_account(
+            &membership.to_account_info(),
+            &ctx.accounts.token_account.to_account_info(),
+            &ctx.accounts.edition_account,
+            &ctx.accounts.nft_mint.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.token_metadata_program,
+            &[seeds],
+        )?;
+
+        let membership = &mut ctx.accounts.membership;
+        membership.frozen = false;
+
+        emit!(MembershipFreezeChanged {
+            membership: membership.key(),
+            token_account: membership.token_account,
+            frozen: false,
+        });
+        Ok(())
+    }
+
+    pub fn initialize_voucher_nonce_page(ctx: Context<InitializeVoucherNoncePageContext>, page: u64) -> Result<()> {
+        let nonce_page = &mut ctx.accounts.nonce_page;
+        nonce_page.creator = ctx.accounts.creator.key();
+        nonce_page.page = page;
+        nonce_page.bump = *ctx.bumps.get("nonce_page").unwrap();
+        Ok(())
+    }
+
+    /// Mints a master edition from a creator-signed voucher. The transaction must carry
+    /// an Ed25519 program instruction over the Borsh-serialized voucher right before this one.
+    pub fn redeem_mint_voucher(ctx: Context<RedeemMintVoucherContext>, voucher: MintVoucher) -> Result<()> {
+        let message = voucher.try_to_vec()?;
+        utils::verify_ed25519_instruction(&ctx.accounts.instructions_sysvar, &voucher.creator, &message)?;
+
+        if let Some(expires_at) = voucher.expires_at {
+            require!(Clock::get()?.unix_timestamp < expires_at, MintNftError::VoucherExpired);
+        }
+        if let Some(recipient) = voucher.recipient {
+            require_keys_eq!(recipient, ctx.accounts.buyer.key(), MintNftError::VoucherRecipientMismatch);
+        }
+        require!(voucher.seller_fee_basis_points <= MAX_BASIS_POINTS, MintNftError::InvalidBasisPoints);
+        ctx.accounts.nonce_page.consume(voucher.nonce)?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.creator.to_account_info(),
+                },
+            ),
+            voucher.price,
+        )?;
+
+        let creator = voucher.creator;
+        let bump = *ctx.bumps.get("lazy_mint_authority").unwrap();
+        let seeds: &[&[u8]] = &[
+            LAZY_MINT_AUTHORITY_SEED,
+            creator.as_ref(),
+            &[bump],
+        ];
+        let buyer = ctx.accounts.buyer.to_account_info();
+        let lazy_mint_authority = ctx.accounts.lazy_mint_authority.to_account_info();
+        let mint = ctx.accounts.mint.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let rent = ctx.accounts.rent.to_account_info();
+        let token_metadata_program = ctx.accounts.token_metadata_program.to_account_info();
+
+        // The creator did not sign this transaction, so it is listed unverified
+        let mut data = utils::metadata_data(
+            vec![Creator { address: creator, verified: false, share: 100 }],
+            voucher.name,
+            voucher.symbol,
+            voucher.uri,
+            None,
+        );
+        data.seller_fee_basis_points = voucher.seller_fee_basis_points;
+        utils::create_metadata_account(
+            &ctx.accounts.metadata_account,
+            &mint,
+            &buyer,
+            &buyer,
+            &lazy_mint_authority,
+            &system_program,
+            &rent,
+            &token_metadata_program,
+            data,
+            true,
+            &[seeds],
+        )?;
+        utils::create_master_edition_account(
+            &ctx.accounts.master_edition_account,
+            &ctx.accounts.metadata_account,
+            &mint,
+            &buyer,
+            &buyer,
+            &lazy_mint_authority,
+            &system_program,
+            &rent,
+            &token_metadata_program,
+            &ctx.accounts.token_program.to_account_info(),
+            voucher.max_supply,
+            &[seeds],
+        )?;
+        utils::update_metadata_account(
+            &ctx.accounts.metadata_account,
+            &lazy_mint_authority,
+            &token_metadata_program,
+            None,
+            None,
+            None,
+            Some(creator),
+            &[seeds],
+        )?;
+
+        emit!(MintVoucherRedeemed {
+            creator,
+            buyer: buyer.key(),
+            mint: mint.key(),
+            nonce: voucher.nonce,
+            price: voucher.price,
+        });
+        Ok(())
+    }
+
+    /// Opens a cross-chain drop: holders of the Ethereum addresses committed to by
+    /// `merkle_root` can each claim one print of the escrowed master edition.
+    pub fn create_eth_drop(ctx: Context<CreateEthDropContext>, id: u64, merkle_root: [u8; 32]) -> Result<()> {
+        // The drop PDA must hold the master edition token to sign prints
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let drop = &mut ctx.accounts.drop;
+        drop.authority = ctx.accounts.authority.key();
+        drop.id = id;
+        drop.merkle_root = merkle_root;
+        drop.master_mint = ctx.accounts.master_mint.key();
+        drop.claimed_count = 0;
+        drop.bump = *ctx.bumps.get("drop").unwrap();
+
+        emit!(EthDropCreated {
+            drop: drop.key(),
+            authority: drop.authority,
+            master_mint: drop.master_mint,
+            merkle_root,
+        });
+        Ok(())
+    }
+
+    /// Claims print `edition` for `eth_address`. The transaction must carry a Secp256k1
+    /// program instruction right before this one, proving `eth_address` signed (via
+    /// `personal_sign`) the drop and recipient keys. The claim PDA makes each address
+    /// claim once; the claimer picks a free edition number, as ticket buyers do.
+    pub fn claim_eth_drop(
+        ctx: Context<ClaimEthDropContext>,
+        eth_address: [u8; 20],
+        edition: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let drop = &ctx.accounts.drop;
+        let recipient = ctx.accounts.recipient.key();
+        let message = [ETH_CLAIM_MESSAGE_PREFIX, drop.key().as_ref(), recipient.as_ref()].concat();
+        utils::verify_secp256k1_instruction(&ctx.accounts.instructions_sysvar, &eth_address, &message)?;
+
+        let leaf = utils::merkle_leaf(&[&eth_address]);
+        require!(utils::verify_merkle_proof(&proof, &drop.merkle_root, leaf), MintNftError::InvalidMerkleProof);
+
+        let authority = drop.authority;
+        let seeds: &[&[u8]] = &[
+            ETH_DROP_SEED,
+            authority.as_ref(),
+            &drop.id.to_le_bytes(),
+            &[drop.bump],
+        ];
+        invoke_signed(
+            &mpl_instruction::mint_new_edition_from_master_edition_via_token(
+                TOKEN_METADATA_ID,
+                ctx.accounts.edition_metadata_account.key(),
+                ctx.accounts.edition_account.key(),
+                ctx.accounts.master_edition_account.key(),
+                ctx.accounts.edition_mint.key(),
+                ctx.accounts.payer.key(),
+                ctx.accounts.payer.key(),
+                drop.key(),
+                ctx.accounts.vault.key(),
+                authority,
+                ctx.accounts.master_metadata_account.key(),
+                ctx.accounts.master_mint.key(),
+                edition,
+            ),
+            &[
+                ctx.accounts.edition_metadata_account.to_account_info(),
+                ctx.accounts.edition_account.to_account_info(),
+                ctx.accounts.master_edition_account.to_account_info(),
+                ctx.accounts.edition_mint.to_account_info(),
+                ctx.accounts.edition_mark_pda.to_account_info(),
+                ctx.accounts.payer.to_account_info(),
+                drop.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.master_metadata_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+            ],
+            &[seeds],
+        )?;
+
+        let drop = &mut ctx.accounts.drop;
+        drop.claimed_count += 1;
+
+        let claim = &mut ctx.accounts.claim;
+        claim.drop = drop.key();
+        claim.eth_address = eth_address;
+        claim.recipient = recipient;
+        claim.edition_mint = ctx.accounts.edition_mint.key();
+        claim.claimed_at = Clock::get()?.unix_timestamp;
+
+        emit!(EthDropClaimed {
+            drop: drop.key(),
+            eth_address,
+            recipient,
+            edition_mint: claim.edition_mint,
+            edition,
+        });
+        Ok(())
+    }
+
+    /// Ends an ETH drop, returning the escrowed master edition to the authority.
+    /// Existing claim PDAs stay, so addresses that claimed cannot claim again from a
+    /// drop recreated under the same id.
+    pub fn close_eth_drop(ctx: Context<CloseEthDropContext>) -> Result<()> {
+        let drop = &ctx.accounts.drop;
+        let authority = drop.authority;
+        let seeds: &[&[u8]] = &[
+            ETH_DROP_SEED,
+            authority.as_ref(),
+            &drop.id.to_le_bytes(),
+            &[drop.bump],
+        ];
+        utils::release_escrow(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.vault,
+            &ctx.accounts.authority_token_account.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &drop.to_account_info(),
+            &[seeds],
+        )?;
+
+        emit!(EthDropClosed {
+            drop: drop.key(),
+            authority,
+            claimed_count: drop.claimed_count,
+        });
+        Ok(())
+    }
+
+    /// Opens an airdrop whose recipients claim their own NFTs. `merkle_root` commits to
+    /// `utils::airdrop_leaf(index, recipient, item)` leaves, as built by `airdrop tree`.
+    /// Without a root the distributor is push-only: the authority sends items
+    /// `0..max_recipients` with `push_airdrop` and nothing can be claimed. Pushed
+    /// print distributors mint edition `item + 1`, since Metaplex editions start at 1.
+    pub fn create_airdrop_distributor(
+        ctx: Context<CreateAirdropDistributorContext>,
+        id: u64,
+        merkle_root: Option<[u8; 32]>,
+        max_recipients: u64,
+        kind: AirdropKind,
+    ) -> Result<()> {
+        require!(
+            max_recipients > 0 && max_recipients <= (AIRDROP_BITMAP_BYTES * 8) as u64,
+            MintNftError::InvalidAirdropDistributor
+        );
+        if let AirdropKind::FreshNft { name, symbol, base_uri } = &kind {
+            // Leave room for the " #<item>" and "<item>.json" suffixes of the largest index
+            require!(
+                name.len() + 7 <= MAX_NAME_LENGTH && symbol.len() <= MAX_SYMBOL_LENGTH && base_uri.len() + 10 <= MAX_URI_LENGTH,
+                MintNftError::InvalidAirdropDistributor
+            );
+        }
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.authority = ctx.accounts.authority.key();
+        distributor.id = id;
+        distributor.merkle_root = merkle_root;
+        distributor.kind = kind;
+        distributor.max_recipients = max_recipients;
+        distributor.claimed_count = 0;
+        distributor.pushed_count = 0;
+        distributor.bump = *ctx.bumps.get("distributor").unwrap();
+
+        ctx.accounts.claim_bitmap.load_init()?.distributor = distributor.key();
+
+        emit!(AirdropDistributorCreated {
+            distributor: distributor.key(),
+            authority: distributor.authority,
+            merkle_root,
+            max_recipients,
+        });
+        Ok(())
+    }
+
+    /// Escrows the master edition a print distributor prints from.
+    pub fn fund_airdrop_distributor(ctx: Context<FundAirdropDistributorContext>) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            1,
+        )
+    }
+
+    /// Returns the master edition escrowed by a print distributor to its authority and
+    /// closes the vault, ending claims and pushes of prints.
+    pub fn withdraw_airdrop_vault(ctx: Context<WithdrawAirdropVaultContext>) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        let authority = distributor.authority;
+        let seeds: &[&[u8]] = &[
+            AIRDROP_DISTRIBUTOR_SEED,
+            authority.as_ref(),
+            &distributor.id.to_le_bytes(),
+            &[distributor.bump],
+        ];
+        utils::release_escrow(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.vault,
+            &ctx.accounts.authority_token_account.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &distributor.to_account_info(),
+            &[seeds],
+        )?;
+
+        emit!(AirdropVaultWithdrawn {
+            distributor: distributor.key(),
+            authority,
+            master_mint: ctx.accounts.master_mint.key(),
+            claimed_count: distributor.claimed_count,
+        });
+        Ok(())
+    }
+
+    /// Claims leaf `index` for its recipient: prints edition `item` of the escrowed master
+    /// edition, or mints fresh NFT number `item`, onto the mint supplied by the payer.
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdropContext>,
+        index: u64,
+        item: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        let recipient = ctx.accounts.recipient.key();
+        let merkle_root = distributor.merkle_root.ok_or(MintNftError::AirdropMethodNotSupported)?;
+        require!(index < distributor.max_recipients, MintNftError::InvalidAirdropIndex);
+        let leaf = utils::airdrop_leaf(index, &recipient, item);
+        require!(utils::verify_merkle_proof(&proof, &merkle_root, leaf), MintNftError::InvalidMerkleProof);
+        ctx.accounts.claim_bitmap.load_mut()?.claim(index)?;
+
+        let authority = distributor.authority;
+        let seeds: &[&[u8]] = &[
+            AIRDROP_DISTRIBUTOR_SEED,
+            authority.as_ref(),
+            &distributor.id.to_le_bytes(),
+            &[distributor.bump],
+        ];
+        let payer = ctx.accounts.payer.to_account_info();
+        let mint = ctx.accounts.mint.to_account_info();
+        let distributor_info = distributor.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let rent = ctx.accounts.rent.to_account_info();
+        let token_metadata_program = ctx.accounts.token_metadata_program.to_account_info();
+
+        match distributor.kind.clone() {
+            AirdropKind::PrintEdition { master_mint } => {
+                let missing = || error!(MintNftError::InvalidAirdropDistributor);
+                let edition_mark_pda = ctx.accounts.edition_mark_pda.as_ref().ok_or_else(missing)?;
+                let master_edition_account = ctx.accounts.master_edition_account.as_ref().ok_or_else(missing)?;
+                let master_metadata_account = ctx.accounts.master_metadata_account.as_ref().ok_or_else(missing)?;
+                let vault = ctx.accounts.vault.as_ref().ok_or_else(missing)?;
+                require_keys_eq!(
+                    ctx.accounts.master_mint.as_ref().ok_or_else(missing)?.key(),
+                    master_mint,
+                    MintNftError::InvalidAirdropDistributor
+                );
+
+                invoke_signed(
+                    &mpl_instruction::mint_new_edition_from_master_edition_via_token(
+                        TOKEN_METADATA_ID,
+                        ctx.accounts.metadata_account.key(),
+                        ctx.accounts.edition_account.key(),
+                        master_edition_account.key(),
+                        mint.key(),
+                        payer.key(),
+                        payer.key(),
+                        distributor_info.key(),
+                        vault.key(),
+                        authority,
+                        master_metadata_account.key(),
+                        master_mint,
+                        item,
+                    ),
+                    &[
+                        ctx.accounts.metadata_account.to_account_info(),
+                        ctx.accounts.edition_account.to_account_info(),
+                        master_edition_account.to_account_info(),
+                        mint.clone(),
+                        edition_mark_pda.to_account_info(),
+                        payer.clone(),
+                        distributor_info.clone(),
+                        vault.to_account_info(),
+                        ctx.accounts.authority.to_account_info(),
+                        master_metadata_account.to_account_info(),
+                        ctx.accounts.token_program.to_account_info(),
+                        system_program,
+                        rent,
+                    ],
+                    &[seeds],
+                )?;
+            }
+            AirdropKind::FreshNft { name, symbol, base_uri } => {
+                let metadata_account = ctx.accounts.metadata_account.to_account_info();
+                utils::create_metadata_account(
+                    &metadata_account,
+                    &mint,
+                    &payer,
+                    &payer,
+                    &distributor_info,
+                    &system_program,
+                    &rent,
+                    &token_metadata_program,
+                    utils::metadata_data(
+                        vec![Creator { address: authority, verified: false, share: 100 }],
+                        format!("{} #{}", name, item),
+                        symbol,
+                        format!("{}{}.json", base_uri, item),
+                        None,
+                    ),
+                    true,
+                    &[seeds],
+                )?;
+                utils::create_master_edition_account(
+                    &ctx.accounts.edition_account,
+                    &metadata_account,
+                    &mint,
+                    &payer,
+                    &payer,
+                    &distributor_info,
+                    &system_program,
+                    &rent,
+                    &token_metadata_program,
+                    &ctx.accounts.token_program.to_account_info(),
+                    0,
+                    &[seeds],
+                )?;
+                utils::update_metadata_account(
+                    &metadata_account,
+                    &distributor_info,
+                    &token_metadata_program,
+                    None,
+                    None,
+                    None,
+                    Some(authority),
+                    &[seeds],
+                )?;
+            }
+        }
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.claimed_count += 1;
+
+        emit!(AirdropClaimed {
+            distributor: distributor.key(),
+            index,
+            recipient,
+            item,
+            mint: mint.key(),
+        });
+        Ok(())
+    }
+
+    /// Admin push airdrop of items `first_item..` to the recipients in `remaining_accounts`,
+    /// one group per recipient: (recipient, recipient ATA, mint, metadata, edition), followed
+    /// by the marker of edition `item + 1` for print distributors. Only push-only distributors accept
+    /// pushes; items must be below `max_recipients` and are marked in the claim bitmap, so
+    /// resending a batch fails instead of minting twice. Recipient ATAs are created if missing.
+    pub fn push_airdrop<'info>(ctx: Context<'_, '_, '_, 'info, PushAirdropContext<'info>>, first_item: u64) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        require!(distributor.merkle_root.is_none(), MintNftError::AirdropMethodNotSupported);
+        let accounts_per_recipient = match distributor.kind {
+            AirdropKind::PrintEdition { .. } => PUSH_AIRDROP_PRINT_ACCOUNTS,
+            AirdropKind::FreshNft { .. } => PUSH_AIRDROP_NFT_ACCOUNTS,
+        };
+        require!(
+            !ctx.remaining_accounts.is_empty()
+                && ctx.remaining_accounts.chunks_exact(accounts_per_recipient).remainder().is_empty(),
+            MintNftError::InvalidPushAirdropAccounts
+        );
+        let count = (ctx.remaining_accounts.len() / accounts_per_recipient) as u64;
+        require!(
+            matches!(first_item.checked_add(count), Some(end) if end <= distributor.max_recipients),
+            MintNftError::InvalidAirdropIndex
+        );
+        if let AirdropKind::PrintEdition { master_mint } = distributor.kind {
+            require!(
+                ctx.accounts.master_mint.as_ref().map(|mint| mint.key()) == Some(master_mint)
+                    && ctx.accounts.master_edition_account.is_some()
+                    && ctx.accounts.master_metadata_account.is_some()
+                    && ctx.accounts.vault.is_some(),
+                MintNftError::InvalidAirdropDistributor
+            );
+        }
+
+        let authority = ctx.accounts.authority.to_account_info();
+        let distributor_info = distributor.to_account_info();
+        let distributor_key = distributor.key();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let rent = ctx.accounts.rent.to_account_info();
+        let token_metadata_program = ctx.accounts.token_metadata_program.to_account_info();
+        let seeds: &[&[u8]] = &[
+            AIRDROP_DISTRIBUTOR_SEED,
+            authority.key.as_ref(),
+            &distributor.id.to_le_bytes(),
+            &[distributor.bump],
+        ];
+
+        let mut claim_bitmap = ctx.accounts.claim_bitmap.load_mut()?;
+        for (offset, accounts) in ctx.remaining_accounts.chunks(accounts_per_recipient).enumerate() {
+            let item = first_item + offset as u64;
+            claim_bitmap.claim(item)?;
+            let (recipient, recipient_token_account, mint, metadata_account, edition_account) =
+                (&accounts[0], &accounts[1], &accounts[2], &accounts[3], &accounts[4]);
+
+            let (mint_address, mint_bump) = Pubkey::find_program_address(
+                &[AIRDROP_MINT_SEED, distributor_key.as_ref(), &item.to_le_bytes()],
+                ctx.program_id,
+            );
+            require_keys_eq!(mint.key(), mint_address, MintNftError::InvalidPushAirdropAccounts);
+            let mint_seeds: &[&[u8]] = &[
+                AIRDROP_MINT_SEED,
+                distributor_key.as_ref(),
+                &item.to_le_bytes(),
+                &[mint_bump],
+            ];
+
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    system_program::CreateAccount {
+                        from: authority.clone(),
+                        to: mint.clone(),
+                    },
+                    &[mint_seeds],
+                ),
+                Rent::get()?.minimum_balance(token::Mint::LEN),
+                token::Mint::LEN as u64,
+                &token::ID,
+            )?;
+            token::initialize_mint2(
+                CpiContext::new(
+                    token_program.clone(),
+                    token::InitializeMint2 { mint: mint.clone() },
+                ),
+                0,
+                &distributor_key,
+                Some(&distributor_key),
+            )?;
+            associated_token::create_idempotent(
+                CpiContext::new(
+                    ctx.accounts.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: authority.clone(),
+                        associated_token: recipient_token_account.clone(),
+                        authority: recipient.clone(),
+                        mint: mint.clone(),
+                        system_program: system_program.clone(),
+                        token_program: token_program.clone(),
+                    },
+                ),
+            )?;
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    token::MintTo {
+                        mint: mint.clone(),
+                        to: recipient_token_account.clone(),
+                        authority: distributor_info.clone(),
+                    },
+                    &[seeds],
+                ),
+                1,
+            )?;
+
+            match &distributor.kind {
+                AirdropKind::PrintEdition { master_mint } => {
+                    // Metaplex rejects edition 0, so item `n` is printed as edition `n + 1`
+                    let edition = item + 1;
+                    let (edition_marker, _) = Pubkey::find_program_address(
+                        &[
+                            PREFIX.as_bytes(),
+                            TOKEN_METADATA_ID.as_ref(),
+                            master_mint.as_ref(),
+                            EDITION.as_bytes(),
+                            (edition / EDITION_MARKER_BIT_SIZE).to_string().as_bytes(),
+                        ],
+                        &TOKEN_METADATA_ID,
+                    );
+                    require_keys_eq!(accounts[5].key(), edition_marker, MintNftError::InvalidPushAirdropAccounts);
+                    let master_edition_account = ctx.accounts.master_edition_account.as_ref().unwrap();
+                    let master_metadata_account = ctx.accounts.master_metadata_account.as_ref().unwrap();
+                    let vault = ctx.accounts.vault.as_ref().unwrap();
+                    invoke_signed(
+                        &mpl_instruction::mint_new_edition_from_master_edition_via_token(
+                            TOKEN_METADATA_ID,
+                            metadata_account.key(),
+                            edition_account.key(),
+                            master_edition_account.key(),
+                            mint.key(),
+                            distributor_key,
+                            authority.key(),
+                            distributor_key,
+                            vault.key(),
+                            authority.key(),
+                            master_metadata_account.key(),
+                            *master_mint,
+                            edition,
+                        ),
+                        &[
+                            metadata_account.clone(),
+                            edition_account.clone(),
+                            master_edition_account.to_account_info(),
+                            mint.clone(),
+                            accounts[5].clone(),
+                            distributor_info.clone(),
+                            authority.clone(),
+                            vault.to_account_info(),
+                            master_metadata_account.to_account_info(),
+                            token_program.clone(),
+                            system_program.clone(),
+                            rent.clone(),
+                        ],
+                        &[seeds],
+                    )?;
+                }
+                AirdropKind::FreshNft { name, symbol, base_uri } => {
+                    // The authority signs, so it is both update authority and a verified creator
+                    utils::create_metadata_account(
+                        metadata_account,
+                        mint,
+                        &distributor_info,
+                        &authority,
+                        &authority,
+                        &system_program,
+                        &rent,
+                        &token_metadata_program,
+                        utils::metadata_data(
+                            vec![Creator { address: authority.key(), verified: true, share: 100 }],
+                            format!("{} #{}", name, item),
+                            symbol.clone(),
+                            format!("{}{}.json", base_uri, item),
+                            None,
+                        ),
+                        true,
+                        &[seeds],
+                    )?;
+                    utils::create_master_edition_account(
+                        edition_account,
+                        metadata_account,
+                        mint,
+                        &distributor_info,
+                        &authority,
+                        &authority,
+                        &system_program,
+                        &rent,
+                        &token_metadata_program,
+                        &token_program,
+                        0,
+                        &[seeds],
+                    )?;
+                }
+            }
+
+            emit!(AirdropPushed {
+                distributor: distributor_key,
+                recipient: recipient.key(),
+                item,
+                mint: mint.key(),
+            });
+        }
+        drop(claim_bitmap);
+
+        ctx.accounts.distributor.pushed_count += count;
+        Ok(())
+    }
+
+    /// Opens a hidden drop. Every item mints with `placeholder_uri`; `provenance_hash` commits
+    /// to the final uri of each index and cannot change afterwards, so `reveal` can only
+    /// publish the uris fixed before minting started.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_drop(
+        ctx: Context<CreateDropContext>,
+        id: u64,
+        name: String,
+        symbol: String,
+        placeholder_uri: String,
+        seller_fee_basis_points: u16,
+        price: u64,
+        items_available: u32,
+        provenance_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            items_available > 0
+                && items_available as usize <= DROP_BITMAP_BYTES * 8
+                && format!("{} #{}", name, items_available - 1).len() <= MAX_NAME_LENGTH
+                && symbol.len() <= MAX_SYMBOL_LENGTH
+                && placeholder_uri.len() <= MAX_URI_LENGTH,
+            MintNftError::InvalidDropConfig
+        );
+        require!(seller_fee_basis_points <= MAX_BASIS_POINTS, MintNftError::InvalidBasisPoints);
+
+        let drop = &mut ctx.accounts.drop;
+        drop.authority = ctx.accounts.authority.key();
+        drop.id = id;
+        drop.name = name;
+        drop.symbol = symbol;
+        drop.placeholder_uri = placeholder_uri;
+        drop.seller_fee_basis_points = seller_fee_basis_points;
+        drop.price = price;
+        drop.items_available = items_available;
+        drop.items_minted = 0;
+        drop.items_revealed = 0;
+        drop.provenance_hash = provenance_hash;
+        drop.bot_protection = BotProtection::default();
+        drop.bump = *ctx.bumps.get("drop").unwrap();
+
+        ctx.accounts.mint_bitmap.load_init()?.drop = drop.key();
+
+        emit!(DropCreated {
+            drop: drop.key(),
+            authority: drop.authority,
+            items_available,
+            provenance_hash,
+        });
+        Ok(())
+    }
+
+    pub fn set_drop_bot_protection(ctx: Context<UpdateDropContext>, bot_protection: BotProtection) -> Result<()> {
+        require!(
+            bot_protection.allowed_programs.len() <= MAX_ALLOWED_PROGRAMS,
+            MintNftError::InvalidDropConfig
+        );
+        ctx.accounts.drop.bot_protection = bot_protection;
+        Ok(())
+    }
+
+    /// Mints a pseudo-randomly picked unminted drop item with the placeholder uri. This only
+    /// stops naive sequential sniping: the seed is predictable, so a bot can simulate a mint and
+    /// submit only when it lands on an index it wants. The drop PDA stays update authority so it
+    /// can sign the reveal.
+    pub fn mint_from_drop(ctx: Context<MintFromDropContext>) -> Result<()> {
+        let drop = &ctx.accounts.drop;
+        utils::check_bot_protection(
+            &ctx.accounts.instructions_sysvar,
+            &drop.bot_protection,
+            <instruction::MintFromDrop as anchor_lang::Discriminator>::DISCRIMINATOR,
+        )?;
+        let seed = utils::mint_order_seed(&ctx.accounts.slot_hashes, &ctx.accounts.buyer.key(), drop.items_minted)?;
+        let index = utils::take_random_index(
+            &mut ctx.accounts.mint_bitmap.load_mut()?.bits,
+            drop.items_available,
+            drop.items_available - drop.items_minted,
+            seed,
+        ).ok_or(MintNftError::DropSoldOut)?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            drop.price,
+        )?;
+
+        let authority = drop.authority;
+        let seeds: &[&[u8]] = &[
+            DROP_CONFIG_SEED,
+            authority.as_ref(),
+            &drop.id.to_le_bytes(),
+            &[drop.bump],
+        ];
+        let buyer = ctx.accounts.buyer.to_account_info();
+        let drop_info = drop.to_account_info();
+        let mint = ctx.accounts.mint.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let rent = ctx.accounts.rent.to_account_info();
+        let token_metadata_program = ctx.accounts.token_metadata_program.to_account_info();
+
+        let mut data = utils::metadata_data(
+            vec![Creator { address: authority, verified: false, share: 100 }],
+            format!("{} #{}", drop.name, index),
+            drop.symbol.clone(),
+            drop.placeholder_uri.clone(),
+            None,
+        );
+        data.seller_fee_basis_points = drop.seller_fee_basis_points;
+        utils::create_metadata_account(
+            &ctx.accounts.metadata_account,
+            &mint,
+            &buyer,
+            &buyer,
+            &drop_info,
+            &system_program,
+            &rent,
+            &token_metadata_program,
+            data,
+            true,
+            &[seeds],
+        )?;
+        utils::create_master_edition_account(
+            &ctx.accounts.master_edition_account,
+            &ctx.accounts.metadata_account,
+            &mint,
+            &buyer,
+            &buyer,
+            &drop_info,
+            &system_program,
+            &rent,
+            &token_metadata_program,
+            &ctx.accounts.token_program.to_account_info(),
+            0,
+            &[seeds],
+        )?;
+
+        let drop = &mut ctx.accounts.drop;
+        drop.items_minted += 1;
+
+        let drop_item = &mut ctx.accounts.drop_item;
+        drop_item.drop = drop.key();
+        drop_item.index = index;
+        drop_item.mint = mint.key();
+        drop_item.revealed = false;
+        drop_item.bump = *ctx.bumps.get("drop_item").unwrap();
+
+        emit!(DropItemMinted {
+            drop: drop.key(),
+            index,
+            mint: mint.key(),
+            buyer: buyer.key(),
+        });
+        Ok(())
+    }
+
+    /// Reveals a batch of drop items. Each entry's uri is checked against the provenance
+    /// hash before the drop PDA writes it to the item's metadata.
+    /// Remaining accounts: (drop item, metadata) for every entry, in order.
+    pub fn reveal<'info>(ctx: Context<'_, '_, '_, 'info, RevealDropContext<'info>>, entries: Vec<RevealEntry>) -> Result<()> {
+        require!(
+            !entries.is_empty() && ctx.remaining_accounts.len() == 2 * entries.len(),
+            MintNftError::InvalidRevealAccounts
+        );
+
+        let drop = &ctx.accounts.drop;
+        let drop_key = drop.key();
+        let drop_info = drop.to_account_info();
+        let token_metadata_program = ctx.accounts.token_metadata_program.to_account_info();
+        let authority = drop.authority;
+        let seeds: &[&[u8]] = &[
+            DROP_CONFIG_SEED,
+            authority.as_ref(),
+            &drop.id.to_le_bytes(),
+            &[drop.bump],
+        ];
+
+        for (entry, accounts) in entries.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let mut drop_item = Account::<DropItem>::try_from(&accounts[0])?;
+            require!(
+                drop_item.drop == drop_key
+                    && drop_item.index == entry.index
+                    && accounts[1].key() == mpl_token_metadata::pda::find_metadata_account(&drop_item.mint).0,
+                MintNftError::InvalidRevealAccounts
+            );
+            require!(!drop_item.revealed, MintNftError::DropItemAlreadyRevealed);
+            require!(
+                utils::verify_merkle_proof(&entry.proof, &drop.provenance_hash, utils::provenance_leaf(entry.index, &entry.uri)),
+                MintNftError::InvalidMerkleProof
+            );
+
+            utils::update_metadata_account(
+                &accounts[1],
+                &drop_info,
+                &token_metadata_program,
+                None,
+                None,
+                Some(entry.uri.clone()),
+                None,
+                &[seeds],
+            )?;
+
+            drop_item.revealed = true;
+            drop_item.exit(ctx.program_id)?;
+
+            emit!(DropItemRevealed {
+                drop: drop_key,
+                index: entry.index,
+                mint: drop_item.mint,
+                uri: entry.uri.clone(),
+            });
+        }
+
+        ctx.accounts.drop.items_revealed += entries.len() as u32;
+        Ok(())
+    }
+
+    /// Opens a burn-to-redeem program for a collection. Every `burns_required` verified
+    /// members burned pay one reward, up to `inventory` rewards; the authority escrows
+    /// the reward tokens for the whole inventory, or the master edition printed from.
+    pub fn create_redemption(
+        ctx: Context<CreateRedemptionContext>,
+        burns_required: u16,
+        inventory: u64,
+        reward: RedemptionReward,
+    ) -> Result<()> {
+        require!(burns_required > 0 && inventory > 0, MintNftError::InvalidRedemption);
+        require_keys_eq!(reward.vault_mint(), ctx.accounts.reward_mint.key(), MintNftError::InvalidRedemption);
+        let deposit = match &reward {
+            RedemptionReward::Token { amount, .. } => {
+                require!(*amount > 0, MintNftError::InvalidRedemption);
+                amount.checked_mul(inventory).ok_or(MintNftError::NumericalOverflow)?
+            }
+            RedemptionReward::PrintEdition { .. } => 1,
+        };
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            deposit,
+        )?;
+
+        let redemption = &mut ctx.accounts.redemption;
+        redemption.authority = ctx.accounts.authority.key();
+        redemption.collection_mint = ctx.accounts.collection_mint.key();
+        redemption.reward = reward;
+        redemption.burns_required = burns_required;
+        redemption.inventory = inventory;
+        redemption.redeemed_count = 0;
+        redemption.bump = *ctx.bumps.get("redemption").unwrap();
+
+        emit!(RedemptionCreated {
+            redemption: redemption.key(),
+            authority: redemption.authority,
+            collection_mint: redemption.collection_mint,
+            burns_required,
+            inventory,
+        });
+        Ok(())
+    }
+
+    /// Adds `inventory` rewards, escrowing the extra reward tokens for token rewards.
+    pub fn restock_redemption(ctx: Context<RestockRedemptionContext>, inventory: u64) -> Result<()> {
+        require!(inventory > 0, MintNftError::InvalidRedemption);
+        if let RedemptionReward::Token { amount, .. } = ctx.accounts.redemption.reward {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.authority_token_account.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                ),
+                amount.checked_mul(inventory).ok_or(MintNftError::NumericalOverflow)?,
+            )?;
+        }
+
+        let redemption = &mut ctx.accounts.redemption;
+        redemption.inventory = redemption.inventory.checked_add(inventory).ok_or(MintNftError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    /// Ends a redemption program, returning the unredeemed reward tokens or the escrowed
+    /// master edition to the authority. Holders reclaim their progress accounts with
+    /// `close_redemption_progress`.
+    pub fn close_redemption(ctx: Context<CloseRedemptionContext>) -> Result<()> {
+        let redemption = &ctx.accounts.redemption;
+        let authority = redemption.authority;
+        let collection_mint = redemption.collection_mint;
+        let returned = ctx.accounts.vault.amount;
+        let seeds: &[&[u8]] = &[
+            REDEMPTION_SEED,
+            authority.as_ref(),
+            collection_mint.as_ref(),
+            &[redemption.bump],
+        ];
+        utils::release_escrow(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.vault,
+            &ctx.accounts.authority_token_account.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &redemption.to_account_info(),
+            &[seeds],
+        )?;
+
+        emit!(RedemptionClosed {
+            redemption: redemption.key(),
+            authority,
+            reward_mint: ctx.accounts.vault.mint,
+            returned,
+        });
+        Ok(())
+    }
+
+    pub fn open_redemption_progress(ctx: Context<OpenRedemptionProgressContext>) -> Result<()> {
+        let progress = &mut ctx.accounts.progress;
+        progress.redemption = ctx.accounts.redemption.key();
+        progress.owner = ctx.accounts.owner.key();
+        progress.burned = 0;
+        progress.bump = *ctx.bumps.get("progress").unwrap();
+        Ok(())
+    }
+
+    /// Closes the caller's progress account, forfeiting any banked burns.
+    pub fn close_redemption_progress(_ctx: Context<CloseRedemptionProgressContext>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Burns a print edition of a verified collection member towards a reward, paying it
+    /// out in the same instruction when the burn completes a redemption. Print rewards
+    /// use the free edition number `reward_edition`; other burns ignore it.
+    pub fn redeem_edition_burn(ctx: Context<RedeemEditionBurnContext>, reward_edition: u64) -> Result<()> {
+        let edition_mint = ctx.accounts.edition_mint.key();
+        let metadata = utils::read_metadata(&ctx.accounts.edition_metadata_account, &edition_mint)?;
+        let collection_mint = ctx.accounts.redemption.collection_mint;
+        require!(
+            matches!(&metadata.collection, Some(collection) if collection.verified && collection.key == collection_mint),
+            MintNftError::CollectionNotVerified
+        );
+        require!(ctx.accounts.redemption.inventory > 0, MintNftError::RedemptionOutOfStock);
+
+        invoke(
+            &mpl_instruction::burn_edition_nft(
+                TOKEN_METADATA_ID,
+                ctx.accounts.edition_metadata_account.key(),
+                ctx.accounts.owner.key(),
+                edition_mint,
+                ctx.accounts.master_edition_mint.key(),
+                ctx.accounts.edition_token_account.key(),
+                ctx.accounts.master_edition_token_account.key(),
+                ctx.accounts.master_edition_account.key(),
+                ctx.accounts.edition_account.key(),
+                ctx.accounts.edition_mark_pda.key(),
+                ctx.accounts.token_program.key(),
+            ),
+            &[
+                ctx.accounts.edition_metadata_account.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.edition_mint.to_account_info(),
+                ctx.accounts.master_edition_mint.to_account_info(),
+                ctx.accounts.edition_token_account.to_account_info(),
+                ctx.accounts.master_edition_token_account.to_account_info(),
+                ctx.accounts.master_edition_account.to_account_info(),
+                ctx.accounts.edition_account.to_account_info(),
+                ctx.accounts.edition_mark_pda.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+        )?;
+
+        let accounts = &mut *ctx.accounts;
+        utils::credit_redemption(
+            &mut accounts.redemption,
+            &mut accounts.progress,
+            &accounts.vault,
+            &accounts.owner.to_account_info(),
+            &accounts.authority.to_account_info(),
+            &accounts.payout,
+            &accounts.token_program.to_account_info(),
+            &accounts.token_metadata_program.to_account_info(),
+            edition_mint,
+            reward_edition,
+        )
+    }
+
+    /// Burns a master edition NFT of a verified collection member towards a reward, paying
+    /// it out in the same instruction when the burn completes a redemption. Print rewards
+    /// use the free edition number `reward_edition`; other burns ignore it.
+    pub fn redeem_master_edition_burn(ctx: Context<RedeemMasterEditionBurnContext>, reward_edition: u64) -> Result<()> {
+        let mint = ctx.accounts.master_edition_mint.key();
+        let metadata = utils::read_metadata(&ctx.accounts.master_edition_metadata, &mint)?;
+        let collection_mint = ctx.accounts.redemption.collection_mint;
+        require!(
+            matches!(&metadata.collection, Some(collection) if collection.verified && collection.key == collection_mint),
+            MintNftError::CollectionNotVerified
+        );
+        require!(ctx.accounts.redemption.inventory > 0, MintNftError::RedemptionOutOfStock);
+
+        invoke(
+            &mpl_instruction::burn_nft(
+                TOKEN_METADATA_ID,
+                ctx.accounts.master_edition_metadata.key(),
+                ctx.accounts.owner.key(),
+                mint,
+                ctx.accounts.master_edition_token_account.key(),
+                ctx.accounts.master_edition_account.key(),
+                ctx.accounts.token_program.key(),
+                Some(ctx.accounts.collection_metadata_account.key()),
+            ),
+            &[
+                ctx.accounts.master_edition_metadata.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.master_edition_mint.to_account_info(),
+                ctx.accounts.master_edition_token_account.to_account_info(),
+                ctx.accounts.master_edition_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.collection_metadata_account.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+        )?;
+
+        let accounts = &mut *ctx.accounts;
+        utils::credit_redemption(
+            &mut accounts.redemption,
+            &mut accounts.progress,
+            &accounts.vault,
+            &accounts.owner.to_account_info(),
+            &accounts.authority.to_account_info(),
+            &accounts.payout,
+            &accounts.token_program.to_account_info(),
+            &accounts.token_metadata_program.to_account_info(),
+            mint,
+            reward_edition,
+        )
+    }
+
+    pub fn create_multisig(ctx: Context<CreateMultisigContext>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+
+        require!(
+            !members.is_empty() && members.len() <= MAX_MULTISIG_MEMBERS,
+            MintNftError::InvalidMultisigMembers
+        );
+        for (i, member) in members.iter().enumerate() {
+            require!(!members[..i].contains(member), MintNftError::InvalidMultisigMembers);
+        }
+        require!(
+            threshold > 0 && threshold as usize <= members.len(),
+            MintNftError::InvalidMultisigThreshold
+        );
+
+        multisig.threshold = threshold;
+        multisig.members = members;
+        multisig.proposal_count = 0;
+        multisig.signer_bump = *ctx.bumps.get("multisig_signer").unwrap();
+
+        msg!("Multisig: {}", multisig.key());
+        msg!("Multisig signer: {}", ctx.accounts.multisig_signer.key());
+        Ok(())
+    }
+
+    pub fn create_multisig_proposal(ctx: Context<CreateMultisigProposalContext>, action: MultisigAction) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+        let proposer = &ctx.accounts.proposer;
+
+        let mut approvals = vec![false; multisig.members.len()];
+        // Proposing counts as the proposer's approval
+        approvals[multisig.member_index(proposer.key).unwrap()] = true;
+
+        proposal.multisig = multisig.key();
+        proposal.proposer = proposer.key();
+        proposal.index = multisig.proposal_count;
+        proposal.action = action;
+        proposal.approvals = approvals;
+        proposal.executed = false;
+
+        multisig.proposal_count += 1;
+
+        msg!("Proposal {} created: {}", proposal.index, proposal.key());
+        Ok(())
+    }
+
+    pub fn approve_multisig_proposal(ctx: Context<ApproveMultisigProposalContext>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        let index = multisig.member_index(ctx.accounts.member.key).unwrap();
+        proposal.approvals[index] = true;
+
+        msg!("Proposal {} approvals: {}/{}", proposal.index, proposal.approval_count(), multisig.threshold);
+        Ok(())
+    }
+
+    pub fn execute_create_metadata_proposal(ctx: Context<ExecuteCreateMetadataProposalContext>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(
+            proposal.approval_count() >= multisig.threshold as usize,
+            MintNftError::ProposalThresholdNotMet
+        );
+        let (mint, creators, name, symbol, uri) = match proposal.action.clone() {
+            MultisigAction::CreateMetadata { mint, creators, name, symbol, uri } => (mint, creators, name, symbol, uri),
+            _ => return err!(MintNftError::ProposalActionMismatch),
+        };
+        require_keys_eq!(mint, ctx.accounts.mint.key(), MintNftError::ProposalAccountMismatch);
+
+        let multisig_key = multisig.key();
+        let seeds: &[&[u8]] = &[
+            MULTISIG_SIGNER_SEED,
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ];
+
+        let multisig_signer = ctx.accounts.multisig_signer.to_account_info();
+        utils::create_metadata_account(
+            &ctx.accounts.metadata_account,
+            &ctx.accounts.mint.to_account_info(),
+            &multisig_signer,
+            &ctx.accounts.payer,
+            &multisig_signer,
+            &ctx.accounts.system_program,
+            &ctx.accounts.rent.to_account_info(),
+            &ctx.accounts.token_metadata_program,
+            utils::metadata_data(creators, name, symbol, uri, None),
+            // Kept mutable so the multisig can approve `UpdateMetadata` proposals later
+            true,
+            &[seeds],
+        )?;
+
+        proposal.executed = true;
+        Ok(())
+    }
+
+    pub fn execute_create_master_edition_proposal(ctx: Context<ExecuteCreateMasterEditionProposalContext>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(
+            proposal.approval_count() >= multisig.threshold as usize,
+            MintNftError::ProposalThresholdNotMet
+        );
+        let (mint, max_supply) = match proposal.action {
+            MultisigAction::CreateMasterEdition { mint, max_supply } => (mint, max_supply),
+            _ => return err!(MintNftError::ProposalActionMismatch),
+        };
+        require_keys_eq!(mint, ctx.accounts.mint.key(), MintNftError::ProposalAccountMismatch);
+
+        let multisig_key = multisig.key();
+        let seeds: &[&[u8]] = &[
+            MULTISIG_SIGNER_SEED,
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ];
+
+        let multisig_signer = ctx.accounts.multisig_signer.to_account_info();
+        utils::create_master_edition_account(
+            &ctx.accounts.master_edition_account,
+            &ctx.accounts.metadata_account,
+            &ctx.accounts.mint.to_account_info(),
+            &multisig_signer,
+            &ctx.accounts.payer,
+            &multisig_signer,
+            &ctx.accounts.system_program,
+            &ctx.accounts.rent.to_account_info(),
+            &ctx.accounts.token_metadata_program,
+            &ctx.accounts.token_program,
+            max_supply,
+            &[seeds],
+        )?;
+
+        proposal.executed = true;
+        Ok(())
+    }
+
+    pub fn execute_mint_token_proposal(ctx: Context<ExecuteMintTokenProposalContext>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(
+            proposal.approval_count() >= multisig.threshold as usize,
+            MintNftError::ProposalThresholdNotMet
+        );
+        let (mint, token_account, amount) = match proposal.action {
+            MultisigAction::MintToken { mint, token_account, amount } => (mint, token_account, amount),
+            _ => return err!(MintNftError::ProposalActionMismatch),
+        };
+        require_keys_eq!(mint, ctx.accounts.mint.key(), MintNftError::ProposalAccountMismatch);
+        require_keys_eq!(token_account, ctx.accounts.token_account.key(), MintNftError::ProposalAccountMismatch);
+
+        let multisig_key = multisig.key();
+        let seeds: &[&[u8]] = &[
+            MULTISIG_SIGNER_SEED,
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ];
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.token_account.to_account_info(),
+                    authority: ctx.accounts.multisig_signer.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        proposal.executed = true;
+        Ok(())
+    }
+
+    pub fn execute_update_metadata_proposal(ctx: Context<ExecuteUpdateMetadataProposalContext>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(
+            proposal.approval_count() >= multisig.threshold as usize,
+            MintNftError::ProposalThresholdNotMet
+        );
+        let (mint, name, symbol, uri, new_update_authority) = match proposal.action.clone() {
+            MultisigAction::UpdateMetadata { mint, name, symbol, uri, new_update_authority } => (mint, name, symbol, uri, new_update_authority),
+            _ => return err!(MintNftError::ProposalActionMismatch),
+        };
+        let (metadata_address, _) = mpl_token_metadata::pda::find_metadata_account(&mint);
+        require_keys_eq!(metadata_address, ctx.accounts.metadata_account.key(), MintNftError::ProposalAccountMismatch);
+
+        let multisig_key = multisig.key();
+        let seeds: &[&[u8]] = &[
+            MULTISIG_SIGNER_SEED,
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ];
+
+        utils::update_metadata_account(
+            &ctx.accounts.metadata_account,
+            &ctx.accounts.multisig_signer,
+            &ctx.accounts.token_metadata_program,
+            name,
+            symbol,
+            uri,
+            new_update_authority,
+            &[seeds],
+        )?;
+
+        proposal.executed = true;
+        Ok(())
+    }
 }
 
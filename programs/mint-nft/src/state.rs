@@ -1,9 +1,802 @@
 use anchor_lang::prelude::*;
 
+use mpl_token_metadata::state::{
+    MAX_NAME_LENGTH,
+    MAX_SYMBOL_LENGTH,
+    MAX_URI_LENGTH,
+};
+
+use crate::{
+    constant::{
+        AIRDROP_BITMAP_BYTES,
+        DROP_BITMAP_BYTES,
+        MAX_ALLOWED_PROGRAMS,
+        MAX_GATE_STAFF,
+        MAX_SWAP_ITEMS,
+        MAX_TICKET_TIERS,
+        NONCES_PER_PAGE,
+    },
+    error::MintNftError,
+};
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct Creator {
     pub address: Pubkey,
     pub verified: bool,
     // In percentages, NOT basis points ;) Watch out!
     pub share: u8,
-}
\ No newline at end of file
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum NftUseMethod {
+    // Burned by Metaplex once the last use is consumed
+    Burn,
+    Multiple,
+    Single,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct NftUses {
+    pub use_method: NftUseMethod,
+    pub total: u64,
+}
+
+impl NftUses {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.total > 0 && (self.use_method != NftUseMethod::Single || self.total == 1),
+            MintNftError::InvalidUses
+        );
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Multisig {
+    pub threshold: u8,
+    pub members: Vec<Pubkey>,
+    pub proposal_count: u64,
+    // Bump of the signer PDA (['MULTISIG_SIGNER', multisig]) used as mint/update authority
+    pub signer_bump: u8,
+}
+
+impl Multisig {
+    pub fn space(member_count: usize) -> usize {
+        8 + 1 + 4 + 32 * member_count + 8 + 1
+    }
+
+    pub fn member_index(&self, key: &Pubkey) -> Option<usize> {
+        self.members.iter().position(|member| member == key)
+    }
+}
+
+#[account]
+pub struct MultisigProposal {
+    pub multisig: Pubkey,
+    pub proposer: Pubkey,
+    pub index: u64,
+    pub action: MultisigAction,
+    // One flag per multisig member, in the same order as `Multisig::members`
+    pub approvals: Vec<bool>,
+    pub executed: bool,
+}
+
+impl MultisigProposal {
+    pub fn space(action: &MultisigAction, member_count: usize) -> usize {
+        8 + 32 + 32 + 8 + action.try_to_vec().unwrap().len() + 4 + member_count + 1
+    }
+
+    pub fn approval_count(&self) -> usize {
+        self.approvals.iter().filter(|approved| **approved).count()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum MultisigAction {
+    CreateMetadata {
+        mint: Pubkey,
+        creators: Vec<Creator>,
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+    CreateMasterEdition {
+        mint: Pubkey,
+        max_supply: u64,
+    },
+    MintToken {
+        mint: Pubkey,
+        token_account: Pubkey,
+        amount: u64,
+    },
+    UpdateMetadata {
+        mint: Pubkey,
+        name: Option<String>,
+        symbol: Option<String>,
+        uri: Option<String>,
+        new_update_authority: Option<Pubkey>,
+    },
+}
+
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct FeeConfig {
+    // None charges fees in lamports, Some(mint) charges them in that SPL token
+    pub fee_mint: Option<Pubkey>,
+    pub mint_fee: u64,
+    pub master_edition_fee: u64,
+    pub print_fee: u64,
+    // Share of every fee paid to the referrer, when one is supplied
+    pub referral_basis_points: u16,
+}
+
+#[account]
+pub struct Treasury {
+    pub admin: Pubkey,
+    pub config: FeeConfig,
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub const LEN: usize = 8 + 32 + (1 + 32) + 8 + 8 + 8 + 2 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeeKind {
+    Mint,
+    MasterEdition,
+    Print,
+}
+
+
+#[account]
+pub struct Listing {
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub price: u64,
+    // None prices the listing in lamports, Some(mint) in that SPL token
+    pub price_mint: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl Listing {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + (1 + 32) + 1;
+}
+
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BidTarget {
+    // Any NFT of this exact mint
+    Mint(Pubkey),
+    // Any NFT whose metadata has this collection mint verified
+    Collection(Pubkey),
+}
+
+impl BidTarget {
+    pub fn key(&self) -> Pubkey {
+        match self {
+            BidTarget::Mint(key) | BidTarget::Collection(key) => *key,
+        }
+    }
+
+    // Seeded next to `key` so a mint bid and a collection bid on the same key get different PDAs
+    pub fn kind_seed(&self) -> [u8; 1] {
+        match self {
+            BidTarget::Mint(_) => [0],
+            BidTarget::Collection(_) => [1],
+        }
+    }
+}
+
+#[account]
+pub struct Bid {
+    pub bidder: Pubkey,
+    pub target: BidTarget,
+    // Lamports escrowed in this account on top of its rent
+    pub amount: u64,
+    pub expires_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl Bid {
+    pub const LEN: usize = 8 + 32 + (1 + 32) + 8 + (1 + 8) + 1;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if now >= expires_at)
+    }
+}
+
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuctionKind {
+    English {
+        reserve_price: u64,
+        min_bid_increment: u64,
+        // A bid placed this close to the end pushes the end back to now + extension_seconds
+        extension_seconds: i64,
+    },
+    Dutch {
+        start_price: u64,
+        end_price: u64,
+        // 0 declines linearly, otherwise the price only drops every step_seconds
+        step_seconds: i64,
+    },
+}
+
+#[account]
+pub struct Auction {
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub kind: AuctionKind,
+    pub start_time: i64,
+    // English: bidding closes. Dutch: the price reaches end_price and stays there
+    pub end_time: i64,
+    // English only. The highest bid is held in lamports by this account
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+    pub bump: u8,
+}
+
+impl Auction {
+    pub const LEN: usize = 8 + 32 + 32 + (1 + 8 + 8 + 8) + 8 + 8 + (1 + 32) + 8 + 1;
+
+    pub fn dutch_price(&self, now: i64) -> Option<u64> {
+        let (start_price, end_price, step_seconds) = match self.kind {
+            AuctionKind::Dutch { start_price, end_price, step_seconds } => (start_price, end_price, step_seconds),
+            _ => return None,
+        };
+        if now <= self.start_time {
+            return Some(start_price);
+        }
+        if now >= self.end_time {
+            return Some(end_price);
+        }
+
+        let duration = (self.end_time - self.start_time) as u128;
+        let mut elapsed = (now - self.start_time) as u128;
+        if step_seconds > 0 {
+            elapsed -= elapsed % step_seconds as u128;
+        }
+        let drop = (start_price - end_price) as u128 * elapsed / duration;
+        Some(start_price - drop as u64)
+    }
+}
+
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SwapItem {
+    pub mint: Pubkey,
+    // 1 for NFTs, any amount for fungible SPL tokens
+    pub amount: u64,
+}
+
+impl SwapItem {
+    pub const LEN: usize = 32 + 8;
+}
+
+#[account]
+pub struct SwapOffer {
+    pub maker: Pubkey,
+    // When set, only this wallet can accept the offer
+    pub taker: Option<Pubkey>,
+    pub id: u64,
+    pub offered: Vec<SwapItem>,
+    // Escrowed in this account on top of its rent
+    pub offered_lamports: u64,
+    pub requested: Vec<SwapItem>,
+    pub requested_lamports: u64,
+    // Number of offered items already moved into escrow
+    pub deposited: u8,
+    pub expires_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl SwapOffer {
+    pub const LEN: usize = 8 + 32 + (1 + 32) + 8
+        + (4 + SwapItem::LEN * MAX_SWAP_ITEMS) + 8
+        + (4 + SwapItem::LEN * MAX_SWAP_ITEMS) + 8
+        + 1 + (1 + 8) + 1;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if now >= expires_at)
+    }
+}
+
+
+#[account]
+pub struct Rental {
+    pub owner: Pubkey,
+    pub nft_mint: Pubkey,
+    // Owner token account this PDA was approved as delegate on
+    pub owner_token_account: Pubkey,
+    pub daily_price: u64,
+    pub max_days: u16,
+    pub renter: Option<Pubkey>,
+    pub renter_token_account: Pubkey,
+    pub rented_until: i64,
+    // Rent paid by the current renter, escrowed here until the rental ends
+    pub paid: u64,
+    pub bump: u8,
+}
+
+impl Rental {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 2 + (1 + 32) + 32 + 8 + 8 + 1;
+}
+
+
+#[account]
+pub struct StakePool {
+    pub authority: Pubkey,
+    // Only NFTs with this collection verified can be staked
+    pub collection_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_vault: Pubkey,
+    // Reward tokens accrued per staked NFT per second
+    pub reward_rate: u64,
+    pub total_staked: u64,
+    // Rewards accrued by a single NFT staked since pool creation
+    pub reward_per_nft: u128,
+    // Rewards accrued by all entries and not yet paid out; the rest of the vault is unallocated
+    pub unpaid_rewards: u128,
+    pub last_update_time: i64,
+    pub bump: u8,
+}
+
+impl StakePool {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 16 + 16 + 8 + 1;
+
+    // Must run before the rate or total staked changes
+    pub fn update(&mut self, now: i64) -> Result<()> {
+        if now > self.last_update_time {
+            let elapsed = (now - self.last_update_time) as u128;
+            let accrued = elapsed * self.reward_rate as u128;
+            self.reward_per_nft = self.reward_per_nft
+                .checked_add(accrued)
+                .ok_or(MintNftError::NumericalOverflow)?;
+            self.unpaid_rewards = accrued
+                .checked_mul(self.total_staked as u128)
+                .and_then(|accrued| self.unpaid_rewards.checked_add(accrued))
+                .ok_or(MintNftError::NumericalOverflow)?;
+            self.last_update_time = now;
+        }
+        Ok(())
+    }
+
+    pub fn unallocated_rewards(&self, vault_balance: u64) -> u64 {
+        // Never above `vault_balance`, so the cast is lossless
+        (vault_balance as u128).saturating_sub(self.unpaid_rewards) as u64
+    }
+}
+
+#[account]
+pub struct StakeEntry {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub nft_mint: Pubkey,
+    pub token_account: Pubkey,
+    pub staked_at: i64,
+    pub reward_per_nft_paid: u128,
+    // Earned but not yet paid out, e.g. when the vault ran dry
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+impl StakeEntry {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 16 + 8 + 1;
+
+    // Moves everything accrued since the last checkpoint into `pending_rewards`
+    pub fn checkpoint(&mut self, pool: &StakePool) -> Result<()> {
+        let accrued = pool.reward_per_nft - self.reward_per_nft_paid;
+        self.pending_rewards = u64::try_from(accrued)
+            .ok()
+            .and_then(|accrued| self.pending_rewards.checked_add(accrued))
+            .ok_or(MintNftError::NumericalOverflow)?;
+        self.reward_per_nft_paid = pool.reward_per_nft;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct FractionVault {
+    pub curator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub share_mint: Pubkey,
+    pub share_supply: u64,
+    // Lamports anyone can pay to take the NFT out of the vault
+    pub reserve_price: u64,
+    pub buyer: Option<Pubkey>,
+    // Shares not yet redeemed against the buyout proceeds
+    pub outstanding_shares: u64,
+    // Buyout lamports not yet paid out to share holders
+    pub remaining_proceeds: u64,
+    pub bump: u8,
+}
+
+impl FractionVault {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + (1 + 32) + 8 + 8 + 1;
+
+    // Pro-rata share of the remaining proceeds; the last redeemer receives any rounding dust
+    pub fn redemption_amount(&self, shares: u64) -> Result<u64> {
+        if shares == self.outstanding_shares {
+            return Ok(self.remaining_proceeds);
+        }
+        let amount = self.remaining_proceeds as u128 * shares as u128 / self.outstanding_shares as u128;
+        u64::try_from(amount).map_err(|_| error!(MintNftError::NumericalOverflow))
+    }
+}
+
+#[account]
+pub struct Loan {
+    pub lender: Pubkey,
+    pub id: u64,
+    // Only NFTs with this collection verified are accepted as collateral
+    pub collection_mint: Pubkey,
+    pub principal: u64,
+    // Annual interest, pro-rated over `duration`
+    pub interest_rate_basis_points: u16,
+    pub duration: i64,
+    pub borrower: Option<Pubkey>,
+    pub nft_mint: Pubkey,
+    // Borrower token account frozen with this PDA as delegate
+    pub collateral_token_account: Pubkey,
+    pub due_time: i64,
+    pub repayment_amount: u64,
+    pub bump: u8,
+}
+
+impl Loan {
+    pub const LEN: usize = 8 + 32 + 8 + 32 + 8 + 2 + 8 + (1 + 32) + 32 + 32 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct SoulboundMint {
+    pub issuer: Pubkey,
+    pub mint: Pubkey,
+    // SPL Token or Token-2022; decides how the NFT is kept in place and burned
+    pub token_program: Pubkey,
+    pub holder: Option<Pubkey>,
+    pub token_account: Pubkey,
+    pub bump: u8,
+}
+
+impl SoulboundMint {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + (1 + 32) + 32 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TicketTier {
+    // Master edition held by the event PDA; each ticket is a print of it
+    pub master_mint: Pubkey,
+    pub price: u64,
+    pub sold: u32,
+}
+
+impl TicketTier {
+    pub const LEN: usize = 32 + 8 + 4;
+}
+
+#[account]
+pub struct TicketEvent {
+    pub organizer: Pubkey,
+    pub id: u64,
+    // Venue capacity across all tiers
+    pub capacity: u32,
+    pub tickets_sold: u32,
+    // Sales close once the event starts
+    pub start_time: i64,
+    // Post-event mode: no more check-ins
+    pub ended: bool,
+    pub gate_staff: Vec<Pubkey>,
+    pub tiers: Vec<TicketTier>,
+    pub bump: u8,
+}
+
+impl TicketEvent {
+    pub const LEN: usize = 8 + 32 + 8 + 4 + 4 + 8 + 1
+        + (4 + 32 * MAX_GATE_STAFF)
+        + (4 + TicketTier::LEN * MAX_TICKET_TIERS)
+        + 1;
+
+    pub fn is_gate_staff(&self, key: &Pubkey) -> bool {
+        *key == self.organizer || self.gate_staff.contains(key)
+    }
+}
+
+#[account]
+pub struct Ticket {
+    pub event: Pubkey,
+    pub tier: u8,
+    pub edition_mint: Pubkey,
+    pub checked_in: bool,
+    pub checked_in_at: i64,
+    pub checked_in_by: Pubkey,
+    // Token account frozen at check-in, if any
+    pub frozen_token_account: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl Ticket {
+    pub const LEN: usize = 8 + 32 + 1 + 32 + 1 + 8 + 32 + (1 + 32) + 1;
+}
+
+#[account]
+pub struct Membership {
+    // Update authority of the NFT metadata when the membership was created
+    pub issuer: Pubkey,
+    pub mint: Pubkey,
+    // Holder token account delegated to this PDA at creation, updated to the account
+    // the crank last froze
+    pub token_account: Pubkey,
+    pub expires_at: i64,
+    // Lamports per renewal period, paid to the issuer
+    pub renewal_price: u64,
+    pub renewal_period: i64,
+    pub auto_freeze: bool,
+    pub frozen: bool,
+    pub bump: u8,
+}
+
+impl Membership {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1 + 1;
+
+    pub fn is_active(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
+}
+
+/// Off-chain mint authorization signed by `creator` with ed25519. The signed message
+/// is the Borsh serialization of this struct.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintVoucher {
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub max_supply: u64,
+    // Lamports paid to the creator on redemption
+    pub price: u64,
+    // None lets anyone redeem the voucher
+    pub recipient: Option<Pubkey>,
+    pub expires_at: Option<i64>,
+    pub nonce: u64,
+}
+
+#[account]
+pub struct VoucherNoncePage {
+    pub creator: Pubkey,
+    pub page: u64,
+    // Bit `nonce % NONCES_PER_PAGE` is set once that voucher nonce is redeemed
+    pub bitmap: [u8; NONCES_PER_PAGE as usize / 8],
+    pub bump: u8,
+}
+
+impl VoucherNoncePage {
+    pub const LEN: usize = 8 + 32 + 8 + NONCES_PER_PAGE as usize / 8 + 1;
+
+    pub fn page_of(nonce: u64) -> u64 {
+        nonce / NONCES_PER_PAGE
+    }
+
+    // Marks `nonce` as used, failing if it already was
+    pub fn consume(&mut self, nonce: u64) -> Result<()> {
+        require!(Self::page_of(nonce) == self.page, MintNftError::InvalidNoncePage);
+        let bit = (nonce % NONCES_PER_PAGE) as usize;
+        let mask = 1u8 << (bit % 8);
+        require!(self.bitmap[bit / 8] & mask == 0, MintNftError::VoucherAlreadyRedeemed);
+        self.bitmap[bit / 8] |= mask;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct EthDrop {
+    pub authority: Pubkey,
+    pub id: u64,
+    // Root over `utils::merkle_leaf(eth_address)` leaves with sorted-pair hashing
+    pub merkle_root: [u8; 32],
+    // Master edition held by the drop PDA; each claim prints an edition of it
+    pub master_mint: Pubkey,
+    pub claimed_count: u64,
+    pub bump: u8,
+}
+
+impl EthDrop {
+    pub const LEN: usize = 8 + 32 + 8 + 32 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct EthClaim {
+    pub drop: Pubkey,
+    pub eth_address: [u8; 20],
+    pub recipient: Pubkey,
+    pub edition_mint: Pubkey,
+    pub claimed_at: i64,
+}
+
+impl EthClaim {
+    pub const LEN: usize = 8 + 32 + 20 + 32 + 32 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum AirdropKind {
+    // Leaf item is the edition number printed from the escrowed master edition
+    PrintEdition {
+        master_mint: Pubkey,
+    },
+    // Leaf item is the token number: minted as "<name> #<item>" with uri "<base_uri><item>.json"
+    FreshNft {
+        name: String,
+        symbol: String,
+        base_uri: String,
+    },
+}
+
+#[account]
+pub struct AirdropDistributor {
+    pub authority: Pubkey,
+    pub id: u64,
+    // Root over `utils::airdrop_leaf(index, recipient, item)` leaves with sorted-pair hashing;
+    // push-only distributors have none
+    pub merkle_root: Option<[u8; 32]>,
+    pub kind: AirdropKind,
+    // Bounds leaf indices of claims, and items of pushes
+    pub max_recipients: u64,
+    pub claimed_count: u64,
+    pub pushed_count: u64,
+    pub bump: u8,
+}
+
+impl AirdropDistributor {
+    pub const LEN: usize = 8 + 32 + 8 + (1 + 32)
+        + 1 + (4 + MAX_NAME_LENGTH) + (4 + MAX_SYMBOL_LENGTH) + (4 + MAX_URI_LENGTH)
+        + 8 + 8 + 8 + 1;
+}
+
+#[account(zero_copy)]
+pub struct AirdropClaimBitmap {
+    pub distributor: Pubkey,
+    // Bit `index` is set once leaf `index` has been claimed, or item `index` pushed
+    pub bits: [u8; AIRDROP_BITMAP_BYTES],
+}
+
+impl AirdropClaimBitmap {
+    pub const LEN: usize = 8 + 32 + AIRDROP_BITMAP_BYTES;
+
+    // Marks leaf or item `index` as claimed, failing if it already was
+    pub fn claim(&mut self, index: u64) -> Result<()> {
+        let bit = usize::try_from(index).map_err(|_| MintNftError::InvalidAirdropIndex)?;
+        require!(bit < AIRDROP_BITMAP_BYTES * 8, MintNftError::InvalidAirdropIndex);
+        let mask = 1u8 << (bit % 8);
+        require!(self.bits[bit / 8] & mask == 0, MintNftError::AirdropAlreadyClaimed);
+        self.bits[bit / 8] |= mask;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct DropConfig {
+    pub authority: Pubkey,
+    pub id: u64,
+    // Items are named "<name> #<index>"
+    pub name: String,
+    pub symbol: String,
+    // Shown by every item until it is revealed
+    pub placeholder_uri: String,
+    pub seller_fee_basis_points: u16,
+    pub price: u64,
+    pub items_available: u32,
+    pub items_minted: u32,
+    pub items_revealed: u32,
+    // Merkle root over `utils::provenance_leaf(index, final uri)`, committed before the first mint
+    pub provenance_hash: [u8; 32],
+    pub bot_protection: BotProtection,
+    pub bump: u8,
+}
+
+impl DropConfig {
+    pub const LEN: usize = 8 + 32 + 8
+        + (4 + MAX_NAME_LENGTH) + (4 + MAX_SYMBOL_LENGTH) + (4 + MAX_URI_LENGTH)
+        + 2 + 8 + 4 + 4 + 4 + 32 + BotProtection::LEN + 1;
+}
+
+// Instructions-sysvar checks run by `mint_from_drop`; all off by default
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct BotProtection {
+    // The mint must be a top-level instruction, not a CPI from another program
+    pub require_top_level: bool,
+    // Every instruction in the transaction must target the mint's own dependencies
+    // (token, associated token, system, compute budget, Metaplex) or `allowed_programs`
+    pub restrict_programs: bool,
+    // At most one drop mint per transaction
+    pub single_mint_per_tx: bool,
+    pub allowed_programs: Vec<Pubkey>,
+}
+
+impl BotProtection {
+    pub const LEN: usize = 1 + 1 + 1 + 4 + 32 * MAX_ALLOWED_PROGRAMS;
+}
+
+#[account(zero_copy)]
+pub struct DropMintBitmap {
+    pub drop: Pubkey,
+    // Bit `index` is set once item `index` has been minted
+    pub bits: [u8; DROP_BITMAP_BYTES],
+}
+
+impl DropMintBitmap {
+    pub const LEN: usize = 8 + 32 + DROP_BITMAP_BYTES;
+}
+
+#[account]
+pub struct DropItem {
+    pub drop: Pubkey,
+    pub index: u32,
+    pub mint: Pubkey,
+    pub revealed: bool,
+    pub bump: u8,
+}
+
+impl DropItem {
+    pub const LEN: usize = 8 + 32 + 4 + 32 + 1 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RevealEntry {
+    pub index: u32,
+    pub uri: String,
+    pub proof: Vec<[u8; 32]>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum RedemptionReward {
+    // `amount` of `mint` paid from the vault per redemption
+    Token {
+        mint: Pubkey,
+        amount: u64,
+    },
+    // One print of the escrowed master edition per redemption
+    PrintEdition {
+        master_mint: Pubkey,
+    },
+}
+
+impl RedemptionReward {
+    // Mint held by the redemption vault
+    pub fn vault_mint(&self) -> Pubkey {
+        match self {
+            RedemptionReward::Token { mint, .. } => *mint,
+            RedemptionReward::PrintEdition { master_mint } => *master_mint,
+        }
+    }
+}
+
+#[account]
+pub struct Redemption {
+    pub authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub reward: RedemptionReward,
+    // Collection NFTs a holder burns per reward
+    pub burns_required: u16,
+    // Rewards still available
+    pub inventory: u64,
+    pub redeemed_count: u64,
+    pub bump: u8,
+}
+
+impl Redemption {
+    pub const LEN: usize = 8 + 32 + 32 + (1 + 32 + 8) + 2 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct RedemptionProgress {
+    pub redemption: Pubkey,
+    pub owner: Pubkey,
+    // Burns not yet exchanged for a reward
+    pub burned: u16,
+    pub bump: u8,
+}
+
+impl RedemptionProgress {
+    pub const LEN: usize = 8 + 32 + 32 + 2 + 1;
+}
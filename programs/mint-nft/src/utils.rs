@@ -0,0 +1,1102 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        ed25519_program,
+        instruction::Instruction,
+        keccak,
+        program::invoke_signed,
+        secp256k1_program,
+        sysvar::instructions as sysvar_instructions,
+    },
+    system_program,
+};
+
+use anchor_spl::token::{
+    self,
+    TokenAccount,
+};
+
+use mpl_token_metadata::{
+    instruction as mpl_instruction,
+    state::{
+        DataV2,
+        Metadata,
+        TokenMetadataAccount,
+        UseMethod,
+        Uses,
+    },
+    ID as TOKEN_METADATA_ID
+};
+
+use crate::{
+    constant::*,
+    context::{
+        FeeAccounts,
+        RedemptionPayoutAccounts,
+    },
+    error::*,
+    event::*,
+    state::*,
+};
+
+pub fn metadata_data(creators: Vec<Creator>, name: String, symbol: String, uri: String, uses: Option<NftUses>) -> DataV2 {
+    let token_creators = creators.iter()
+        .map(|creator| mpl_token_metadata::state::Creator {
+            address: creator.address,
+            verified: creator.verified,
+            share: creator.share,
+        })
+        .collect();
+
+    DataV2 {
+        name,
+        symbol,
+        uri,
+        seller_fee_basis_points: 0,
+        creators: Some(token_creators),
+        collection: None,
+        uses: uses.map(|uses| Uses {
+            use_method: match uses.use_method {
+                NftUseMethod::Burn => UseMethod::Burn,
+                NftUseMethod::Multiple => UseMethod::Multiple,
+                NftUseMethod::Single => UseMethod::Single,
+            },
+            remaining: uses.total,
+            total: uses.total,
+        }),
+    }
+}
+
+// Metaplex pads name/symbol/uri with null bytes when they are stored on-chain
+pub fn trim_metadata_string(value: &str) -> String {
+    value.trim_matches(char::from(0)).to_string()
+}
+
+/// Creates the Metaplex metadata account of `mint`.
+/// `signer_seeds` is empty unless the mint or update authority is a PDA of this program.
+#[allow(clippy::too_many_arguments)]
+pub fn create_metadata_account<'info>(
+    metadata_account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    mint_authority: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    update_authority: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &AccountInfo<'info>,
+    token_metadata_program: &AccountInfo<'info>,
+    data: DataV2,
+    is_mutable: bool,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let instruction = mpl_instruction::create_metadata_accounts_v3(
+        TOKEN_METADATA_ID,
+        metadata_account.key(),
+        mint.key(),
+        mint_authority.key(),
+        payer.key(),
+        update_authority.key(),
+        data.name,
+        data.symbol,
+        data.uri,
+        data.creators,
+        data.seller_fee_basis_points,
+        true,
+        is_mutable,
+        data.collection,
+        data.uses,
+        None,
+    );
+    msg!("DEBUG: create metadata {:?}", instruction);
+
+    invoke_signed(
+        &instruction,
+        &[
+            metadata_account.clone(),
+            mint.clone(),
+            mint_authority.clone(),
+            payer.clone(),
+            update_authority.clone(),
+            system_program.clone(),
+            rent.clone(),
+            token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Creates the master edition account of `mint`, which also hands the mint
+/// and freeze authority of `mint` over to Metaplex.
+#[allow(clippy::too_many_arguments)]
+pub fn create_master_edition_account<'info>(
+    master_edition_account: &AccountInfo<'info>,
+    metadata_account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    mint_authority: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    update_authority: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &AccountInfo<'info>,
+    token_metadata_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    max_supply: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let instruction = mpl_instruction::create_master_edition_v3(
+        TOKEN_METADATA_ID,
+        master_edition_account.key(),
+        mint.key(),
+        update_authority.key(),
+        mint_authority.key(),
+        metadata_account.key(),
+        payer.key(),
+        Some(max_supply),
+    );
+
+    msg!("DEBUG: Create master edition instruction {:?}", instruction);
+
+    invoke_signed(
+        &instruction,
+        &[
+            metadata_account.clone(),
+            mint.clone(),
+            mint_authority.clone(),
+            payer.clone(),
+            update_authority.clone(),
+            system_program.clone(),
+            rent.clone(),
+            token_metadata_program.clone(),
+            master_edition_account.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Rewrites name/symbol/uri of an existing metadata account, keeping creators,
+/// royalties, collection and uses as they are on-chain.
+#[allow(clippy::too_many_arguments)]
+pub fn update_metadata_account<'info>(
+    metadata_account: &AccountInfo<'info>,
+    update_authority: &AccountInfo<'info>,
+    token_metadata_program: &AccountInfo<'info>,
+    name: Option<String>,
+    symbol: Option<String>,
+    uri: Option<String>,
+    new_update_authority: Option<Pubkey>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metadata = Metadata::from_account_info(metadata_account)?;
+
+    let data = DataV2 {
+        name: name.unwrap_or_else(|| trim_metadata_string(&metadata.data.name)),
+        symbol: symbol.unwrap_or_else(|| trim_metadata_string(&metadata.data.symbol)),
+        uri: uri.unwrap_or_else(|| trim_metadata_string(&metadata.data.uri)),
+        seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+        creators: metadata.data.creators,
+        collection: metadata.collection,
+        uses: metadata.uses,
+    };
+
+    let instruction = mpl_instruction::update_metadata_accounts_v2(
+        TOKEN_METADATA_ID,
+        metadata_account.key(),
+        update_authority.key(),
+        new_update_authority,
+        Some(data),
+        None,
+        None,
+    );
+
+    invoke_signed(
+        &instruction,
+        &[
+            metadata_account.clone(),
+            update_authority.clone(),
+            token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Charges the configured `kind` fee to `payer`, splitting off the referral share
+/// when a referrer account is supplied, and emits `FeeCollected`.
+pub fn collect_fee<'info>(fee: &FeeAccounts<'info>, kind: FeeKind, payer: &AccountInfo<'info>) -> Result<()> {
+    let config = &fee.treasury.config;
+    let amount = match kind {
+        FeeKind::Mint => config.mint_fee,
+        FeeKind::MasterEdition => config.master_edition_fee,
+        FeeKind::Print => config.print_fee,
+    };
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let (treasury_amount, referral_amount, referrer) = match config.fee_mint {
+        None => {
+            let referral_amount = match &fee.referrer {
+                Some(referrer) => {
+                    require_keys_neq!(referrer.key(), payer.key(), MintNftError::SelfReferral);
+                    basis_points_of(amount, config.referral_basis_points)?
+                }
+                None => 0,
+            };
+            let treasury_amount = amount - referral_amount;
+
+            system_program::transfer(
+                CpiContext::new(
+                    fee.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.clone(),
+                        to: fee.treasury.to_account_info(),
+                    },
+                ),
+                treasury_amount,
+            )?;
+            if let (Some(referrer), true) = (&fee.referrer, referral_amount > 0) {
+                system_program::transfer(
+                    CpiContext::new(
+                        fee.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: payer.clone(),
+                            to: referrer.to_account_info(),
+                        },
+                    ),
+                    referral_amount,
+                )?;
+            }
+            (treasury_amount, referral_amount, fee.referrer.as_ref().map(|referrer| referrer.key()))
+        }
+        Some(fee_mint) => {
+            let (payer_token_account, treasury_token_account) = match (&fee.payer_token_account, &fee.treasury_token_account) {
+                (Some(payer_token_account), Some(treasury_token_account)) => (payer_token_account, treasury_token_account),
+                _ => return err!(MintNftError::InvalidFeeAccounts),
+            };
+            require_keys_eq!(payer_token_account.mint, fee_mint, MintNftError::InvalidFeeAccounts);
+            require_keys_eq!(treasury_token_account.mint, fee_mint, MintNftError::InvalidFeeAccounts);
+            require_keys_eq!(treasury_token_account.owner, fee.treasury.key(), MintNftError::InvalidFeeAccounts);
+
+            let referral_amount = match &fee.referrer_token_account {
+                Some(referrer_token_account) => {
+                    require_keys_eq!(referrer_token_account.mint, fee_mint, MintNftError::InvalidFeeAccounts);
+                    require_keys_neq!(referrer_token_account.owner, payer.key(), MintNftError::SelfReferral);
+                    basis_points_of(amount, config.referral_basis_points)?
+                }
+                None => 0,
+            };
+            let treasury_amount = amount - referral_amount;
+
+            token::transfer(
+                CpiContext::new(
+                    fee.token_program.to_account_info(),
+                    token::Transfer {
+                        from: payer_token_account.to_account_info(),
+                        to: treasury_token_account.to_account_info(),
+                        authority: payer.clone(),
+                    },
+                ),
+                treasury_amount,
+            )?;
+            if let (Some(referrer_token_account), true) = (&fee.referrer_token_account, referral_amount > 0) {
+                token::transfer(
+                    CpiContext::new(
+                        fee.token_program.to_account_info(),
+                        token::Transfer {
+                            from: payer_token_account.to_account_info(),
+                            to: referrer_token_account.to_account_info(),
+                            authority: payer.clone(),
+                        },
+                    ),
+                    referral_amount,
+                )?;
+            }
+            (treasury_amount, referral_amount, fee.referrer_token_account.as_ref().map(|account| account.owner))
+        }
+    };
+
+    emit!(FeeCollected {
+        kind,
+        payer: payer.key(),
+        fee_mint: config.fee_mint,
+        treasury_amount,
+        referrer,
+        referral_amount,
+    });
+    Ok(())
+}
+
+pub fn basis_points_of(amount: u64, basis_points: u16) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(basis_points as u128)
+        .ok_or(MintNftError::NumericalOverflow)?
+        / MAX_BASIS_POINTS as u128;
+    Ok(value as u64)
+}
+
+/// Principal plus annual interest pro-rated over `duration` seconds.
+pub fn loan_repayment_amount(principal: u64, interest_rate_basis_points: u16, duration: i64) -> Result<u64> {
+    let duration = u128::try_from(duration).map_err(|_| error!(MintNftError::InvalidLoanTerms))?;
+    let interest = (principal as u128)
+        .checked_mul(interest_rate_basis_points as u128)
+        .and_then(|value| value.checked_mul(duration))
+        .ok_or(MintNftError::NumericalOverflow)?
+        / (MAX_BASIS_POINTS as u128 * SECONDS_PER_YEAR as u128);
+    u64::try_from(interest)
+        .ok()
+        .and_then(|interest| principal.checked_add(interest))
+        .ok_or_else(|| error!(MintNftError::NumericalOverflow))
+}
+
+/// Where the proceeds of a sale are paid from.
+pub enum PaymentSource<'a, 'info> {
+    // Lamports moved by the system program out of a signing wallet
+    Wallet {
+        from: &'a AccountInfo<'info>,
+        system_program: &'a AccountInfo<'info>,
+    },
+    // Lamports debited directly from an account owned by this program
+    ProgramAccount {
+        from: &'a AccountInfo<'info>,
+    },
+    // SPL tokens moved out of a token account by its (possibly PDA) authority
+    TokenAccount {
+        from: &'a AccountInfo<'info>,
+        authority: &'a AccountInfo<'info>,
+        token_program: &'a AccountInfo<'info>,
+        signer_seeds: &'a [&'a [&'a [u8]]],
+    },
+}
+
+impl<'a, 'info> PaymentSource<'a, 'info> {
+    pub fn pay(&self, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        match self {
+            PaymentSource::Wallet { from, system_program } => system_program::transfer(
+                CpiContext::new(
+                    (*system_program).clone(),
+                    system_program::Transfer {
+                        from: (*from).clone(),
+                        to: to.clone(),
+                    },
+                ),
+                amount,
+            ),
+            PaymentSource::ProgramAccount { from } => {
+                **from.try_borrow_mut_lamports()? = from.lamports()
+                    .checked_sub(amount)
+                    .ok_or(MintNftError::NumericalOverflow)?;
+                **to.try_borrow_mut_lamports()? = to.lamports()
+                    .checked_add(amount)
+                    .ok_or(MintNftError::NumericalOverflow)?;
+                Ok(())
+            }
+            PaymentSource::TokenAccount { from, authority, token_program, signer_seeds } => token::transfer(
+                CpiContext::new_with_signer(
+                    (*token_program).clone(),
+                    token::Transfer {
+                        from: (*from).clone(),
+                        to: to.clone(),
+                        authority: (*authority).clone(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            ),
+        }
+    }
+}
+
+/// Reads the Metaplex metadata of `nft_mint`, checking the account is its metadata PDA.
+pub fn read_metadata(metadata_account: &AccountInfo, nft_mint: &Pubkey) -> Result<Metadata> {
+    let (metadata_address, _) = mpl_token_metadata::pda::find_metadata_account(nft_mint);
+    require_keys_eq!(metadata_address, metadata_account.key(), MintNftError::InvalidMetadataAccount);
+
+    Ok(Metadata::from_account_info(metadata_account)?)
+}
+
+/// Pays `seller_fee_basis_points` of `price` to the metadata creators by share and
+/// returns the total paid. `creator_accounts` holds one account per creator, in
+/// metadata order: the creator wallet for lamport sales, or a token account of
+/// `price_mint` owned by the creator for SPL sales.
+pub fn pay_royalties<'info>(
+    source: &PaymentSource<'_, 'info>,
+    metadata: &Metadata,
+    price: u64,
+    price_mint: Option<Pubkey>,
+    creator_accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let creators = match &metadata.data.creators {
+        Some(creators) if metadata.data.seller_fee_basis_points > 0 => creators,
+        _ => return Ok(0),
+    };
+    require!(creator_accounts.len() >= creators.len(), MintNftError::InvalidCreatorAccounts);
+
+    let royalty = basis_points_of(price, metadata.data.seller_fee_basis_points)?;
+    let mut paid = 0u64;
+
+    for (creator, creator_account) in creators.iter().zip(creator_accounts.iter()) {
+        match price_mint {
+            None => {
+                require_keys_eq!(creator_account.key(), creator.address, MintNftError::InvalidCreatorAccounts);
+            }
+            Some(price_mint) => {
+                let token_account = Account::<TokenAccount>::try_from(creator_account)?;
+                require_keys_eq!(token_account.owner, creator.address, MintNftError::InvalidCreatorAccounts);
+                require_keys_eq!(token_account.mint, price_mint, MintNftError::InvalidCreatorAccounts);
+            }
+        }
+
+        let amount = (royalty as u128)
+            .checked_mul(creator.share as u128)
+            .ok_or(MintNftError::NumericalOverflow)?
+            / 100;
+        source.pay(creator_account, amount as u64)?;
+        paid += amount as u64;
+    }
+
+    Ok(paid)
+}
+
+/// Moves the whole balance of a PDA-owned escrow token account to `destination`
+/// and closes the escrow, returning its rent to `rent_destination`.
+pub fn release_escrow<'info>(
+    token_program: &AccountInfo<'info>,
+    escrow_token_account: &Account<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    rent_destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            token::Transfer {
+                from: escrow_token_account.to_account_info(),
+                to: destination.clone(),
+                authority: authority.clone(),
+            },
+            signer_seeds,
+        ),
+        escrow_token_account.amount,
+    )?;
+
+    token::close_account(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            token::CloseAccount {
+                account: escrow_token_account.to_account_info(),
+                destination: rent_destination.clone(),
+                authority: authority.clone(),
+            },
+            signer_seeds,
+        ),
+    )
+}
+
+pub fn swap_escrow_address(offer: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[SWAP_ESCROW_SEED, offer.as_ref(), mint.as_ref()],
+        &crate::ID,
+    ).0
+}
+
+/// Freezes `token_account` through Metaplex with `delegate` (a PDA of this program,
+/// already approved as the token account delegate) so its owner cannot move the NFT.
+pub fn freeze_delegated_account<'info>(
+    delegate: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    edition: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    token_metadata_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let instruction = mpl_instruction::freeze_delegated_account(
+        TOKEN_METADATA_ID,
+        delegate.key(),
+        token_account.key(),
+        edition.key(),
+        mint.key(),
+    );
+
+    invoke_signed(
+        &instruction,
+        &[
+            delegate.clone(),
+            token_account.clone(),
+            edition.clone(),
+            mint.clone(),
+            token_program.clone(),
+            token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Reverses `freeze_delegated_account`.
+pub fn thaw_delegated_account<'info>(
+    delegate: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    edition: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    token_metadata_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let instruction = mpl_instruction::thaw_delegated_account(
+        TOKEN_METADATA_ID,
+        delegate.key(),
+        token_account.key(),
+        edition.key(),
+        mint.key(),
+    );
+
+    invoke_signed(
+        &instruction,
+        &[
+            delegate.clone(),
+            token_account.clone(),
+            edition.clone(),
+            mint.clone(),
+            token_program.clone(),
+            token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Pays the staker whatever the vault can cover of `entry.pending_rewards`, signed by
+/// the pool PDA. Anything the vault cannot cover stays pending for a later claim.
+pub fn pay_stake_rewards<'info>(
+    pool: &mut Account<'info, StakePool>,
+    entry: &mut StakeEntry,
+    reward_vault: &Account<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<u64> {
+    let amount = entry.pending_rewards.min(reward_vault.amount);
+    if amount > 0 {
+        let seeds: &[&[u8]] = &[
+            STAKE_POOL_SEED,
+            pool.collection_mint.as_ref(),
+            &[pool.bump],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                token::Transfer {
+                    from: reward_vault.to_account_info(),
+                    to: destination.clone(),
+                    authority: pool.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+        entry.pending_rewards -= amount;
+        pool.unpaid_rewards -= amount as u128;
+    }
+
+    emit!(StakeRewardsClaimed {
+        pool: pool.key(),
+        owner: entry.owner,
+        nft_mint: entry.nft_mint,
+        amount,
+        pending_rewards: entry.pending_rewards,
+    });
+    Ok(amount)
+}
+
+/// Checks that the instruction right before the current one is an Ed25519 program
+/// instruction verifying a single signature by `signer` over exactly `message`, with
+/// all offsets pointing into that instruction's own data.
+pub fn verify_ed25519_instruction(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = sysvar_instructions::load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, MintNftError::InvalidVoucherSignature);
+    let instruction = sysvar_instructions::load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    require_keys_eq!(instruction.program_id, ed25519_program::ID, MintNftError::InvalidVoucherSignature);
+
+    // Layout: [count: u8, padding: u8] followed by one 14-byte offsets record
+    let data = &instruction.data;
+    require!(data.len() >= 16 && data[0] == 1, MintNftError::InvalidVoucherSignature);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_instruction_index = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_instruction_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_instruction_index = read_u16(14);
+    require!(
+        signature_instruction_index == u16::MAX
+            && public_key_instruction_index == u16::MAX
+            && message_instruction_index == u16::MAX,
+        MintNftError::InvalidVoucherSignature
+    );
+
+    let public_key = data.get(public_key_offset..public_key_offset + 32)
+        .ok_or(MintNftError::InvalidVoucherSignature)?;
+    let signed_message = data.get(message_offset..message_offset + message_size)
+        .ok_or(MintNftError::InvalidVoucherSignature)?;
+    require!(
+        public_key == signer.as_ref() && signed_message == message,
+        MintNftError::InvalidVoucherSignature
+    );
+    Ok(())
+}
+
+/// Checks that the instruction right before the current one is a Secp256k1 program
+/// instruction recovering `eth_address` from a signature over exactly `message`, with
+/// all offsets pointing into that instruction's own data.
+pub fn verify_secp256k1_instruction(
+    instructions_sysvar: &AccountInfo,
+    eth_address: &[u8; 20],
+    message: &[u8],
+) -> Result<()> {
+    let current_index = sysvar_instructions::load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, MintNftError::InvalidEthSignature);
+    let signature_index = current_index - 1;
+    let instruction = sysvar_instructions::load_instruction_at_checked(signature_index as usize, instructions_sysvar)?;
+    require_keys_eq!(instruction.program_id, secp256k1_program::ID, MintNftError::InvalidEthSignature);
+
+    // Layout: [count: u8] followed by one 11-byte offsets record
+    let data = &instruction.data;
+    require!(data.len() >= 12 && data[0] == 1, MintNftError::InvalidEthSignature);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let eth_address_offset = read_u16(4);
+    let message_offset = read_u16(7);
+    let message_size = read_u16(9);
+    require!(
+        data[3] as u16 == signature_index && data[6] as u16 == signature_index && data[11] as u16 == signature_index,
+        MintNftError::InvalidEthSignature
+    );
+
+    let signed_address = data.get(eth_address_offset..eth_address_offset + 20)
+        .ok_or(MintNftError::InvalidEthSignature)?;
+    let signed_message = data.get(message_offset..message_offset + message_size)
+        .ok_or(MintNftError::InvalidEthSignature)?;
+    require!(
+        signed_address == eth_address.as_ref() && signed_message == message,
+        MintNftError::InvalidEthSignature
+    );
+    Ok(())
+}
+
+/// Verifies `leaf` against `root` using keccak256 over sorted pairs, as in OpenZeppelin's MerkleProof.
+/// `leaf` must come from `merkle_leaf`; inner nodes are hashed with `MERKLE_NODE_PREFIX`.
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| merkle_node(&node, sibling));
+    computed == *root
+}
+
+/// Inner node over two children, sorted so proofs need no direction bits.
+pub fn merkle_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[&[MERKLE_NODE_PREFIX], left, right]).0
+}
+
+/// Leaf over the concatenation of `parts`, prefixed with `MERKLE_LEAF_PREFIX`.
+pub fn merkle_leaf(parts: &[&[u8]]) -> [u8; 32] {
+    let mut input: Vec<&[u8]> = vec![&[MERKLE_LEAF_PREFIX]];
+    input.extend_from_slice(parts);
+    keccak::hashv(&input).0
+}
+
+/// Leaf committed to by an airdrop distributor's Merkle root. Built off-chain by `airdrop tree`.
+pub fn airdrop_leaf(index: u64, recipient: &Pubkey, item: u64) -> [u8; 32] {
+    merkle_leaf(&[&index.to_le_bytes(), recipient.as_ref(), &item.to_le_bytes()])
+}
+
+/// Leaf of a drop's provenance hash: item `index` is revealed with `uri`.
+pub fn provenance_leaf(index: u32, uri: &str) -> [u8; 32] {
+    merkle_leaf(&[&index.to_le_bytes(), uri.as_bytes()])
+}
+
+/// Mixes the newest SlotHashes entry with the minter and a counter into a seed for picking
+/// the next mint index. All three are known before the transaction is signed, so the result
+/// can be simulated; it is not a substitute for a commit-reveal scheme.
+pub fn mint_order_seed(slot_hashes: &AccountInfo, minter: &Pubkey, counter: u32) -> Result<u64> {
+    let data = slot_hashes.try_borrow_data()?;
+    // Layout: entry count (u64) followed by (slot: u64, hash: [u8; 32]) entries, newest first
+    let newest = data.get(8..48).ok_or(MintNftError::InvalidSlotHashes)?;
+    let hash = keccak::hashv(&[newest, minter.as_ref(), &counter.to_le_bytes()]).0;
+    Ok(u64::from_le_bytes(hash[..8].try_into().unwrap()))
+}
+
+/// Picks the `seed % remaining`-th unset bit among the first `items_available` bits of
+/// `bits`, sets it and returns its index. `remaining` is the number of unset bits.
+pub fn take_random_index(bits: &mut [u8], items_available: u32, remaining: u32, seed: u64) -> Option<u32> {
+    if remaining == 0 {
+        return None;
+    }
+    let mut target = (seed % remaining as u64) as u32;
+    for (byte_index, byte) in bits.iter_mut().enumerate() {
+        let first = byte_index as u32 * 8;
+        if first >= items_available {
+            break;
+        }
+        // Bits at or past `items_available` never count as free
+        let valid = (items_available - first).min(8);
+        let mut free = !*byte & (0xffu16 >> (8 - valid)) as u8;
+        if target >= free.count_ones() {
+            target -= free.count_ones();
+            continue;
+        }
+        for _ in 0..target {
+            free &= free - 1;
+        }
+        let bit = free.trailing_zeros();
+        *byte |= 1 << bit;
+        return Some(first + bit);
+    }
+    None
+}
+
+/// Enforces a drop's bot protection against the instructions of the current transaction.
+/// `mint_discriminator` identifies the protected mint instruction.
+pub fn check_bot_protection(
+    instructions_sysvar: &AccountInfo,
+    protection: &BotProtection,
+    mint_discriminator: [u8; 8],
+) -> Result<()> {
+    if !(protection.require_top_level || protection.restrict_programs || protection.single_mint_per_tx) {
+        return Ok(());
+    }
+
+    let current_index = sysvar_instructions::load_current_index_checked(instructions_sysvar)? as usize;
+    // The sysvar data starts with the instruction count as a u16
+    let count = {
+        let data = instructions_sysvar.try_borrow_data()?;
+        u16::from_le_bytes([data[0], data[1]]) as usize
+    };
+    let instructions = (0..count)
+        .map(|index| sysvar_instructions::load_instruction_at_checked(index, instructions_sysvar))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    check_bot_protection_rules(protection, current_index, &instructions, mint_discriminator)
+}
+
+/// Rules behind `check_bot_protection`, over the top-level instructions of a transaction.
+pub fn check_bot_protection_rules(
+    protection: &BotProtection,
+    current_index: usize,
+    instructions: &[Instruction],
+    mint_discriminator: [u8; 8],
+) -> Result<()> {
+    let is_mint = |instruction: &Instruction| {
+        instruction.program_id == crate::ID && instruction.data.starts_with(&mint_discriminator)
+    };
+
+    // Under CPI the current top-level instruction belongs to the calling program
+    if protection.require_top_level {
+        require!(
+            matches!(instructions.get(current_index), Some(instruction) if is_mint(instruction)),
+            MintNftError::MintNotTopLevel
+        );
+    }
+    if protection.restrict_programs {
+        let builtin = [
+            crate::ID,
+            COMPUTE_BUDGET_PROGRAM_ID,
+            system_program::ID,
+            token::ID,
+            anchor_spl::associated_token::ID,
+            TOKEN_METADATA_ID,
+        ];
+        for instruction in instructions {
+            require!(
+                builtin.contains(&instruction.program_id) || protection.allowed_programs.contains(&instruction.program_id),
+                MintNftError::DisallowedProgram
+            );
+        }
+    }
+    if protection.single_mint_per_tx {
+        require!(
+            instructions.iter().filter(|instruction| is_mint(instruction)).count() <= 1,
+            MintNftError::MultipleMintsInTransaction
+        );
+    }
+    Ok(())
+}
+
+/// Counts one burn towards the owner's next reward and, once `burns_required` burns
+/// are banked, pays it out: `amount` reward tokens from the vault, or print
+/// `reward_edition` of the escrowed master edition onto the owner's fresh mint.
+#[allow(clippy::too_many_arguments)]
+pub fn credit_redemption<'info>(
+    redemption: &mut Account<'info, Redemption>,
+    progress: &mut Account<'info, RedemptionProgress>,
+    vault: &Account<'info, TokenAccount>,
+    owner: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    payout: &RedemptionPayoutAccounts<'info>,
+    token_program: &AccountInfo<'info>,
+    token_metadata_program: &AccountInfo<'info>,
+    burned_mint: Pubkey,
+    reward_edition: u64,
+) -> Result<()> {
+    progress.burned = progress.burned.checked_add(1).ok_or(MintNftError::NumericalOverflow)?;
+    emit!(RedemptionBurned {
+        redemption: redemption.key(),
+        owner: owner.key(),
+        mint: burned_mint,
+        burned: progress.burned,
+    });
+    if progress.burned < redemption.burns_required {
+        return Ok(());
+    }
+
+    require!(redemption.inventory > 0, MintNftError::RedemptionOutOfStock);
+    progress.burned -= redemption.burns_required;
+    redemption.inventory -= 1;
+    redemption.redeemed_count += 1;
+
+    let redemption_authority = redemption.authority;
+    let collection_mint = redemption.collection_mint;
+    let seeds: &[&[u8]] = &[
+        REDEMPTION_SEED,
+        redemption_authority.as_ref(),
+        collection_mint.as_ref(),
+        &[redemption.bump],
+    ];
+    let redemption_info = redemption.to_account_info();
+    let missing = || error!(MintNftError::InvalidRedemptionPayout);
+
+    let (reward_mint, amount) = match redemption.reward.clone() {
+        RedemptionReward::Token { mint, amount } => {
+            let reward_token_account = payout.reward_token_account.as_ref().ok_or_else(missing)?;
+            require_keys_eq!(reward_token_account.mint, mint, MintNftError::InvalidRedemptionPayout);
+            token::transfer(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    token::Transfer {
+                        from: vault.to_account_info(),
+                        to: reward_token_account.to_account_info(),
+                        authority: redemption_info,
+                    },
+                    &[seeds],
+                ),
+                amount,
+            )?;
+            (mint, amount)
+        }
+        RedemptionReward::PrintEdition { master_mint } => {
+            let new_mint = payout.new_mint.as_ref().ok_or_else(missing)?;
+            let new_metadata_account = payout.new_metadata_account.as_ref().ok_or_else(missing)?;
+            let new_edition_account = payout.new_edition_account.as_ref().ok_or_else(missing)?;
+            let edition_mark_pda = payout.edition_mark_pda.as_ref().ok_or_else(missing)?;
+            let master_edition_account = payout.master_edition_account.as_ref().ok_or_else(missing)?;
+            let master_metadata_account = payout.master_metadata_account.as_ref().ok_or_else(missing)?;
+
+            invoke_signed(
+                &mpl_instruction::mint_new_edition_from_master_edition_via_token(
+                    TOKEN_METADATA_ID,
+                    new_metadata_account.key(),
+                    new_edition_account.key(),
+                    master_edition_account.key(),
+                    new_mint.key(),
+                    owner.key(),
+                    owner.key(),
+                    redemption_info.key(),
+                    vault.key(),
+                    redemption_authority,
+                    master_metadata_account.key(),
+                    master_mint,
+                    reward_edition,
+                ),
+                &[
+                    new_metadata_account.to_account_info(),
+                    new_edition_account.to_account_info(),
+                    master_edition_account.to_account_info(),
+                    new_mint.to_account_info(),
+                    edition_mark_pda.to_account_info(),
+                    owner.clone(),
+                    redemption_info,
+                    vault.to_account_info(),
+                    authority.clone(),
+                    master_metadata_account.to_account_info(),
+                    token_program.clone(),
+                    payout.system_program.to_account_info(),
+                    payout.rent.to_account_info(),
+                    token_metadata_program.clone(),
+                ],
+                &[seeds],
+            )?;
+            (new_mint.key(), 1)
+        }
+    };
+
+    emit!(RedemptionPaid {
+        redemption: redemption.key(),
+        owner: owner.key(),
+        reward_mint,
+        amount,
+    });
+    Ok(())
+}
+
+/// Forwards the rent Metaplex refunded to `owner` while burning (the token account,
+/// metadata and edition) to `rent_recipient`, when one is given.
+pub fn forward_reclaimed_rent<'info>(
+    owner: &AccountInfo<'info>,
+    rent_recipient: Option<&UncheckedAccount<'info>>,
+    lamports_before: u64,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let rent_recipient = match rent_recipient {
+        Some(rent_recipient) => rent_recipient,
+        None => return Ok(()),
+    };
+    let reclaimed = owner.lamports().saturating_sub(lamports_before);
+    if reclaimed == 0 || rent_recipient.key() == owner.key() {
+        return Ok(());
+    }
+    system_program::transfer(
+        CpiContext::new(
+            system_program.clone(),
+            system_program::Transfer {
+                from: owner.clone(),
+                to: rent_recipient.to_account_info(),
+            },
+        ),
+        reclaimed,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loan_repayment_amount_pro_rates_annual_interest() {
+        // 10% APR for a full year, then for 30 days
+        assert_eq!(loan_repayment_amount(1_000_000_000, 1_000, SECONDS_PER_YEAR).unwrap(), 1_100_000_000);
+        assert_eq!(loan_repayment_amount(1_000_000_000, 1_000, 30 * SECONDS_PER_DAY).unwrap(), 1_008_219_178);
+        assert_eq!(loan_repayment_amount(1_000_000_000, 0, SECONDS_PER_YEAR).unwrap(), 1_000_000_000);
+    }
+
+    #[test]
+    fn loan_repayment_amount_rejects_overflow() {
+        // Intermediate product exceeds u128
+        assert!(loan_repayment_amount(u64::MAX, u16::MAX, i64::MAX).is_err());
+        // Interest fits u128 but principal + interest exceeds u64
+        assert!(loan_repayment_amount(u64::MAX, MAX_BASIS_POINTS, SECONDS_PER_YEAR).is_err());
+        assert!(loan_repayment_amount(u64::MAX / 2, MAX_BASIS_POINTS, SECONDS_PER_YEAR).is_ok());
+    }
+
+    #[test]
+    fn loan_repayment_amount_rejects_negative_duration() {
+        assert!(loan_repayment_amount(1_000, 1_000, -1).is_err());
+    }
+
+    #[test]
+    fn merkle_leaf_cannot_pose_as_an_inner_node() {
+        let left = provenance_leaf(0, "a");
+        let right = provenance_leaf(1, "b");
+        let node = merkle_node(&left, &right);
+        let (first, second) = if left <= right { (left, right) } else { (right, left) };
+
+        // The 64-byte preimage of the node, hashed as a leaf, lands elsewhere
+        assert_ne!(merkle_leaf(&[&first, &second]), node);
+        assert!(verify_merkle_proof(&[right], &node, left));
+    }
+
+    #[test]
+    fn take_random_index_mints_every_index_exactly_once() {
+        for items_available in 1..=70u32 {
+            for salt in 0..4u64 {
+                // Sized for the largest case, so trailing bits must be ignored
+                let mut bits = [0u8; 9];
+                let mut minted = vec![false; items_available as usize];
+                for counter in 0..items_available {
+                    let seed = keccak::hashv(&[&salt.to_le_bytes(), &counter.to_le_bytes()]).0;
+                    let index = take_random_index(
+                        &mut bits,
+                        items_available,
+                        items_available - counter,
+                        u64::from_le_bytes(seed[..8].try_into().unwrap()),
+                    ).unwrap();
+                    assert!(!minted[index as usize], "index {} minted twice", index);
+                    minted[index as usize] = true;
+                }
+                assert!(minted.iter().all(|minted| *minted));
+                assert_eq!(take_random_index(&mut bits, items_available, 0, 0), None);
+            }
+        }
+    }
+
+    #[test]
+    fn take_random_index_ignores_bits_past_items_available() {
+        // Only indexes 0..3 exist; with index 0 and 1 taken, every seed must land on 2
+        let mut bits = [0b0000_0011u8, 0];
+        for seed in 0..8 {
+            let mut copy = bits;
+            assert_eq!(take_random_index(&mut copy, 3, 1, seed), Some(2));
+        }
+        assert_eq!(take_random_index(&mut bits, 3, 1, 0), Some(2));
+        assert_eq!(bits, [0b0000_0111, 0]);
+    }
+
+    fn bot_protection_instruction(program_id: Pubkey, data: &[u8]) -> Instruction {
+        Instruction { program_id, accounts: vec![], data: data.to_vec() }
+    }
+
+    #[test]
+    fn bot_protection_rejects_cpi_mints() {
+        let mint = [7u8; 8];
+        let protection = BotProtection { require_top_level: true, ..Default::default() };
+        let caller = Pubkey::new_unique();
+        let top_level = [bot_protection_instruction(crate::ID, &mint)];
+        let via_cpi = [bot_protection_instruction(caller, &mint)];
+
+        assert!(check_bot_protection_rules(&protection, 0, &top_level, mint).is_ok());
+        assert_eq!(
+            check_bot_protection_rules(&protection, 0, &via_cpi, mint).unwrap_err(),
+            MintNftError::MintNotTopLevel.into()
+        );
+        assert!(check_bot_protection_rules(&BotProtection::default(), 0, &via_cpi, mint).is_ok());
+    }
+
+    #[test]
+    fn bot_protection_rejects_disallowed_programs() {
+        let mint = [7u8; 8];
+        let other = Pubkey::new_unique();
+        let instructions = [
+            bot_protection_instruction(COMPUTE_BUDGET_PROGRAM_ID, &[2]),
+            bot_protection_instruction(other, &[]),
+            bot_protection_instruction(crate::ID, &mint),
+        ];
+        let mut protection = BotProtection { restrict_programs: true, ..Default::default() };
+        assert_eq!(COMPUTE_BUDGET_PROGRAM_ID.to_string(), "ComputeBudget111111111111111111111111111111");
+
+        assert_eq!(
+            check_bot_protection_rules(&protection, 2, &instructions, mint).unwrap_err(),
+            MintNftError::DisallowedProgram.into()
+        );
+        protection.allowed_programs.push(other);
+        assert!(check_bot_protection_rules(&protection, 2, &instructions, mint).is_ok());
+    }
+
+    #[test]
+    fn bot_protection_rejects_multiple_mints() {
+        let mint = [7u8; 8];
+        let protection = BotProtection { single_mint_per_tx: true, ..Default::default() };
+        let other_instruction = bot_protection_instruction(crate::ID, &[8u8; 8]);
+        let one_mint = [bot_protection_instruction(crate::ID, &mint), other_instruction.clone()];
+        let two_mints = [bot_protection_instruction(crate::ID, &mint), bot_protection_instruction(crate::ID, &mint)];
+
+        assert!(check_bot_protection_rules(&protection, 0, &one_mint, mint).is_ok());
+        assert_eq!(
+            check_bot_protection_rules(&protection, 1, &two_mints, mint).unwrap_err(),
+            MintNftError::MultipleMintsInTransaction.into()
+        );
+    }
+}
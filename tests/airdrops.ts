@@ -0,0 +1,310 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID, createMint, getAccount, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, mintTo,
+} from '@solana/spl-token';
+import { Edition, EditionMarker, Metadata } from '@metaplex-foundation/mpl-token-metadata';
+import { ComputeBudgetProgram } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import {
+  airdrop, buildMerkleTree, createNft, findEditionMarkAddress, findMasterEditionAddress, findMetadataAddress, merkleLeaf, TestNft,
+  TOKEN_METADATA_PROGRAM_ID,
+} from "./utils";
+
+// Mirrors `utils::airdrop_leaf`: merkleLeaf(index le u64 || recipient || item le u64)
+const airdropLeaf = (index: number, recipient: anchor.web3.PublicKey, item: number) => merkleLeaf(
+  new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+  recipient.toBuffer(),
+  new anchor.BN(item).toArrayLike(Buffer, "le", 8),
+);
+
+describe("airdrops", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  let root: anchor.web3.Keypair;
+  let authority: anchor.web3.Keypair;
+  let payer: anchor.web3.Keypair;
+  const recipients = [0, 1, 2].map(() => anchor.web3.Keypair.generate().publicKey);
+
+  const findDistributor = (id: number) => findProgramAddressSync(
+    [Buffer.from("AIRDROP_DISTRIBUTOR"), authority.publicKey.toBuffer(), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+    program.programId,
+  )[0];
+  const findBitmap = (distributor: anchor.web3.PublicKey) =>
+    findProgramAddressSync([Buffer.from("AIRDROP_BITMAP"), distributor.toBuffer()], program.programId)[0];
+  const findVault = (distributor: anchor.web3.PublicKey) =>
+    findProgramAddressSync([Buffer.from("AIRDROP_VAULT"), distributor.toBuffer()], program.programId)[0];
+
+  const createDistributor = async (id: number, merkleRoot: number[] | null, kind: any) => {
+    const distributor = findDistributor(id);
+    await program.methods.createAirdropDistributor(new anchor.BN(id), merkleRoot, new anchor.BN(recipients.length), kind).accounts({
+      authority: authority.publicKey,
+      distributor,
+      claimBitmap: findBitmap(distributor),
+    }).signers([authority]).rpc();
+    return distributor;
+  };
+
+  const claim = async (
+    distributor: anchor.web3.PublicKey,
+    index: number,
+    item: number,
+    proof: number[][],
+    master: TestNft = null,
+  ) => {
+    const recipient = recipients[index];
+    const mint = await createMint(connection, payer, payer.publicKey, payer.publicKey, 0);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, payer, mint, recipient);
+    await mintTo(connection, payer, mint, tokenAccount.address, payer, 1);
+
+    await program.methods.claimAirdrop(new anchor.BN(index), new anchor.BN(item), proof).accounts({
+      payer: payer.publicKey,
+      recipient,
+      authority: authority.publicKey,
+      distributor,
+      claimBitmap: findBitmap(distributor),
+      mint,
+      recipientTokenAccount: tokenAccount.address,
+      metadataAccount: findMetadataAddress(mint),
+      editionAccount: findMasterEditionAddress(mint),
+      editionMarkPda: master ? findEditionMarkAddress(master.mint, item) : null,
+      masterMint: master ? master.mint : null,
+      masterEditionAccount: master ? master.masterEdition : null,
+      masterMetadataAccount: master ? master.metadata : null,
+      vault: master ? findVault(distributor) : null,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([payer]).rpc();
+
+    return { mint, tokenAccount: tokenAccount.address };
+  };
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    authority = anchor.web3.Keypair.generate();
+    payer = anchor.web3.Keypair.generate();
+    await airdrop(connection, authority.publicKey);
+    await airdrop(connection, payer.publicKey, 2);
+  });
+
+  describe("fresh NFTs", () => {
+    const items = [7, 8, 9];
+    const tree = buildMerkleTree(recipients.map((recipient, index) => airdropLeaf(index, recipient, items[index])));
+    let distributor: anchor.web3.PublicKey;
+
+    before(async () => {
+      distributor = await createDistributor(1, tree.root, {
+        freshNft: { name: "Airdrop", symbol: "DROP", baseUri: "https://example.com/drop/" },
+      });
+    });
+
+    it('Claim mints the leaf item to the recipient', async () => {
+      const { mint, tokenAccount } = await claim(distributor, 1, items[1], tree.proof(1));
+
+      assert.equal(Number((await getAccount(connection, tokenAccount)).amount), 1);
+      const metadata = await Metadata.fromAccountAddress(connection, findMetadataAddress(mint));
+      assert.equal(metadata.data.name.replace(/\0/g, ''), "Airdrop #8");
+      assert.equal(metadata.data.uri.replace(/\0/g, ''), "https://example.com/drop/8.json");
+      assert.ok(metadata.updateAuthority.equals(authority.publicKey));
+      assert.equal((await program.account.airdropDistributor.fetch(distributor)).claimedCount.toNumber(), 1);
+    });
+
+    it('A leaf cannot be claimed twice', async () => {
+      try {
+        await claim(distributor, 1, items[1], tree.proof(1));
+        assert.fail("second claim should fail");
+      } catch (e) {
+        assert.equal(e.error.errorCode.code, "AirdropAlreadyClaimed");
+      }
+    });
+
+    it('Reject a proof for a different item', async () => {
+      try {
+        await claim(distributor, 0, items[2], tree.proof(0));
+        assert.fail("wrong item should fail");
+      } catch (e) {
+        assert.equal(e.error.errorCode.code, "InvalidMerkleProof");
+      }
+    });
+  });
+
+  describe("print editions", () => {
+    let master: TestNft;
+    let distributor: anchor.web3.PublicKey;
+    let tree: ReturnType<typeof buildMerkleTree>;
+
+    before(async () => {
+      master = await createNft(connection, root, authority.publicKey, 0, null, null, 10);
+      tree = buildMerkleTree(recipients.map((recipient, index) => airdropLeaf(index, recipient, index + 1)));
+      distributor = await createDistributor(2, tree.root, { printEdition: { masterMint: master.mint } });
+
+      await program.methods.fundAirdropDistributor().accounts({
+        authority: authority.publicKey,
+        distributor,
+        masterMint: master.mint,
+        authorityTokenAccount: master.tokenAccount,
+        vault: findVault(distributor),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([authority]).rpc();
+    });
+
+    it('Claim prints the leaf edition to the recipient', async () => {
+      const { tokenAccount } = await claim(distributor, 2, 3, tree.proof(2), master);
+
+      assert.equal(Number((await getAccount(connection, tokenAccount)).amount), 1);
+      const bitmap = await program.account.airdropClaimBitmap.fetch(findBitmap(distributor));
+      assert.equal(bitmap.bits[0], 0b100);
+    });
+
+    it('Withdraw returns the master edition and ends claims', async () => {
+      await program.methods.withdrawAirdropVault().accounts({
+        authority: authority.publicKey,
+        distributor,
+        masterMint: master.mint,
+        authorityTokenAccount: master.tokenAccount,
+        vault: findVault(distributor),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([authority]).rpc();
+
+      assert.equal(Number((await getAccount(connection, master.tokenAccount)).amount), 1);
+      assert.isNull(await connection.getAccountInfo(findVault(distributor)));
+      try {
+        await claim(distributor, 0, 1, tree.proof(0), master);
+        assert.fail("claim after withdraw should fail");
+      } catch (e) {
+        assert.notEqual(e.message, "claim after withdraw should fail");
+      }
+    });
+  });
+
+  describe("push", () => {
+    let distributor: anchor.web3.PublicKey;
+
+    const findAirdropMint = (item: number, target = distributor) => findProgramAddressSync(
+      [Buffer.from("AIRDROP_MINT"), target.toBuffer(), new anchor.BN(item).toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    )[0];
+
+    // Print distributors take the master NFT; item `n` is printed as edition `n + 1`
+    const push = (
+      firstItem: number,
+      wallets: anchor.web3.PublicKey[],
+      target = distributor,
+      master: TestNft = null,
+    ) => program.methods.pushAirdrop(new anchor.BN(firstItem)).accounts({
+      authority: authority.publicKey,
+      distributor: target,
+      claimBitmap: findBitmap(target),
+      masterMint: master ? master.mint : null,
+      masterEditionAccount: master ? master.masterEdition : null,
+      masterMetadataAccount: master ? master.metadata : null,
+      vault: master ? findVault(target) : null,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    }).remainingAccounts(wallets.flatMap((wallet, offset) => {
+      const item = firstItem + offset;
+      const mint = findAirdropMint(item, target);
+      const accounts = [
+        { pubkey: wallet, isSigner: false, isWritable: false },
+        { pubkey: getAssociatedTokenAddressSync(mint, wallet), isSigner: false, isWritable: true },
+        { pubkey: mint, isSigner: false, isWritable: true },
+        { pubkey: findMetadataAddress(mint), isSigner: false, isWritable: true },
+        { pubkey: findMasterEditionAddress(mint), isSigner: false, isWritable: true },
+      ];
+      if (master) {
+        accounts.push({ pubkey: findEditionMarkAddress(master.mint, item + 1), isSigner: false, isWritable: true });
+      }
+      return accounts;
+    })).preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+    ]).signers([authority]).rpc();
+
+    before(async () => {
+      distributor = await createDistributor(3, null, {
+        freshNft: { name: "Pushed", symbol: "PUSH", baseUri: "https://example.com/push/" },
+      });
+    });
+
+    it('Push mints items into each recipient ATA', async () => {
+      await push(0, recipients.slice(0, 2));
+
+      for (const [offset, wallet] of recipients.slice(0, 2).entries()) {
+        const mint = findAirdropMint(offset);
+        assert.equal(Number((await getAccount(connection, getAssociatedTokenAddressSync(mint, wallet))).amount), 1);
+        const metadata = await Metadata.fromAccountAddress(connection, findMetadataAddress(mint));
+        assert.equal(metadata.data.name.replace(/\0/g, ''), `Pushed #${offset}`);
+      }
+      const account = await program.account.airdropDistributor.fetch(distributor);
+      assert.equal(account.pushedCount.toNumber(), 2);
+      assert.equal(account.claimedCount.toNumber(), 0);
+    });
+
+    it('Resending a batch does not mint twice', async () => {
+      try {
+        await push(1, recipients.slice(1, 3));
+        assert.fail("resent batch should fail");
+      } catch (e) {
+        assert.include(String(e), "AirdropAlreadyClaimed");
+      }
+    });
+
+    it('Reject items beyond max recipients', async () => {
+      try {
+        await push(2, recipients.slice(0, 2));
+        assert.fail("pushed past max recipients");
+      } catch (e) {
+        assert.include(String(e), "InvalidAirdropIndex");
+      }
+    });
+
+    it('Push prints edition item + 1 from a print distributor', async () => {
+      const master = await createNft(connection, root, authority.publicKey, 0, null, null, 10);
+      const printDistributor = await createDistributor(4, null, { printEdition: { masterMint: master.mint } });
+      await program.methods.fundAirdropDistributor().accounts({
+        authority: authority.publicKey,
+        distributor: printDistributor,
+        masterMint: master.mint,
+        authorityTokenAccount: master.tokenAccount,
+        vault: findVault(printDistributor),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([authority]).rpc();
+
+      await push(0, recipients.slice(0, 2), printDistributor, master);
+
+      for (const [item, wallet] of recipients.slice(0, 2).entries()) {
+        const mint = findAirdropMint(item, printDistributor);
+        assert.equal(Number((await getAccount(connection, getAssociatedTokenAddressSync(mint, wallet))).amount), 1);
+        const edition = await Edition.fromAccountAddress(connection, findMasterEditionAddress(mint));
+        assert.equal(Number(edition.edition), item + 1);
+        assert.ok(edition.parent.equals(master.masterEdition));
+      }
+      // Editions 1 and 2 are the second and third bits of the first marker byte
+      const marker = await EditionMarker.fromAccountAddress(connection, findEditionMarkAddress(master.mint, 1));
+      assert.equal(marker.ledger[0], 0b0110_0000);
+      assert.equal((await program.account.airdropDistributor.fetch(printDistributor)).pushedCount.toNumber(), 2);
+    });
+
+    it('Push-only and claim distributors do not mix', async () => {
+      try {
+        await claim(distributor, 2, 2, []);
+        assert.fail("claimed from a push-only distributor");
+      } catch (e) {
+        assert.include(String(e), "AirdropMethodNotSupported");
+      }
+      try {
+        await push(2, recipients.slice(2), findDistributor(1));
+        assert.fail("pushed from a claim distributor");
+      } catch (e) {
+        assert.include(String(e), "AirdropMethodNotSupported");
+      }
+    });
+  });
+});
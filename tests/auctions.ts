@@ -0,0 +1,180 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { getAccount, getOrCreateAssociatedTokenAccount } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, createNft, createPrint, TestNft } from "./utils";
+
+describe("auctions", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  let root: anchor.web3.Keypair;
+  let seller: anchor.web3.Keypair;
+  let alice: anchor.web3.Keypair;
+  let bob: anchor.web3.Keypair;
+
+  const auctionAccounts = (nft: TestNft) => ({
+    auction: findProgramAddressSync([Buffer.from("AUCTION"), nft.mint.toBuffer()], program.programId)[0],
+    escrowTokenAccount: findProgramAddressSync([Buffer.from("AUCTION_ESCROW"), nft.mint.toBuffer()], program.programId)[0],
+  });
+
+  const now = () => Math.floor(Date.now() / 1000);
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    seller = anchor.web3.Keypair.generate();
+    alice = anchor.web3.Keypair.generate();
+    bob = anchor.web3.Keypair.generate();
+    await airdrop(connection, seller.publicKey);
+    await airdrop(connection, alice.publicKey, 2);
+    await airdrop(connection, bob.publicKey, 2);
+  });
+
+  it('English auction refunds outbid bidder and settles to winner', async () => {
+    const nft = await createNft(connection, root, seller.publicKey);
+    const { auction, escrowTokenAccount } = auctionAccounts(nft);
+
+    await program.methods.createAuction(
+      {
+        english: {
+          reservePrice: new anchor.BN(LAMPORTS_PER_SOL / 10),
+          minBidIncrement: new anchor.BN(LAMPORTS_PER_SOL / 100),
+          extensionSeconds: new anchor.BN(0),
+        },
+      },
+      new anchor.BN(now() - 1),
+      new anchor.BN(now() + 8),
+    ).accounts({
+      seller: seller.publicKey,
+      nftMint: nft.mint,
+      masterEditionAccount: nft.masterEdition,
+      sellerTokenAccount: nft.tokenAccount,
+      auction,
+      escrowTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([seller]).rpc();
+
+    try {
+      await program.methods.placeAuctionBid(new anchor.BN(LAMPORTS_PER_SOL / 20)).accounts({
+        bidder: alice.publicKey,
+        auction,
+        previousBidder: null,
+      }).signers([alice]).rpc();
+      assert.fail("Accepted a bid below the reserve price");
+    } catch (e) {
+      assert.include(String(e), "BidTooLow");
+    }
+
+    await program.methods.placeAuctionBid(new anchor.BN(LAMPORTS_PER_SOL / 10)).accounts({
+      bidder: alice.publicKey,
+      auction,
+      previousBidder: null,
+    }).signers([alice]).rpc();
+
+    const aliceBefore = await connection.getBalance(alice.publicKey);
+    await program.methods.placeAuctionBid(new anchor.BN(LAMPORTS_PER_SOL / 5)).accounts({
+      bidder: bob.publicKey,
+      auction,
+      previousBidder: alice.publicKey,
+    }).signers([bob]).rpc();
+    assert.equal(await connection.getBalance(alice.publicKey) - aliceBefore, LAMPORTS_PER_SOL / 10);
+
+    await new Promise(f => setTimeout(f, 10_000));
+
+    const bobTokenAccount = await getOrCreateAssociatedTokenAccount(connection, bob, nft.mint, bob.publicKey);
+    const tx = await program.methods.settleAuction().accounts({
+      seller: seller.publicKey,
+      auction,
+      nftMint: nft.mint,
+      escrowTokenAccount,
+      destinationTokenAccount: bobTokenAccount.address,
+      metadataAccount: nft.metadata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).rpc();
+    console.log(`Settle auction tx:`, tx);
+
+    assert.equal(Number((await getAccount(connection, bobTokenAccount.address)).amount), 1);
+    assert.isNull(await connection.getAccountInfo(auction));
+  });
+
+  it('Reject auctions of print editions', async () => {
+    const master = await createNft(connection, root, root.publicKey, 0, null, null, null);
+    const print = await createPrint(connection, root, master, seller.publicKey, 1);
+    const { auction, escrowTokenAccount } = auctionAccounts(print);
+
+    try {
+      await program.methods.createAuction(
+        {
+          english: {
+            reservePrice: new anchor.BN(LAMPORTS_PER_SOL / 10),
+            minBidIncrement: new anchor.BN(LAMPORTS_PER_SOL / 100),
+            extensionSeconds: new anchor.BN(0),
+          },
+        },
+        new anchor.BN(now() - 1),
+        new anchor.BN(now() + 8),
+      ).accounts({
+        seller: seller.publicKey,
+        nftMint: print.mint,
+        masterEditionAccount: print.masterEdition,
+        sellerTokenAccount: print.tokenAccount,
+        auction,
+        escrowTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([seller]).rpc();
+      assert.fail("Auctioned a print edition");
+    } catch (e) {
+      assert.include(String(e), "NotMasterEdition");
+    }
+  });
+
+  it('Dutch auction sells at a price no higher than the start price', async () => {
+    const nft = await createNft(connection, root, seller.publicKey);
+    const { auction, escrowTokenAccount } = auctionAccounts(nft);
+
+    const startPrice = LAMPORTS_PER_SOL;
+    await program.methods.createAuction(
+      {
+        dutch: {
+          startPrice: new anchor.BN(startPrice),
+          endPrice: new anchor.BN(LAMPORTS_PER_SOL / 10),
+          stepSeconds: new anchor.BN(0),
+        },
+      },
+      new anchor.BN(now() - 1),
+      new anchor.BN(now() + 60),
+    ).accounts({
+      seller: seller.publicKey,
+      nftMint: nft.mint,
+      masterEditionAccount: nft.masterEdition,
+      sellerTokenAccount: nft.tokenAccount,
+      auction,
+      escrowTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([seller]).rpc();
+
+    const aliceTokenAccount = await getOrCreateAssociatedTokenAccount(connection, alice, nft.mint, alice.publicKey);
+    const aliceBefore = await connection.getBalance(alice.publicKey);
+    const tx = await program.methods.buyDutchAuction(new anchor.BN(startPrice)).accounts({
+      buyer: alice.publicKey,
+      seller: seller.publicKey,
+      auction,
+      nftMint: nft.mint,
+      escrowTokenAccount,
+      buyerTokenAccount: aliceTokenAccount.address,
+      metadataAccount: nft.metadata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([alice]).rpc();
+    console.log(`Buy dutch auction tx:`, tx);
+
+    assert.isAtMost(aliceBefore - await connection.getBalance(alice.publicKey), startPrice);
+    assert.equal(Number((await getAccount(connection, aliceTokenAccount.address)).amount), 1);
+  });
+});
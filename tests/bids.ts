@@ -0,0 +1,131 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { getAccount, getOrCreateAssociatedTokenAccount } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, createNft, TestNft } from "./utils";
+
+describe("bids", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const AMOUNT = LAMPORTS_PER_SOL / 4;
+
+  let root: anchor.web3.Keypair;
+  let owner: anchor.web3.Keypair;
+  let bidder: anchor.web3.Keypair;
+  let nft: TestNft;
+
+  // `kind` is 0 for mint bids and 1 for collection bids
+  const findBid = (target: anchor.web3.PublicKey, kind = 0) => findProgramAddressSync(
+    [Buffer.from("BID"), bidder.publicKey.toBuffer(), Buffer.from([kind]), target.toBuffer()],
+    program.programId,
+  )[0];
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    owner = anchor.web3.Keypair.generate();
+    bidder = anchor.web3.Keypair.generate();
+    await airdrop(connection, owner.publicKey);
+    await airdrop(connection, bidder.publicKey);
+
+    nft = await createNft(connection, root, owner.publicKey);
+  });
+
+  it('Place and cancel a bid', async () => {
+    const bid = findBid(nft.mint);
+    await program.methods.placeBid({ mint: [nft.mint] }, new anchor.BN(AMOUNT), null).accounts({
+      bidder: bidder.publicKey,
+      bid,
+    }).signers([bidder]).rpc();
+
+    const tx = await program.methods.cancelBid().accounts({
+      bidder: bidder.publicKey,
+      bid,
+    }).signers([bidder]).rpc();
+    console.log(`Cancel bid tx:`, tx);
+
+    assert.isNull(await connection.getAccountInfo(bid));
+  });
+
+  it('Mint and collection bids on the same key do not collide', async () => {
+    const mintBid = findBid(nft.mint, 0);
+    const collectionBid = findBid(nft.mint, 1);
+    await program.methods.placeBid({ mint: [nft.mint] }, new anchor.BN(AMOUNT), null).accounts({
+      bidder: bidder.publicKey,
+      bid: mintBid,
+    }).signers([bidder]).rpc();
+    await program.methods.placeBid({ collection: [nft.mint] }, new anchor.BN(AMOUNT), null).accounts({
+      bidder: bidder.publicKey,
+      bid: collectionBid,
+    }).signers([bidder]).rpc();
+
+    const collectionBidAccount = await program.account.bid.fetch(collectionBid);
+    assert.isDefined(collectionBidAccount.target.collection);
+
+    for (const bid of [mintBid, collectionBid]) {
+      await program.methods.cancelBid().accounts({
+        bidder: bidder.publicKey,
+        bid,
+      }).signers([bidder]).rpc();
+    }
+  });
+
+  it('Reject closing a bid before expiry', async () => {
+    const bid = findBid(nft.mint);
+    const expiresAt = Math.floor(Date.now() / 1000) + 3600;
+    await program.methods.placeBid({ mint: [nft.mint] }, new anchor.BN(AMOUNT), new anchor.BN(expiresAt)).accounts({
+      bidder: bidder.publicKey,
+      bid,
+    }).signers([bidder]).rpc();
+
+    try {
+      await program.methods.closeExpiredBid().accounts({
+        bidder: bidder.publicKey,
+        bid,
+      }).rpc();
+      assert.fail("Closed a live bid");
+    } catch (e) {
+      assert.include(String(e), "BidNotExpired");
+    }
+
+    await program.methods.cancelBid().accounts({
+      bidder: bidder.publicKey,
+      bid,
+    }).signers([bidder]).rpc();
+  });
+
+  it('Accept a bid', async () => {
+    const bid = findBid(nft.mint);
+    await program.methods.placeBid({ mint: [nft.mint] }, new anchor.BN(AMOUNT), null).accounts({
+      bidder: bidder.publicKey,
+      bid,
+    }).signers([bidder]).rpc();
+
+    const bidderTokenAccount = await getOrCreateAssociatedTokenAccount(connection, bidder, nft.mint, bidder.publicKey);
+    const ownerBefore = await connection.getBalance(owner.publicKey);
+
+    const tx = await program.methods.acceptBid(new anchor.BN(AMOUNT)).accounts({
+      seller: owner.publicKey,
+      bidder: bidder.publicKey,
+      bid,
+      nftMint: nft.mint,
+      sellerTokenAccount: nft.tokenAccount,
+      bidderTokenAccount: bidderTokenAccount.address,
+      metadataAccount: nft.metadata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([owner]).rpc();
+    console.log(`Accept bid tx:`, tx);
+
+    // No royalty is configured on the test NFT, so the owner receives the full bid
+    assert.equal(await connection.getBalance(owner.publicKey) - ownerBefore, AMOUNT);
+    assert.equal(Number((await getAccount(connection, bidderTokenAccount.address)).amount), 1);
+    assert.isNull(await connection.getAccountInfo(bid));
+  });
+});
@@ -0,0 +1,78 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { createAccount, createMint, mintTo } from '@solana/spl-token';
+import { assert } from "chai";
+import { airdrop, createNft, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+describe("burns", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  let owner: anchor.web3.Keypair;
+
+  before(async () => {
+    owner = anchor.web3.Keypair.generate();
+    await airdrop(connection, owner.publicKey, 2);
+  });
+
+  it('Burn a master edition and send the reclaimed rent elsewhere', async () => {
+    const nft = await createNft(connection, owner, owner.publicKey);
+    const rentRecipient = anchor.web3.Keypair.generate().publicKey;
+
+    const tx = await program.methods.burnMasterEditionNft().accounts({
+      masterEditionMetadata: nft.metadata,
+      masterEditionAccount: nft.masterEdition,
+      masterEditionMint: nft.mint,
+      masterEditionTokenAccount: nft.tokenAccount,
+      owner: owner.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      rentRecipient,
+    }).signers([owner]).rpc();
+    console.log(`Burn master edition tx:`, tx);
+
+    assert.isNull(await connection.getAccountInfo(nft.tokenAccount));
+    assert.isNull(await connection.getAccountInfo(nft.metadata));
+    assert.isAbove(await connection.getBalance(rentRecipient), 0);
+  });
+
+  it('Sweep empty token accounts', async () => {
+    const mint = await createMint(connection, owner, owner.publicKey, null, 0);
+    const empty = await Promise.all([0, 1].map(() => createAccount(connection, owner, mint, owner.publicKey, anchor.web3.Keypair.generate())));
+    const rentRecipient = anchor.web3.Keypair.generate().publicKey;
+
+    await program.methods.closeEmptyTokenAccounts().accounts({
+      owner: owner.publicKey,
+      rentRecipient,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).remainingAccounts(empty.map(pubkey => ({ pubkey, isSigner: false, isWritable: true })))
+      .signers([owner]).rpc();
+
+    for (const account of empty) {
+      assert.isNull(await connection.getAccountInfo(account));
+    }
+    assert.isAbove(await connection.getBalance(rentRecipient), 0);
+  });
+
+  it('Refuse to sweep a token account that still holds tokens', async () => {
+    const mint = await createMint(connection, owner, owner.publicKey, null, 0);
+    const funded = await createAccount(connection, owner, mint, owner.publicKey, anchor.web3.Keypair.generate());
+    await mintTo(connection, owner, mint, funded, owner, 1);
+
+    try {
+      await program.methods.closeEmptyTokenAccounts().accounts({
+        owner: owner.publicKey,
+        rentRecipient: owner.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).remainingAccounts([{ pubkey: funded, isSigner: false, isWritable: true }])
+        .signers([owner]).rpc();
+      assert.fail("Closed a token account holding tokens");
+    } catch (e) {
+      assert.include(String(e), "TokenAccountNotEmpty");
+    }
+  });
+});
@@ -0,0 +1,267 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { createMint, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { Metadata } from '@metaplex-foundation/mpl-token-metadata';
+import {
+  ComputeBudgetProgram, LAMPORTS_PER_SOL, PublicKey, SYSVAR_INSTRUCTIONS_PUBKEY, SYSVAR_SLOT_HASHES_PUBKEY, TransactionInstruction,
+} from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, buildMerkleTree, findMasterEditionAddress, findMetadataAddress, merkleLeaf, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+// Mirrors `utils::provenance_leaf`: merkleLeaf(index le u32 || uri)
+const provenanceLeaf = (index: number, uri: string) => {
+  const indexBytes = Buffer.alloc(4);
+  indexBytes.writeUInt32LE(index);
+  return merkleLeaf(indexBytes, Buffer.from(uri));
+};
+
+describe("drops", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const PRICE = LAMPORTS_PER_SOL / 10;
+  const PLACEHOLDER_URI = "https://example.com/hidden.json";
+  const finalUris = [0, 1, 2, 3, 4, 5].map(index => `https://example.com/revealed/${index}.json`);
+  const provenance = buildMerkleTree(finalUris.map((uri, index) => provenanceLeaf(index, uri)));
+
+  let authority: anchor.web3.Keypair;
+  let buyer: anchor.web3.Keypair;
+  let drop: anchor.web3.PublicKey;
+  let mintBitmap: anchor.web3.PublicKey;
+  // Mint of every item, by the index the program picked for it
+  const mints: anchor.web3.PublicKey[] = [];
+
+  const findDropItem = (mint: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from("DROP_ITEM"), drop.toBuffer(), mint.toBuffer()],
+    program.programId,
+  )[0];
+
+  const mintItem = async () => {
+    const mint = await createMint(connection, buyer, buyer.publicKey, buyer.publicKey, 0);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, buyer, mint, buyer.publicKey);
+    await mintTo(connection, buyer, mint, tokenAccount.address, buyer, 1);
+
+    await program.methods.mintFromDrop().accounts({
+      buyer: buyer.publicKey,
+      authority: authority.publicKey,
+      drop,
+      mintBitmap,
+      mint,
+      dropItem: findDropItem(mint),
+      metadataAccount: findMetadataAddress(mint),
+      masterEditionAccount: findMasterEditionAddress(mint),
+      slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+      instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([buyer]).rpc();
+
+    const { index } = await program.account.dropItem.fetch(findDropItem(mint));
+    assert.isUndefined(mints[index], `index ${index} minted twice`);
+    mints[index] = mint;
+  };
+
+  const reveal = (entries: { index: number, uri: string }[]) => program.methods.reveal(entries.map(({ index, uri }) => ({
+    index,
+    uri,
+    proof: provenance.proof(index),
+  }))).accounts({
+    authority: authority.publicKey,
+    drop,
+    tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+  }).remainingAccounts(entries.flatMap(({ index }) => [
+    { pubkey: findDropItem(mints[index]), isSigner: false, isWritable: true },
+    { pubkey: findMetadataAddress(mints[index]), isSigner: false, isWritable: true },
+  ])).signers([authority]).rpc();
+
+  before(async () => {
+    authority = anchor.web3.Keypair.generate();
+    buyer = anchor.web3.Keypair.generate();
+    await airdrop(connection, authority.publicKey);
+    await airdrop(connection, buyer.publicKey);
+
+    drop = findProgramAddressSync(
+      [Buffer.from("DROP_CONFIG"), authority.publicKey.toBuffer(), new anchor.BN(1).toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    )[0];
+    mintBitmap = findProgramAddressSync([Buffer.from("DROP_BITMAP"), drop.toBuffer()], program.programId)[0];
+    await program.methods.createDrop(
+      new anchor.BN(1),
+      "Hidden",
+      "HIDE",
+      PLACEHOLDER_URI,
+      500,
+      new anchor.BN(PRICE),
+      finalUris.length,
+      provenance.root,
+    ).accounts({
+      authority: authority.publicKey,
+      drop,
+      mintBitmap,
+    }).signers([authority]).rpc();
+
+    for (const _ of finalUris) {
+      await mintItem();
+    }
+  });
+
+  it('Every index is minted exactly once', async () => {
+    assert.deepEqual(mints.map((_, index) => index), finalUris.map((_, index) => index));
+    assert.equal(new Set(mints.map(mint => mint.toBase58())).size, finalUris.length);
+    const bitmap = await program.account.dropMintBitmap.fetch(mintBitmap);
+    assert.equal(bitmap.bits[0], 0b111111);
+    assert.equal((await program.account.dropConfig.fetch(drop)).itemsMinted, finalUris.length);
+  });
+
+  it('Items mint with the placeholder uri', async () => {
+    const metadata = await Metadata.fromAccountAddress(connection, findMetadataAddress(mints[1]));
+    assert.equal(metadata.data.name.replace(/\0/g, ''), "Hidden #1");
+    assert.equal(metadata.data.uri.replace(/\0/g, ''), PLACEHOLDER_URI);
+    assert.ok(metadata.updateAuthority.equals(drop));
+    assert.isAbove(await connection.getBalance(authority.publicKey), LAMPORTS_PER_SOL);
+  });
+
+  it('Drop is sold out after the last item', async () => {
+    try {
+      await mintItem();
+      assert.fail("mint past supply should fail");
+    } catch (e) {
+      assert.notEqual(e.message, "mint past supply should fail");
+    }
+  });
+
+  it('Reveal a batch of items against the provenance hash', async () => {
+    await reveal([{ index: 0, uri: finalUris[0] }, { index: 2, uri: finalUris[2] }]);
+
+    for (const index of [0, 2]) {
+      const metadata = await Metadata.fromAccountAddress(connection, findMetadataAddress(mints[index]));
+      assert.equal(metadata.data.uri.replace(/\0/g, ''), finalUris[index]);
+      assert.isTrue((await program.account.dropItem.fetch(findDropItem(mints[index]))).revealed);
+    }
+    assert.equal((await program.account.dropConfig.fetch(drop)).itemsRevealed, 2);
+  });
+
+  it('Reject a reshuffled uri', async () => {
+    try {
+      await program.methods.reveal([{ index: 1, uri: finalUris[2], proof: provenance.proof(1) }]).accounts({
+        authority: authority.publicKey,
+        drop,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      }).remainingAccounts([
+        { pubkey: findDropItem(mints[1]), isSigner: false, isWritable: true },
+        { pubkey: findMetadataAddress(mints[1]), isSigner: false, isWritable: true },
+      ]).signers([authority]).rpc();
+      assert.fail("reshuffled uri should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "InvalidMerkleProof");
+    }
+  });
+
+  it('An item cannot be revealed twice', async () => {
+    try {
+      await reveal([{ index: 0, uri: finalUris[0] }]);
+      assert.fail("second reveal should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "DropItemAlreadyRevealed");
+    }
+  });
+
+  // Rejecting mints made through CPI needs a caller program and is covered by the Rust unit tests
+  describe("bot protection", () => {
+    const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+    const memo = new TransactionInstruction({ programId: MEMO_PROGRAM_ID, keys: [], data: Buffer.from("bot") });
+
+    let guardedDrop: anchor.web3.PublicKey;
+    let guardedBitmap: anchor.web3.PublicKey;
+
+    const prepareMint = async () => {
+      const mint = await createMint(connection, buyer, buyer.publicKey, buyer.publicKey, 0);
+      const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, buyer, mint, buyer.publicKey);
+      await mintTo(connection, buyer, mint, tokenAccount.address, buyer, 1);
+      return mint;
+    };
+
+    const mintMethod = (mint: anchor.web3.PublicKey) => program.methods.mintFromDrop().accounts({
+      buyer: buyer.publicKey,
+      authority: authority.publicKey,
+      drop: guardedDrop,
+      mintBitmap: guardedBitmap,
+      mint,
+      dropItem: findProgramAddressSync([Buffer.from("DROP_ITEM"), guardedDrop.toBuffer(), mint.toBuffer()], program.programId)[0],
+      metadataAccount: findMetadataAddress(mint),
+      masterEditionAccount: findMasterEditionAddress(mint),
+      slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+      instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([buyer]);
+
+    const setProtection = (allowedPrograms: PublicKey[]) => program.methods.setDropBotProtection({
+      requireTopLevel: true,
+      restrictPrograms: true,
+      singleMintPerTx: true,
+      allowedPrograms,
+    }).accounts({
+      authority: authority.publicKey,
+      drop: guardedDrop,
+    }).signers([authority]).rpc();
+
+    before(async () => {
+      guardedDrop = findProgramAddressSync(
+        [Buffer.from("DROP_CONFIG"), authority.publicKey.toBuffer(), new anchor.BN(2).toArrayLike(Buffer, "le", 8)],
+        program.programId,
+      )[0];
+      guardedBitmap = findProgramAddressSync([Buffer.from("DROP_BITMAP"), guardedDrop.toBuffer()], program.programId)[0];
+      await program.methods.createDrop(
+        new anchor.BN(2),
+        "Guarded",
+        "GUARD",
+        PLACEHOLDER_URI,
+        0,
+        new anchor.BN(0),
+        3,
+        provenance.root,
+      ).accounts({
+        authority: authority.publicKey,
+        drop: guardedDrop,
+        mintBitmap: guardedBitmap,
+      }).signers([authority]).rpc();
+      await setProtection([]);
+    });
+
+    it('Allow a lone top-level mint', async () => {
+      await mintMethod(await prepareMint())
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+        .rpc();
+      assert.equal((await program.account.dropConfig.fetch(guardedDrop)).itemsMinted, 1);
+    });
+
+    it('Reject programs outside the allowed set', async () => {
+      try {
+        await mintMethod(await prepareMint()).preInstructions([memo]).rpc();
+        assert.fail("disallowed program should fail");
+      } catch (e) {
+        assert.equal(e.error.errorCode.code, "DisallowedProgram");
+      }
+
+      await setProtection([MEMO_PROGRAM_ID]);
+      await mintMethod(await prepareMint()).preInstructions([memo]).rpc();
+      assert.equal((await program.account.dropConfig.fetch(guardedDrop)).itemsMinted, 2);
+    });
+
+    it('Reject a second mint in the same transaction', async () => {
+      const first = await mintMethod(await prepareMint()).instruction();
+      try {
+        await mintMethod(await prepareMint()).preInstructions([first]).rpc();
+        assert.fail("second mint should fail");
+      } catch (e) {
+        assert.equal(e.error.errorCode.code, "MultipleMintsInTransaction");
+      }
+    });
+  });
+});
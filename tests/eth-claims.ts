@@ -0,0 +1,167 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { Secp256k1Program, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { randomBytes } from "crypto";
+import { assert } from "chai";
+import {
+  airdrop, buildMerkleTree, createNft, findEditionMarkAddress, findMasterEditionAddress, findMetadataAddress, merkleLeaf, TestNft,
+  TOKEN_METADATA_PROGRAM_ID,
+} from "./utils";
+
+describe("eth-claims", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  let root: anchor.web3.Keypair;
+  let authority: anchor.web3.Keypair;
+  let payer: anchor.web3.Keypair;
+  let master: TestNft;
+  let drop: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+
+  const ethKeys = [randomBytes(32), randomBytes(32), randomBytes(32)];
+  // The secp256k1 instruction data carries the recovered address at offset 12
+  const ethAddressOf = (privateKey: Buffer) =>
+    Buffer.from(Secp256k1Program.createInstructionWithPrivateKey({ privateKey, message: Buffer.alloc(0) }).data.subarray(12, 32));
+  const snapshot = ethKeys.slice(0, 2).map(ethAddressOf);
+  const tree = buildMerkleTree(snapshot.map(address => merkleLeaf(address)));
+
+  const findClaim = (ethAddress: Buffer) => findProgramAddressSync(
+    [Buffer.from("ETH_CLAIM"), drop.toBuffer(), ethAddress],
+    program.programId,
+  )[0];
+
+  const claim = async (
+    privateKey: Buffer,
+    ethAddress: Buffer,
+    proof: number[][],
+    recipient: anchor.web3.PublicKey,
+    edition: number,
+    signedRecipient = recipient,
+  ) => {
+    const editionMint = await createMint(connection, payer, payer.publicKey, payer.publicKey, 0);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, payer, editionMint, recipient);
+    await mintTo(connection, payer, editionMint, tokenAccount.address, payer, 1);
+
+    const message = Buffer.concat([
+      Buffer.from("\x19Ethereum Signed Message:\n64"),
+      drop.toBuffer(),
+      signedRecipient.toBuffer(),
+    ]);
+    const signatureIx = Secp256k1Program.createInstructionWithPrivateKey({ privateKey, message });
+
+    await program.methods.claimEthDrop([...ethAddress], new anchor.BN(edition), proof).accounts({
+      payer: payer.publicKey,
+      recipient,
+      authority: authority.publicKey,
+      drop,
+      claim: findClaim(ethAddress),
+      editionMetadataAccount: findMetadataAddress(editionMint),
+      editionAccount: findMasterEditionAddress(editionMint),
+      editionMarkPda: findEditionMarkAddress(master.mint, edition),
+      editionMint,
+      recipientTokenAccount: tokenAccount.address,
+      masterMint: master.mint,
+      masterEditionAccount: master.masterEdition,
+      masterMetadataAccount: master.metadata,
+      vault,
+      instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).preInstructions([signatureIx]).signers([payer]).rpc();
+
+    return { editionMint, tokenAccount: tokenAccount.address };
+  };
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    authority = anchor.web3.Keypair.generate();
+    payer = anchor.web3.Keypair.generate();
+    await airdrop(connection, authority.publicKey);
+    await airdrop(connection, payer.publicKey);
+
+    master = await createNft(connection, root, authority.publicKey, 0, null, null, 10);
+    drop = findProgramAddressSync(
+      [Buffer.from("ETH_DROP"), authority.publicKey.toBuffer(), new anchor.BN(1).toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    )[0];
+    vault = findProgramAddressSync([Buffer.from("ETH_DROP_VAULT"), drop.toBuffer()], program.programId)[0];
+
+    await program.methods.createEthDrop(new anchor.BN(1), tree.root).accounts({
+      authority: authority.publicKey,
+      drop,
+      masterMint: master.mint,
+      masterEditionAccount: master.masterEdition,
+      authorityTokenAccount: master.tokenAccount,
+      vault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([authority]).rpc();
+  });
+
+  it('Snapshot address claims a print to the signed Solana wallet', async () => {
+    const wallet = anchor.web3.Keypair.generate().publicKey;
+    const { tokenAccount } = await claim(ethKeys[0], snapshot[0], tree.proof(0), wallet, 1);
+
+    assert.equal(Number((await getAccount(connection, tokenAccount)).amount), 1);
+    const claimAccount = await program.account.ethClaim.fetch(findClaim(snapshot[0]));
+    assert.ok(claimAccount.recipient.equals(wallet));
+    const dropAccount = await program.account.ethDrop.fetch(drop);
+    assert.equal(dropAccount.claimedCount.toNumber(), 1);
+  });
+
+  it('An address cannot claim twice', async () => {
+    try {
+      await claim(ethKeys[0], snapshot[0], tree.proof(0), anchor.web3.Keypair.generate().publicKey, 2);
+      assert.fail("second claim should fail");
+    } catch (e) {
+      assert.notEqual(e.message, "second claim should fail");
+    }
+  });
+
+  it('Reject addresses outside the snapshot', async () => {
+    const outsider = ethAddressOf(ethKeys[2]);
+    try {
+      await claim(ethKeys[2], outsider, tree.proof(0), payer.publicKey, 2);
+      assert.fail("outsider claim should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "InvalidMerkleProof");
+    }
+  });
+
+  it('Reject a signature for a different wallet', async () => {
+    try {
+      await claim(ethKeys[1], snapshot[1], tree.proof(1), payer.publicKey, 2, anchor.web3.Keypair.generate().publicKey);
+      assert.fail("mismatched recipient should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "InvalidEthSignature");
+    }
+  });
+
+  it('Claim an edition other than the next one', async () => {
+    const wallet = anchor.web3.Keypair.generate().publicKey;
+    const { tokenAccount } = await claim(ethKeys[1], snapshot[1], tree.proof(1), wallet, 5);
+    assert.equal(Number((await getAccount(connection, tokenAccount)).amount), 1);
+  });
+
+  it('Close the drop and return the master edition', async () => {
+    await program.methods.closeEthDrop().accounts({
+      authority: authority.publicKey,
+      drop,
+      masterMint: master.mint,
+      vault,
+      authorityTokenAccount: master.tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([authority]).rpc();
+
+    assert.equal(Number((await getAccount(connection, master.tokenAccount)).amount), 1);
+    assert.isNull(await connection.getAccountInfo(vault));
+    assert.isNull(await connection.getAccountInfo(drop));
+  });
+});
@@ -0,0 +1,110 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount, transfer } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, createNft } from "./utils";
+
+describe("fractions", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const SHARE_SUPPLY = 1_000;
+  const RESERVE_PRICE = LAMPORTS_PER_SOL / 2;
+
+  let root: anchor.web3.Keypair;
+  let curator: anchor.web3.Keypair;
+  let holder: anchor.web3.Keypair;
+  let buyer: anchor.web3.Keypair;
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    curator = anchor.web3.Keypair.generate();
+    holder = anchor.web3.Keypair.generate();
+    buyer = anchor.web3.Keypair.generate();
+    await airdrop(connection, curator.publicKey);
+    await airdrop(connection, holder.publicKey);
+    await airdrop(connection, buyer.publicKey);
+  });
+
+  it('Fractionalize, buy out and redeem pro rata', async () => {
+    const nft = await createNft(connection, root, curator.publicKey);
+    const vault = findProgramAddressSync([Buffer.from("FRACTION_VAULT"), nft.mint.toBuffer()], program.programId)[0];
+    const escrowTokenAccount = findProgramAddressSync([Buffer.from("FRACTION_ESCROW"), nft.mint.toBuffer()], program.programId)[0];
+    const shareMint = findProgramAddressSync([Buffer.from("FRACTION_SHARE_MINT"), vault.toBuffer()], program.programId)[0];
+    const curatorShareAccount = await getAssociatedTokenAddress(shareMint, curator.publicKey);
+
+    await program.methods.fractionalizeNft(new anchor.BN(SHARE_SUPPLY), 0, new anchor.BN(RESERVE_PRICE)).accounts({
+      curator: curator.publicKey,
+      nftMint: nft.mint,
+      masterEditionAccount: nft.masterEdition,
+      curatorTokenAccount: nft.tokenAccount,
+      vault,
+      escrowTokenAccount,
+      shareMint,
+      curatorShareAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([curator]).rpc();
+
+    assert.equal(Number((await getAccount(connection, curatorShareAccount)).amount), SHARE_SUPPLY);
+    assert.equal(Number((await getAccount(connection, escrowTokenAccount)).amount), 1);
+
+    // A quarter of the shares change hands before the buyout
+    const holderShareAccount = await getOrCreateAssociatedTokenAccount(connection, holder, shareMint, holder.publicKey);
+    await transfer(connection, curator, curatorShareAccount, holderShareAccount.address, curator, SHARE_SUPPLY / 4);
+
+    try {
+      await program.methods.redeemFractionShares(new anchor.BN(1)).accounts({
+        holder: holder.publicKey,
+        vault,
+        shareMint,
+        holderShareAccount: holderShareAccount.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([holder]).rpc();
+      assert.fail("Redeemed shares before a buyout");
+    } catch (e) {
+      assert.include(String(e), "VaultNotBoughtOut");
+    }
+
+    const buyerTokenAccount = await getOrCreateAssociatedTokenAccount(connection, buyer, nft.mint, buyer.publicKey);
+    const tx = await program.methods.buyoutFractionVault(new anchor.BN(RESERVE_PRICE)).accounts({
+      buyer: buyer.publicKey,
+      curator: curator.publicKey,
+      vault,
+      escrowTokenAccount,
+      buyerTokenAccount: buyerTokenAccount.address,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([buyer]).rpc();
+    console.log(`Buyout fraction vault tx:`, tx);
+    assert.equal(Number((await getAccount(connection, buyerTokenAccount.address)).amount), 1);
+
+    const holderBefore = await connection.getBalance(holder.publicKey);
+    await program.methods.redeemFractionShares(new anchor.BN(SHARE_SUPPLY / 4)).accounts({
+      holder: holder.publicKey,
+      vault,
+      shareMint,
+      holderShareAccount: holderShareAccount.address,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([holder]).rpc();
+    // The holder pays the transaction fee out of the redemption
+    assert.approximately(await connection.getBalance(holder.publicKey) - holderBefore, RESERVE_PRICE / 4, 10_000);
+
+    await program.methods.redeemFractionShares(new anchor.BN(SHARE_SUPPLY * 3 / 4)).accounts({
+      holder: curator.publicKey,
+      vault,
+      shareMint,
+      holderShareAccount: curatorShareAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([curator]).rpc();
+
+    const state = await program.account.fractionVault.fetch(vault);
+    assert.equal(state.outstandingShares.toNumber(), 0);
+    assert.equal(state.remainingProceeds.toNumber(), 0);
+  });
+});
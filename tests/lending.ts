@@ -0,0 +1,140 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { getAccount, getOrCreateAssociatedTokenAccount } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, createNft, TestNft, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+describe("lending", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const PRINCIPAL = LAMPORTS_PER_SOL / 2;
+  const INTEREST_RATE_BASIS_POINTS = 1_000;
+
+  let root: anchor.web3.Keypair;
+  let lender: anchor.web3.Keypair;
+  let borrower: anchor.web3.Keypair;
+  let collection: TestNft;
+
+  const findLoan = (id: number) => findProgramAddressSync(
+    [Buffer.from("LOAN"), lender.publicKey.toBuffer(), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+    program.programId,
+  )[0];
+
+  const createOffer = async (id: number, duration: number) => {
+    const loan = findLoan(id);
+    await program.methods.createLoanOffer(
+      new anchor.BN(id),
+      new anchor.BN(PRINCIPAL),
+      INTEREST_RATE_BASIS_POINTS,
+      new anchor.BN(duration),
+    ).accounts({
+      lender: lender.publicKey,
+      collectionMint: collection.mint,
+      loan,
+    }).signers([lender]).rpc();
+    return loan;
+  };
+
+  const takeLoan = async (loan: anchor.web3.PublicKey, nft: TestNft) => program.methods.takeLoan().accounts({
+    borrower: borrower.publicKey,
+    loan,
+    nftMint: nft.mint,
+    borrowerTokenAccount: nft.tokenAccount,
+    metadataAccount: nft.metadata,
+    editionAccount: nft.masterEdition,
+    tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  }).signers([borrower]).rpc();
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    lender = anchor.web3.Keypair.generate();
+    borrower = anchor.web3.Keypair.generate();
+    await airdrop(connection, lender.publicKey, 2);
+    await airdrop(connection, borrower.publicKey);
+
+    collection = await createNft(connection, root, root.publicKey);
+  });
+
+  it('Reject interest terms that overflow', async () => {
+    try {
+      await program.methods.createLoanOffer(
+        new anchor.BN(99),
+        new anchor.BN(PRINCIPAL),
+        65_535,
+        new anchor.BN("9223372036854775807"),
+      ).accounts({
+        lender: lender.publicKey,
+        collectionMint: collection.mint,
+        loan: findLoan(99),
+      }).signers([lender]).rpc();
+      assert.fail("Accepted loan terms whose repayment overflows");
+    } catch (e) {
+      assert.include(String(e), "NumericalOverflow");
+    }
+  });
+
+  it('Borrow against an NFT and repay with interest', async () => {
+    const nft = await createNft(connection, root, borrower.publicKey, 0, null, collection);
+    const loan = await createOffer(1, 3600);
+
+    const borrowerBefore = await connection.getBalance(borrower.publicKey);
+    await takeLoan(loan, nft);
+    assert.approximately(await connection.getBalance(borrower.publicKey) - borrowerBefore, PRINCIPAL, 10_000);
+    assert.isTrue((await getAccount(connection, nft.tokenAccount)).isFrozen);
+
+    const { repaymentAmount } = await program.account.loan.fetch(loan);
+    assert.isAbove(repaymentAmount.toNumber(), PRINCIPAL);
+
+    const lenderBefore = await connection.getBalance(lender.publicKey);
+    const tx = await program.methods.repayLoan().accounts({
+      borrower: borrower.publicKey,
+      lender: lender.publicKey,
+      loan,
+      nftMint: nft.mint,
+      borrowerTokenAccount: nft.tokenAccount,
+      editionAccount: nft.masterEdition,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([borrower]).rpc();
+    console.log(`Repay loan tx:`, tx);
+
+    // Lender also receives the rent of the closed loan account
+    assert.isAtLeast(await connection.getBalance(lender.publicKey) - lenderBefore, repaymentAmount.toNumber());
+    const collateral = await getAccount(connection, nft.tokenAccount);
+    assert.isFalse(collateral.isFrozen);
+    assert.isNull(collateral.delegate);
+  });
+
+  it('Lender forecloses after the deadline', async () => {
+    const nft = await createNft(connection, root, borrower.publicKey, 0, null, collection);
+    const loan = await createOffer(2, 1);
+    await takeLoan(loan, nft);
+
+    await new Promise(f => setTimeout(f, 3_000));
+
+    const lenderTokenAccount = await getOrCreateAssociatedTokenAccount(connection, lender, nft.mint, lender.publicKey);
+    const tx = await program.methods.forecloseLoan().accounts({
+      lender: lender.publicKey,
+      loan,
+      nftMint: nft.mint,
+      borrowerTokenAccount: nft.tokenAccount,
+      lenderTokenAccount: lenderTokenAccount.address,
+      editionAccount: nft.masterEdition,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([lender]).rpc();
+    console.log(`Foreclose loan tx:`, tx);
+
+    assert.equal(Number((await getAccount(connection, lenderTokenAccount.address)).amount), 1);
+    assert.isNull(await connection.getAccountInfo(loan));
+  });
+});
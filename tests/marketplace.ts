@@ -0,0 +1,104 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { getAccount, getOrCreateAssociatedTokenAccount } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, createNft, TestNft } from "./utils";
+
+describe("marketplace", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const PRICE = LAMPORTS_PER_SOL / 2;
+  const SELLER_FEE_BASIS_POINTS = 500;
+
+  let root: anchor.web3.Keypair;
+  let seller: anchor.web3.Keypair;
+  let buyer: anchor.web3.Keypair;
+  let creator: anchor.web3.Keypair;
+  let nft: TestNft;
+  let listing: anchor.web3.PublicKey;
+  let escrowTokenAccount: anchor.web3.PublicKey;
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    seller = anchor.web3.Keypair.generate();
+    buyer = anchor.web3.Keypair.generate();
+    creator = anchor.web3.Keypair.generate();
+    await airdrop(connection, seller.publicKey);
+    await airdrop(connection, buyer.publicKey);
+
+    nft = await createNft(connection, root, seller.publicKey, SELLER_FEE_BASIS_POINTS, [
+      { address: creator.publicKey, verified: false, share: 100 },
+    ]);
+    listing = findProgramAddressSync([Buffer.from("LISTING"), nft.mint.toBuffer()], program.programId)[0];
+    escrowTokenAccount = findProgramAddressSync([Buffer.from("LISTING_ESCROW"), nft.mint.toBuffer()], program.programId)[0];
+  });
+
+  it('List and cancel', async () => {
+    await program.methods.listNft(new anchor.BN(PRICE), null).accounts({
+      seller: seller.publicKey,
+      nftMint: nft.mint,
+      sellerTokenAccount: nft.tokenAccount,
+      listing,
+      escrowTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([seller]).rpc();
+    assert.equal(Number((await getAccount(connection, escrowTokenAccount)).amount), 1);
+
+    const tx = await program.methods.cancelListing().accounts({
+      seller: seller.publicKey,
+      listing,
+      nftMint: nft.mint,
+      escrowTokenAccount,
+      sellerTokenAccount: nft.tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([seller]).rpc();
+    console.log(`Cancel listing tx:`, tx);
+
+    assert.equal(Number((await getAccount(connection, nft.tokenAccount)).amount), 1);
+    assert.isNull(await connection.getAccountInfo(listing));
+  });
+
+  it('List and buy with royalty payout', async () => {
+    await program.methods.listNft(new anchor.BN(PRICE), null).accounts({
+      seller: seller.publicKey,
+      nftMint: nft.mint,
+      sellerTokenAccount: nft.tokenAccount,
+      listing,
+      escrowTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([seller]).rpc();
+
+    const buyerTokenAccount = await getOrCreateAssociatedTokenAccount(connection, buyer, nft.mint, buyer.publicKey);
+    const sellerBefore = await connection.getBalance(seller.publicKey);
+
+    const tx = await program.methods.buyNft(new anchor.BN(PRICE)).accounts({
+      buyer: buyer.publicKey,
+      seller: seller.publicKey,
+      listing,
+      nftMint: nft.mint,
+      escrowTokenAccount,
+      buyerTokenAccount: buyerTokenAccount.address,
+      metadataAccount: nft.metadata,
+      buyerPaymentTokenAccount: null,
+      sellerPaymentTokenAccount: null,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).remainingAccounts([
+      { pubkey: creator.publicKey, isWritable: true, isSigner: false },
+    ]).signers([buyer]).rpc();
+    console.log(`Buy NFT tx:`, tx);
+
+    const royalty = PRICE * SELLER_FEE_BASIS_POINTS / 10_000;
+    assert.equal(await connection.getBalance(creator.publicKey), royalty);
+    // Seller also receives the rent of the closed listing and escrow accounts
+    assert.isAtLeast(await connection.getBalance(seller.publicKey) - sellerBefore, PRICE - royalty);
+    assert.equal(Number((await getAccount(connection, buyerTokenAccount.address)).amount), 1);
+  });
+});
@@ -0,0 +1,133 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { getAccount, revoke } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, createNft, TestNft, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+describe("memberships", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const RENEWAL_PRICE = LAMPORTS_PER_SOL / 100;
+  const RENEWAL_PERIOD = 30 * 86_400;
+
+  let root: anchor.web3.Keypair;
+  let member: anchor.web3.Keypair;
+  let nft: TestNft;
+  let membership: anchor.web3.PublicKey;
+
+  // Reads the bool returned by `is_membership_active` from the simulated return data
+  const isActive = async () => {
+    const { raw } = await program.methods.isMembershipActive().accounts({ membership }).simulate();
+    const prefix = `Program return: ${program.programId.toBase58()} `;
+    const data = raw.find(log => log.startsWith(prefix)).slice(prefix.length);
+    return Buffer.from(data, "base64")[0] === 1;
+  };
+
+  const toggleAccounts = () => ({
+    membership,
+    nftMint: nft.mint,
+    tokenAccount: nft.tokenAccount,
+    editionAccount: nft.masterEdition,
+    tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    member = anchor.web3.Keypair.generate();
+    await airdrop(connection, member.publicKey);
+
+    nft = await createNft(connection, root, member.publicKey);
+    membership = findProgramAddressSync([Buffer.from("MEMBERSHIP"), nft.mint.toBuffer()], program.programId)[0];
+  });
+
+  it('Expired membership is frozen by the crank and thawed after renewal', async () => {
+    await program.methods.createMembership(
+      new anchor.BN(Math.floor(Date.now() / 1000) + 2),
+      new anchor.BN(RENEWAL_PRICE),
+      new anchor.BN(RENEWAL_PERIOD),
+      true,
+    ).accounts({
+      issuer: root.publicKey,
+      holder: member.publicKey,
+      nftMint: nft.mint,
+      holderTokenAccount: nft.tokenAccount,
+      metadataAccount: nft.metadata,
+      membership,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([root, member]).rpc();
+    assert.isTrue(await isActive());
+
+    try {
+      await program.methods.freezeExpiredMembership().accounts(toggleAccounts()).rpc();
+      assert.fail("Froze an active membership");
+    } catch (e) {
+      assert.include(String(e), "MembershipNotExpired");
+    }
+
+    await new Promise(f => setTimeout(f, 4_000));
+    assert.isFalse(await isActive());
+
+    await program.methods.freezeExpiredMembership().accounts(toggleAccounts()).rpc();
+    assert.isTrue((await getAccount(connection, nft.tokenAccount)).isFrozen);
+
+    const tx = await program.methods.renewMembership(2).accounts({
+      payer: member.publicKey,
+      issuer: root.publicKey,
+      membership,
+    }).signers([member]).rpc();
+    console.log(`Renew membership tx:`, tx);
+    assert.isTrue(await isActive());
+
+    const { expiresAt } = await program.account.membership.fetch(membership);
+    assert.isAtLeast(expiresAt.toNumber(), Math.floor(Date.now() / 1000) + 2 * RENEWAL_PERIOD - 60);
+
+    await program.methods.thawRenewedMembership().accounts(toggleAccounts()).rpc();
+    assert.isFalse((await getAccount(connection, nft.tokenAccount)).isFrozen);
+  });
+
+  it('Crank fails clearly once the holder revokes the delegate', async () => {
+    const revokedNft = await createNft(connection, root, member.publicKey);
+    const revokedMembership = findProgramAddressSync([Buffer.from("MEMBERSHIP"), revokedNft.mint.toBuffer()], program.programId)[0];
+    await program.methods.createMembership(
+      new anchor.BN(Math.floor(Date.now() / 1000) + 2),
+      new anchor.BN(RENEWAL_PRICE),
+      new anchor.BN(RENEWAL_PERIOD),
+      true,
+    ).accounts({
+      issuer: root.publicKey,
+      holder: member.publicKey,
+      nftMint: revokedNft.mint,
+      holderTokenAccount: revokedNft.tokenAccount,
+      metadataAccount: revokedNft.metadata,
+      membership: revokedMembership,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([root, member]).rpc();
+
+    await revoke(connection, member, revokedNft.tokenAccount, member);
+    await new Promise(f => setTimeout(f, 4_000));
+
+    try {
+      await program.methods.freezeExpiredMembership().accounts({
+        membership: revokedMembership,
+        nftMint: revokedNft.mint,
+        tokenAccount: revokedNft.tokenAccount,
+        editionAccount: revokedNft.masterEdition,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+      assert.fail("Froze an account that no longer delegates to the membership");
+    } catch (e) {
+      assert.include(String(e), "MembershipDelegateRevoked");
+    }
+    assert.isFalse((await getAccount(connection, revokedNft.tokenAccount)).isFrozen);
+  });
+});
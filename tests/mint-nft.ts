@@ -11,6 +11,7 @@ import {
 import { Connection, LAMPORTS_PER_SOL, SystemProgram } from "@solana/web3.js";
 import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
 import { Metadata } from '@metaplex-foundation/mpl-token-metadata';
+import { assert } from "chai";
 
 describe("mint-nft", () => {
   // Configure the client to use the local cluster.
@@ -44,6 +45,19 @@ describe("mint-nft", () => {
   let delegateAccount: anchor.web3.PublicKey;
   let delegateBump: number;
 
+  // Protocol fee accounts, charged in lamports without a referrer
+  const treasury = findProgramAddressSync([Buffer.from("TREASURY")], program.programId)[0];
+  const BPF_UPGRADEABLE_LOADER_ID = new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+  const fee = {
+    treasury,
+    payerTokenAccount: null,
+    treasuryTokenAccount: null,
+    referrer: null,
+    referrerTokenAccount: null,
+    systemProgram: SystemProgram.programId,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  };
+
 
   const connection = new Connection('https://api.devnet.solana.com', 'confirmed');
   
@@ -58,6 +72,21 @@ describe("mint-nft", () => {
     });
     console.log(`New token (mint): ${mintKeypair.publicKey}`);
     console.log(`New token associated account: ${rootATA}`);
+
+    if (await connection.getAccountInfo(treasury) === null) {
+      await program.methods.initializeTreasury({
+        feeMint: null,
+        mintFee: new anchor.BN(0),
+        masterEditionFee: new anchor.BN(0),
+        printFee: new anchor.BN(0),
+        referralBasisPoints: 0,
+      }).accounts({
+        treasury,
+        admin: root.publicKey,
+        program: program.programId,
+        programData: findProgramAddressSync([program.programId.toBuffer()], BPF_UPGRADEABLE_LOADER_ID)[0],
+      }).signers([root]).rpc();
+    }
   })
 
   it('Create Mint account', async () => {
@@ -87,7 +116,7 @@ describe("mint-nft", () => {
       mint: mintKeypair.publicKey,
       authority: root.publicKey,
       associatedToken: rootATA,
-    }).signers([root, mintKeypair]).rpc();
+    }).signers([root]).rpc();
     console.log(`Create ATA tx:`, tx);
     await new Promise(f => setTimeout(f, 100));
 
@@ -100,6 +129,33 @@ describe("mint-nft", () => {
     console.log('Sender ATA', senderATA.address.toBase58());
   });
 
+  it('Create ATA again without failing', async () => {
+    await program.methods.createAssociatedTokenAccount().accounts({
+      payer: root.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ssl.ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+      mint: mintKeypair.publicKey,
+      authority: root.publicKey,
+      associatedToken: rootATA,
+    }).signers([root]).rpc();
+  });
+
+  it('Reject an ATA address that does not match the authority and mint', async () => {
+    try {
+      await program.methods.createAssociatedTokenAccount().accounts({
+        payer: root.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ssl.ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+        mint: mintKeypair.publicKey,
+        authority: anchor.web3.Keypair.generate().publicKey,
+        associatedToken: rootATA,
+      }).signers([root]).rpc();
+      assert.fail("Created an ATA at a non-derived address");
+    } catch (e) {
+      assert.include(String(e), "InvalidAssociatedTokenAccount");
+    }
+  });
+
   it('Mint token to ATA', async () => {
     const tx = await program.methods.mintToken(new anchor.BN(1)).accounts({
       mint: mintKeypair.publicKey,
@@ -107,7 +163,8 @@ describe("mint-nft", () => {
       tokenProgram: TOKEN_PROGRAM_ID,
       tokenAccount: rootATA,
       payer: root.publicKey,
-    }).signers([mintKeypair, root]).rpc();
+      fee,
+    }).signers([root]).rpc();
     console.log(`Mint token to ATA tx:`, tx);
     await new Promise(f => setTimeout(f, 100));
 
@@ -145,7 +202,8 @@ describe("mint-nft", () => {
     ], 
     "Hello I'm back",
     "HIB", 
-    "https://raw.githubusercontent.com/Coding-and-Crypto/Solana-NFT-Marketplace/master/assets/example.json"
+    "https://raw.githubusercontent.com/Coding-and-Crypto/Solana-NFT-Marketplace/master/assets/example.json",
+    null,
     ).accounts({
       metadataAccount: metadataAddress,
       mint: mintKeypair.publicKey,
@@ -178,6 +236,7 @@ describe("mint-nft", () => {
       updateAuthority: root.publicKey,
       tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       metadataAccount: metadataAddress,
+      fee,
     }).signers([root]).rpc().catch(e => console.log(e));
     console.log(`Create metadata account tx:`, tx);
   });
@@ -229,7 +288,7 @@ describe("mint-nft", () => {
       mint: editionMintAddress.publicKey,
       authority: recipientEditionOwner.publicKey,
       associatedToken: recipientATA,
-    }).signers([root, editionMintAddress, recipientEditionOwner]).rpc();
+    }).signers([root]).rpc();
     console.log(`Create ATA tx:`, createATATx);
 
     const recipientATAAmount = await getOrCreateAssociatedTokenAccount(
@@ -269,7 +328,8 @@ describe("mint-nft", () => {
       tokenProgram: TOKEN_PROGRAM_ID,
       tokenAccount: recipientATA,
       payer: root.publicKey,
-    }).signers([editionMintAddress, root, recipientEditionOwner]).rpc();
+      fee,
+    }).signers([root, recipientEditionOwner]).rpc();
     console.log(`Mint token to ATA tx:`, mintTx);
 
     const encoder = new TextEncoder();
@@ -301,6 +361,7 @@ describe("mint-nft", () => {
       tokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      fee,
     }).signers([recipientEditionOwner, root])
     .rpc();
     console.log(`Create edition account tx:`, tx);
@@ -361,6 +422,7 @@ describe("mint-nft", () => {
   //     editionMarkPda: editionMarkPda,
   //     tokenProgram: TOKEN_PROGRAM_ID,
   //     tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+  //     rentRecipient: null,
   //   }).signers([recipientEditionOwner]).rpc().catch(e => console.log(e));
   //   console.log(`Burn edition tx:`, tx);
   // });
@@ -374,6 +436,7 @@ describe("mint-nft", () => {
   //     owner: root.publicKey,
   //     tokenProgram: TOKEN_PROGRAM_ID,
   //     tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+  //     rentRecipient: null,
   //   }).signers([root]).rpc().catch(e => console.log(e));
   //   console.log(`Burn master edition tx:`, tx);
   // })
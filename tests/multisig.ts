@@ -0,0 +1,207 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import {
+  createMint, getAccount, getOrCreateAssociatedTokenAccount,
+} from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+
+describe("multisig", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey(
+    "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+  );
+
+  let root: anchor.web3.Keypair;
+  let members: anchor.web3.Keypair[];
+  let multisig: anchor.web3.Keypair;
+  let multisigSigner: anchor.web3.PublicKey;
+  let mint: anchor.web3.PublicKey;
+  let tokenAccount: anchor.web3.PublicKey;
+
+  const findProposal = (index: number) => findProgramAddressSync(
+    [
+      Buffer.from("MULTISIG_PROPOSAL"),
+      multisig.publicKey.toBuffer(),
+      new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId,
+  )[0];
+
+  // Proposes `action` with members[0] and approves it with members[1] (threshold 2)
+  const proposeAndApprove = async (action: any): Promise<anchor.web3.PublicKey> => {
+    const multisigInfo = await program.account.multisig.fetch(multisig.publicKey);
+    const proposal = findProposal(multisigInfo.proposalCount.toNumber());
+
+    await program.methods.createMultisigProposal(action).accounts({
+      multisig: multisig.publicKey,
+      proposal,
+      proposer: members[0].publicKey,
+    }).signers([members[0]]).rpc();
+
+    await program.methods.approveMultisigProposal().accounts({
+      multisig: multisig.publicKey,
+      proposal,
+      member: members[1].publicKey,
+    }).signers([members[1]]).rpc();
+
+    return proposal;
+  };
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    members = [0, 1, 2].map(() => anchor.web3.Keypair.generate());
+    for (const member of members) {
+      const signature = await connection.requestAirdrop(member.publicKey, LAMPORTS_PER_SOL);
+      await connection.confirmTransaction(signature);
+    }
+
+    multisig = anchor.web3.Keypair.generate();
+    multisigSigner = findProgramAddressSync(
+      [Buffer.from("MULTISIG_SIGNER"), multisig.publicKey.toBuffer()],
+      program.programId,
+    )[0];
+  });
+
+  it('Create 2-of-3 multisig', async () => {
+    const tx = await program.methods.createMultisig(members.map(m => m.publicKey), 2).accounts({
+      multisig: multisig.publicKey,
+      multisigSigner,
+      payer: root.publicKey,
+    }).signers([multisig, root]).rpc();
+    console.log(`Create multisig tx:`, tx);
+
+    mint = await createMint(connection, root, multisigSigner, multisigSigner, 0);
+    tokenAccount = (await getOrCreateAssociatedTokenAccount(connection, root, mint, root.publicKey)).address;
+  });
+
+  it('Execute mint token proposal only after threshold', async () => {
+    const multisigInfo = await program.account.multisig.fetch(multisig.publicKey);
+    const proposal = findProposal(multisigInfo.proposalCount.toNumber());
+
+    await program.methods.createMultisigProposal({
+      mintToken: { mint, tokenAccount, amount: new anchor.BN(1) },
+    }).accounts({
+      multisig: multisig.publicKey,
+      proposal,
+      proposer: members[0].publicKey,
+    }).signers([members[0]]).rpc();
+
+    try {
+      await program.methods.executeMintTokenProposal().accounts({
+        multisig: multisig.publicKey,
+        proposal,
+        multisigSigner,
+        mint,
+        tokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+      assert.fail("Executed a proposal with a single approval");
+    } catch (e) {
+      assert.include(String(e), "ProposalThresholdNotMet");
+    }
+
+    await program.methods.approveMultisigProposal().accounts({
+      multisig: multisig.publicKey,
+      proposal,
+      member: members[2].publicKey,
+    }).signers([members[2]]).rpc();
+
+    const tx = await program.methods.executeMintTokenProposal().accounts({
+      multisig: multisig.publicKey,
+      proposal,
+      multisigSigner,
+      mint,
+      tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).rpc();
+    console.log(`Execute mint token proposal tx:`, tx);
+
+    const account = await getAccount(connection, tokenAccount);
+    assert.equal(Number(account.amount), 1);
+  });
+
+  it('Execute create metadata proposal', async () => {
+    const metadataAccount = findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID,
+    )[0];
+
+    const proposal = await proposeAndApprove({
+      createMetadata: {
+        mint,
+        creators: [{ address: multisigSigner, verified: true, share: 100 }],
+        name: "Multisig NFT",
+        symbol: "MSIG",
+        uri: "https://raw.githubusercontent.com/Coding-and-Crypto/Solana-NFT-Marketplace/master/assets/example.json",
+      },
+    });
+
+    const tx = await program.methods.executeCreateMetadataProposal().accounts({
+      multisig: multisig.publicKey,
+      proposal,
+      multisigSigner,
+      metadataAccount,
+      mint,
+      payer: root.publicKey,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+    }).signers([root]).rpc();
+    console.log(`Execute create metadata proposal tx:`, tx);
+  });
+
+  it('Execute create master edition proposal', async () => {
+    const metadataAccount = findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID,
+    )[0];
+    const masterEditionAccount = findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from('edition')],
+      TOKEN_METADATA_PROGRAM_ID,
+    )[0];
+
+    const proposal = await proposeAndApprove({
+      createMasterEdition: { mint, maxSupply: new anchor.BN(10) },
+    });
+
+    const tx = await program.methods.executeCreateMasterEditionProposal().accounts({
+      multisig: multisig.publicKey,
+      proposal,
+      multisigSigner,
+      masterEditionAccount,
+      metadataAccount,
+      mint,
+      payer: root.publicKey,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([root]).rpc();
+    console.log(`Execute create master edition proposal tx:`, tx);
+  });
+
+  it('Execute update metadata proposal', async () => {
+    const metadataAccount = findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID,
+    )[0];
+
+    const proposal = await proposeAndApprove({
+      updateMetadata: { mint, name: "Multisig NFT v2", symbol: null, uri: null, newUpdateAuthority: null },
+    });
+
+    const tx = await program.methods.executeUpdateMetadataProposal().accounts({
+      multisig: multisig.publicKey,
+      proposal,
+      multisigSigner,
+      metadataAccount,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+    }).rpc();
+    console.log(`Execute update metadata proposal tx:`, tx);
+  });
+});
@@ -0,0 +1,223 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import {
+  airdrop, createNft, findEditionMarkAddress, findMasterEditionAddress, findMetadataAddress, TestNft,
+  TOKEN_METADATA_PROGRAM_ID,
+} from "./utils";
+
+describe("redemptions", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const REWARD_AMOUNT = 500;
+
+  // Two programs on one collection: `printAuthority` pays a print of `rewardMaster` per
+  // burn, `tokenAuthority` pays REWARD_AMOUNT tokens per two burns
+  let printAuthority: anchor.web3.Keypair;
+  let tokenAuthority: anchor.web3.Keypair;
+  let holder: anchor.web3.Keypair;
+  let collection: TestNft;
+  let rewardMaster: TestNft;
+  let rewardMint: anchor.web3.PublicKey;
+  let holderRewardAccount: anchor.web3.PublicKey;
+  let printRedemption: anchor.web3.PublicKey;
+  let tokenRedemption: anchor.web3.PublicKey;
+
+  const findRedemption = (authority: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from("REDEMPTION"), authority.toBuffer(), collection.mint.toBuffer()],
+    program.programId,
+  )[0];
+  const findVault = (redemption: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from("REDEMPTION_VAULT"), redemption.toBuffer()],
+    program.programId,
+  )[0];
+  const findProgress = (redemption: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from("REDEMPTION_PROGRESS"), redemption.toBuffer(), holder.publicKey.toBuffer()],
+    program.programId,
+  )[0];
+
+  const emptyPayout = {
+    rewardTokenAccount: null,
+    newMint: null,
+    newMetadataAccount: null,
+    newEditionAccount: null,
+    editionMarkPda: null,
+    masterEditionAccount: null,
+    masterMetadataAccount: null,
+    systemProgram: anchor.web3.SystemProgram.programId,
+    rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+  };
+
+  const burnMaster = (
+    authority: anchor.web3.PublicKey,
+    redemption: anchor.web3.PublicKey,
+    nft: TestNft,
+    payout = emptyPayout,
+    rewardEdition = 0,
+  ) =>
+    program.methods.redeemMasterEditionBurn(new anchor.BN(rewardEdition)).accounts({
+      owner: holder.publicKey,
+      authority,
+      redemption,
+      progress: findProgress(redemption),
+      vault: findVault(redemption),
+      masterEditionMetadata: nft.metadata,
+      masterEditionMint: nft.mint,
+      masterEditionTokenAccount: nft.tokenAccount,
+      masterEditionAccount: nft.masterEdition,
+      collectionMetadataAccount: collection.metadata,
+      payout,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([holder]).rpc();
+
+  let print: { mint: anchor.web3.PublicKey, tokenAccount: anchor.web3.PublicKey };
+
+  before(async () => {
+    printAuthority = anchor.web3.Keypair.generate();
+    tokenAuthority = anchor.web3.Keypair.generate();
+    holder = anchor.web3.Keypair.generate();
+    await airdrop(connection, printAuthority.publicKey, 2);
+    await airdrop(connection, tokenAuthority.publicKey);
+    await airdrop(connection, holder.publicKey);
+
+    collection = await createNft(connection, printAuthority, printAuthority.publicKey);
+    rewardMaster = await createNft(connection, printAuthority, printAuthority.publicKey, 0, null, collection, null);
+    printRedemption = findRedemption(printAuthority.publicKey);
+    await program.methods.createRedemption(1, new anchor.BN(5), { printEdition: { masterMint: rewardMaster.mint } }).accounts({
+      authority: printAuthority.publicKey,
+      collectionMint: collection.mint,
+      redemption: printRedemption,
+      rewardMint: rewardMaster.mint,
+      authorityTokenAccount: rewardMaster.tokenAccount,
+      vault: findVault(printRedemption),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([printAuthority]).rpc();
+
+    rewardMint = await createMint(connection, tokenAuthority, tokenAuthority.publicKey, null, 6);
+    const authorityRewardAccount = await getOrCreateAssociatedTokenAccount(connection, tokenAuthority, rewardMint, tokenAuthority.publicKey);
+    await mintTo(connection, tokenAuthority, rewardMint, authorityRewardAccount.address, tokenAuthority, REWARD_AMOUNT);
+    holderRewardAccount = (await getOrCreateAssociatedTokenAccount(connection, holder, rewardMint, holder.publicKey)).address;
+    tokenRedemption = findRedemption(tokenAuthority.publicKey);
+    await program.methods.createRedemption(2, new anchor.BN(1), { token: { mint: rewardMint, amount: new anchor.BN(REWARD_AMOUNT) } }).accounts({
+      authority: tokenAuthority.publicKey,
+      collectionMint: collection.mint,
+      redemption: tokenRedemption,
+      rewardMint,
+      authorityTokenAccount: authorityRewardAccount.address,
+      vault: findVault(tokenRedemption),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([tokenAuthority]).rpc();
+
+    for (const redemption of [printRedemption, tokenRedemption]) {
+      await program.methods.openRedemptionProgress().accounts({
+        owner: holder.publicKey,
+        redemption,
+        progress: findProgress(redemption),
+      }).signers([holder]).rpc();
+    }
+  });
+
+  it('Reject an NFT outside the collection', async () => {
+    const nft = await createNft(connection, printAuthority, holder.publicKey);
+    try {
+      await burnMaster(tokenAuthority.publicKey, tokenRedemption, nft);
+      assert.fail("Redeemed an NFT outside the collection");
+    } catch (e) {
+      assert.include(String(e), "CollectionNotVerified");
+    }
+  });
+
+  it('Burn a master NFT for a print of the reward master', async () => {
+    const nft = await createNft(connection, printAuthority, holder.publicKey, 0, null, collection);
+    const mint = await createMint(connection, holder, holder.publicKey, holder.publicKey, 0);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, holder, mint, holder.publicKey);
+    await mintTo(connection, holder, mint, tokenAccount.address, holder, 1);
+
+    const tx = await burnMaster(printAuthority.publicKey, printRedemption, nft, {
+      ...emptyPayout,
+      newMint: mint,
+      newMetadataAccount: findMetadataAddress(mint),
+      newEditionAccount: findMasterEditionAddress(mint),
+      editionMarkPda: findEditionMarkAddress(rewardMaster.mint, 1),
+      masterEditionAccount: rewardMaster.masterEdition,
+      masterMetadataAccount: rewardMaster.metadata,
+    }, 1);
+    console.log(`Redeem master edition burn tx:`, tx);
+    print = { mint, tokenAccount: tokenAccount.address };
+
+    assert.isNull(await connection.getAccountInfo(nft.tokenAccount));
+    assert.isNotNull(await connection.getAccountInfo(findMasterEditionAddress(mint)));
+    const redemption = await program.account.redemption.fetch(printRedemption);
+    assert.equal(redemption.inventory.toNumber(), 4);
+  });
+
+  it('Bank burns until the ratio is met, then pay tokens', async () => {
+    const nft = await createNft(connection, printAuthority, holder.publicKey, 0, null, collection);
+    await burnMaster(tokenAuthority.publicKey, tokenRedemption, nft);
+    assert.equal((await program.account.redemptionProgress.fetch(findProgress(tokenRedemption))).burned, 1);
+    assert.equal(Number((await getAccount(connection, holderRewardAccount)).amount), 0);
+
+    // The printed reward inherits the verified collection, so it counts as a burn too
+    await program.methods.redeemEditionBurn(new anchor.BN(0)).accounts({
+      owner: holder.publicKey,
+      authority: tokenAuthority.publicKey,
+      redemption: tokenRedemption,
+      progress: findProgress(tokenRedemption),
+      vault: findVault(tokenRedemption),
+      editionMetadataAccount: findMetadataAddress(print.mint),
+      editionMint: print.mint,
+      masterEditionMint: rewardMaster.mint,
+      editionTokenAccount: print.tokenAccount,
+      masterEditionTokenAccount: findVault(printRedemption),
+      masterEditionAccount: rewardMaster.masterEdition,
+      editionAccount: findMasterEditionAddress(print.mint),
+      editionMarkPda: findEditionMarkAddress(rewardMaster.mint, 1),
+      payout: { ...emptyPayout, rewardTokenAccount: holderRewardAccount },
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([holder]).rpc();
+
+    assert.equal(Number((await getAccount(connection, holderRewardAccount)).amount), REWARD_AMOUNT);
+    assert.equal((await program.account.redemptionProgress.fetch(findProgress(tokenRedemption))).burned, 0);
+    const redemption = await program.account.redemption.fetch(tokenRedemption);
+    assert.equal(redemption.inventory.toNumber(), 0);
+    assert.equal(redemption.redeemedCount.toNumber(), 1);
+  });
+
+  it('Reject burns once the inventory is exhausted', async () => {
+    const nft = await createNft(connection, printAuthority, holder.publicKey, 0, null, collection);
+    try {
+      await burnMaster(tokenAuthority.publicKey, tokenRedemption, nft);
+      assert.fail("Burned with no rewards left");
+    } catch (e) {
+      assert.include(String(e), "RedemptionOutOfStock");
+    }
+  });
+
+  it('Close a redemption and return the escrowed master edition', async () => {
+    await program.methods.closeRedemption().accounts({
+      authority: printAuthority.publicKey,
+      redemption: printRedemption,
+      authorityTokenAccount: rewardMaster.tokenAccount,
+      vault: findVault(printRedemption),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([printAuthority]).rpc();
+
+    assert.equal(Number((await getAccount(connection, rewardMaster.tokenAccount)).amount), 1);
+    assert.isNull(await connection.getAccountInfo(printRedemption));
+
+    await program.methods.closeRedemptionProgress().accounts({
+      owner: holder.publicKey,
+      progress: findProgress(printRedemption),
+    }).signers([holder]).rpc();
+    assert.isNull(await connection.getAccountInfo(findProgress(printRedemption)));
+  });
+});
@@ -0,0 +1,107 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { getAccount, getOrCreateAssociatedTokenAccount } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, createNft, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+describe("rentals", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const DAILY_PRICE = LAMPORTS_PER_SOL / 100;
+
+  let root: anchor.web3.Keypair;
+  let owner: anchor.web3.Keypair;
+  let renter: anchor.web3.Keypair;
+
+  const findRental = (mint: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from("RENTAL"), mint.toBuffer()],
+    program.programId,
+  )[0];
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    owner = anchor.web3.Keypair.generate();
+    renter = anchor.web3.Keypair.generate();
+    await airdrop(connection, owner.publicKey);
+    await airdrop(connection, renter.publicKey);
+  });
+
+  it('List and cancel a rental', async () => {
+    const nft = await createNft(connection, root, owner.publicKey);
+    const rental = findRental(nft.mint);
+
+    await program.methods.listRental(new anchor.BN(DAILY_PRICE), 7).accounts({
+      owner: owner.publicKey,
+      nftMint: nft.mint,
+      ownerTokenAccount: nft.tokenAccount,
+      rental,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([owner]).rpc();
+    assert.isTrue((await getAccount(connection, nft.tokenAccount)).delegate.equals(rental));
+
+    const tx = await program.methods.cancelRental().accounts({
+      owner: owner.publicKey,
+      rental,
+      ownerTokenAccount: nft.tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([owner]).rpc();
+    console.log(`Cancel rental tx:`, tx);
+
+    assert.isNull((await getAccount(connection, nft.tokenAccount)).delegate);
+    assert.isNull(await connection.getAccountInfo(rental));
+  });
+
+  it('Rent freezes the NFT in the renter wallet until expiry', async () => {
+    const nft = await createNft(connection, root, owner.publicKey);
+    const rental = findRental(nft.mint);
+
+    await program.methods.listRental(new anchor.BN(DAILY_PRICE), 7).accounts({
+      owner: owner.publicKey,
+      nftMint: nft.mint,
+      ownerTokenAccount: nft.tokenAccount,
+      rental,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([owner]).rpc();
+
+    const renterTokenAccount = await getOrCreateAssociatedTokenAccount(connection, renter, nft.mint, renter.publicKey);
+    const tx = await program.methods.rentNft(2).accounts({
+      renter: renter.publicKey,
+      rental,
+      nftMint: nft.mint,
+      ownerTokenAccount: nft.tokenAccount,
+      renterTokenAccount: renterTokenAccount.address,
+      editionAccount: nft.masterEdition,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([renter]).rpc();
+    console.log(`Rent NFT tx:`, tx);
+
+    const renterAccount = await getAccount(connection, renterTokenAccount.address);
+    assert.equal(Number(renterAccount.amount), 1);
+    assert.isTrue(renterAccount.isFrozen);
+
+    try {
+      await program.methods.endRental().accounts({
+        owner: owner.publicKey,
+        rental,
+        nftMint: nft.mint,
+        ownerTokenAccount: nft.tokenAccount,
+        renterTokenAccount: renterTokenAccount.address,
+        editionAccount: nft.masterEdition,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+      assert.fail("Ended a rental before expiry");
+    } catch (e) {
+      assert.include(String(e), "RentalNotExpired");
+    }
+  });
+});
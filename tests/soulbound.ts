@@ -0,0 +1,156 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import {
+  createAssociatedTokenAccount, getAccount, getMint, getOrCreateAssociatedTokenAccount, mintTo, transfer,
+  TOKEN_2022_PROGRAM_ID,
+} from '@solana/spl-token';
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop } from "./utils";
+
+describe("soulbound", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  let root: anchor.web3.Keypair;
+  let holder: anchor.web3.Keypair;
+
+  const findSoulbound = (mint: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from("SOULBOUND"), mint.toBuffer()],
+    program.programId,
+  )[0];
+
+  const createSoulboundMint = async (tokenProgram: anchor.web3.PublicKey) => {
+    const mint = anchor.web3.Keypair.generate();
+    const soulbound = findSoulbound(mint.publicKey);
+
+    await program.methods.createSoulboundMint().accounts({
+      issuer: root.publicKey,
+      mint: mint.publicKey,
+      soulbound,
+      tokenProgram,
+    }).signers([root, mint]).rpc();
+    return { mint, soulbound };
+  };
+
+  const mintSoulbound = async (tokenProgram: anchor.web3.PublicKey) => {
+    const { mint, soulbound } = await createSoulboundMint(tokenProgram);
+    const tokenAccount = await createAssociatedTokenAccount(
+      connection, root, mint.publicKey, holder.publicKey, undefined, tokenProgram,
+    );
+    const tx = await program.methods.mintSoulboundToken().accounts({
+      issuer: root.publicKey,
+      recipient: holder.publicKey,
+      soulbound,
+      mint: mint.publicKey,
+      recipientTokenAccount: tokenAccount,
+      tokenProgram,
+    }).signers([root, holder]).rpc();
+    console.log(`Mint soulbound token tx:`, tx);
+
+    return { mint: mint.publicKey, soulbound, tokenAccount };
+  };
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    holder = anchor.web3.Keypair.generate();
+    await airdrop(connection, holder.publicKey);
+  });
+
+  it('Classic SPL soulbound NFT is frozen and revocable', async () => {
+    const { mint, soulbound, tokenAccount } = await mintSoulbound(TOKEN_PROGRAM_ID);
+
+    const account = await getAccount(connection, tokenAccount);
+    assert.equal(Number(account.amount), 1);
+    assert.isTrue(account.isFrozen);
+
+    const other = await getOrCreateAssociatedTokenAccount(connection, holder, mint, root.publicKey);
+    try {
+      await transfer(connection, holder, tokenAccount, other.address, holder, 1);
+      assert.fail("Transferred a soulbound NFT");
+    } catch (e) {
+      assert.include(String(e), "0x11");
+    }
+
+    await program.methods.revokeSoulboundToken().accounts({
+      issuer: root.publicKey,
+      soulbound,
+      mint,
+      tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([root]).rpc();
+
+    assert.equal(Number((await getAccount(connection, tokenAccount)).amount), 0);
+    assert.isNull(await connection.getAccountInfo(soulbound));
+  });
+
+  it('Token-2022 soulbound NFT is non-transferable and revocable', async () => {
+    const { mint, soulbound, tokenAccount } = await mintSoulbound(TOKEN_2022_PROGRAM_ID);
+    assert.equal(
+      Number((await getAccount(connection, tokenAccount, undefined, TOKEN_2022_PROGRAM_ID)).amount),
+      1,
+    );
+
+    const other = await createAssociatedTokenAccount(
+      connection, root, mint, root.publicKey, undefined, TOKEN_2022_PROGRAM_ID,
+    );
+    try {
+      await transfer(connection, holder, tokenAccount, other, holder, 1, [], undefined, TOKEN_2022_PROGRAM_ID);
+      assert.fail("Transferred a non-transferable NFT");
+    } catch (e) {
+      assert.include(String(e), "0x25");
+    }
+
+    await program.methods.revokeSoulboundToken().accounts({
+      issuer: root.publicKey,
+      soulbound,
+      mint,
+      tokenAccount,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    }).signers([root]).rpc();
+
+    assert.equal(
+      Number((await getAccount(connection, tokenAccount, undefined, TOKEN_2022_PROGRAM_ID)).amount),
+      0,
+    );
+  });
+
+  it('Soulbound mint has a fixed supply of one', async () => {
+    const { mint, tokenAccount } = await mintSoulbound(TOKEN_PROGRAM_ID);
+    assert.isNull((await getMint(connection, mint)).mintAuthority);
+
+    const other = await getOrCreateAssociatedTokenAccount(connection, root, mint, root.publicKey);
+    try {
+      await mintTo(connection, root, mint, other.address, root, 1);
+      assert.fail("Minted a second soulbound token");
+    } catch (e) {
+      assert.include(String(e), "0x5");
+    }
+    assert.equal(Number((await getAccount(connection, tokenAccount)).amount), 1);
+  });
+
+  it('Reject a recipient token account owned by someone else', async () => {
+    const { mint, soulbound } = await createSoulboundMint(TOKEN_2022_PROGRAM_ID);
+    const rootAccount = await createAssociatedTokenAccount(
+      connection, root, mint.publicKey, root.publicKey, undefined, TOKEN_2022_PROGRAM_ID,
+    );
+    try {
+      await program.methods.mintSoulboundToken().accounts({
+        issuer: root.publicKey,
+        recipient: holder.publicKey,
+        soulbound,
+        mint: mint.publicKey,
+        recipientTokenAccount: rootAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      }).signers([root, holder]).rpc();
+      assert.fail("Minted into an account the recipient does not own");
+    } catch (e) {
+      assert.include(String(e), "InvalidTokenAccountOwner");
+    }
+  });
+});
@@ -0,0 +1,204 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, createNft, TestNft, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+describe("staking", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const REWARD_RATE = 1_000;
+
+  let root: anchor.web3.Keypair;
+  let staker: anchor.web3.Keypair;
+  let collection: TestNft;
+  let rewardMint: anchor.web3.PublicKey;
+  let pool: anchor.web3.PublicKey;
+  let rewardVault: anchor.web3.PublicKey;
+  let stakerRewardAccount: anchor.web3.PublicKey;
+
+  const findStakeEntry = (mint: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from("STAKE_ENTRY"), pool.toBuffer(), mint.toBuffer()],
+    program.programId,
+  )[0];
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    staker = anchor.web3.Keypair.generate();
+    await airdrop(connection, staker.publicKey);
+
+    collection = await createNft(connection, root, root.publicKey);
+    rewardMint = await createMint(connection, root, root.publicKey, null, 6);
+    pool = findProgramAddressSync([Buffer.from("STAKE_POOL"), collection.mint.toBuffer()], program.programId)[0];
+    rewardVault = findProgramAddressSync([Buffer.from("STAKE_VAULT"), pool.toBuffer()], program.programId)[0];
+    stakerRewardAccount = (await getOrCreateAssociatedTokenAccount(connection, staker, rewardMint, staker.publicKey)).address;
+
+    await program.methods.createStakePool(new anchor.BN(REWARD_RATE)).accounts({
+      authority: root.publicKey,
+      collectionMint: collection.mint,
+      rewardMint,
+      pool,
+      rewardVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([root]).rpc();
+
+    const rootRewardAccount = await getOrCreateAssociatedTokenAccount(connection, root, rewardMint, root.publicKey);
+    await mintTo(connection, root, rewardMint, rootRewardAccount.address, root, 1_000_000_000);
+    await program.methods.fundStakePool(new anchor.BN(1_000_000_000)).accounts({
+      funder: root.publicKey,
+      pool,
+      funderTokenAccount: rootRewardAccount.address,
+      rewardVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([root]).rpc();
+  });
+
+  it('Reject an NFT outside the collection', async () => {
+    const nft = await createNft(connection, root, staker.publicKey);
+    try {
+      await program.methods.stakeNft().accounts({
+        owner: staker.publicKey,
+        pool,
+        stakeEntry: findStakeEntry(nft.mint),
+        nftMint: nft.mint,
+        nftTokenAccount: nft.tokenAccount,
+        metadataAccount: nft.metadata,
+        editionAccount: nft.masterEdition,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([staker]).rpc();
+      assert.fail("Staked an NFT outside the collection");
+    } catch (e) {
+      assert.include(String(e), "CollectionNotVerified");
+    }
+  });
+
+  it('Stake, accrue, claim and unstake', async () => {
+    const nft = await createNft(connection, root, staker.publicKey, 0, null, collection);
+    const stakeEntry = findStakeEntry(nft.mint);
+
+    await program.methods.stakeNft().accounts({
+      owner: staker.publicKey,
+      pool,
+      stakeEntry,
+      nftMint: nft.mint,
+      nftTokenAccount: nft.tokenAccount,
+      metadataAccount: nft.metadata,
+      editionAccount: nft.masterEdition,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([staker]).rpc();
+
+    const staked = await getAccount(connection, nft.tokenAccount);
+    assert.isTrue(staked.isFrozen);
+    assert.equal(Number(staked.amount), 1);
+    assert.equal((await program.account.stakePool.fetch(pool)).totalStaked.toNumber(), 1);
+
+    await new Promise(f => setTimeout(f, 3_000));
+
+    const tx = await program.methods.claimStakeRewards().accounts({
+      owner: staker.publicKey,
+      pool,
+      stakeEntry,
+      rewardVault,
+      ownerRewardAccount: stakerRewardAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([staker]).rpc();
+    console.log(`Claim stake rewards tx:`, tx);
+
+    const claimed = Number((await getAccount(connection, stakerRewardAccount)).amount);
+    assert.isAtLeast(claimed, REWARD_RATE);
+    assert.equal(claimed % REWARD_RATE, 0);
+
+    await program.methods.unstakeNft(false).accounts({
+      owner: staker.publicKey,
+      pool,
+      stakeEntry,
+      nftMint: nft.mint,
+      nftTokenAccount: nft.tokenAccount,
+      rewardVault,
+      ownerRewardAccount: stakerRewardAccount,
+      editionAccount: nft.masterEdition,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([staker]).rpc();
+
+    const unstaked = await getAccount(connection, nft.tokenAccount);
+    assert.isFalse(unstaked.isFrozen);
+    assert.isNull(unstaked.delegate);
+    assert.isNull(await connection.getAccountInfo(stakeEntry));
+    assert.equal((await program.account.stakePool.fetch(pool)).totalStaked.toNumber(), 0);
+  });
+
+  it('Authority withdraws only rewards no staker has accrued', async () => {
+    const rootRewardAccount = (await getOrCreateAssociatedTokenAccount(connection, root, rewardMint, root.publicKey)).address;
+    const withdraw = (amount: number | anchor.BN) => program.methods.withdrawStakeRewards(new anchor.BN(amount)).accounts({
+      authority: root.publicKey,
+      pool,
+      rewardVault,
+      authorityTokenAccount: rootRewardAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([root]).rpc();
+
+    const vaultBalance = Number((await getAccount(connection, rewardVault)).amount);
+    const unpaid = (await program.account.stakePool.fetch(pool)).unpaidRewards.toNumber();
+    try {
+      await withdraw(vaultBalance - unpaid + 1);
+      assert.fail("Withdrew rewards owed to stakers");
+    } catch (e) {
+      assert.include(String(e), "InsufficientUnallocatedRewards");
+    }
+
+    await withdraw(vaultBalance - unpaid);
+    assert.equal(Number((await getAccount(connection, rewardVault)).amount), unpaid);
+  });
+
+  it('Unstaking with unpaid rewards requires forfeiting them', async () => {
+    const nft = await createNft(connection, root, staker.publicKey, 0, null, collection);
+    const stakeEntry = findStakeEntry(nft.mint);
+    await program.methods.stakeNft().accounts({
+      owner: staker.publicKey,
+      pool,
+      stakeEntry,
+      nftMint: nft.mint,
+      nftTokenAccount: nft.tokenAccount,
+      metadataAccount: nft.metadata,
+      editionAccount: nft.masterEdition,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([staker]).rpc();
+    await new Promise(f => setTimeout(f, 2_000));
+
+    const unstake = (forfeitUnpaid: boolean) => program.methods.unstakeNft(forfeitUnpaid).accounts({
+      owner: staker.publicKey,
+      pool,
+      stakeEntry,
+      nftMint: nft.mint,
+      nftTokenAccount: nft.tokenAccount,
+      rewardVault,
+      ownerRewardAccount: stakerRewardAccount,
+      editionAccount: nft.masterEdition,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([staker]).rpc();
+
+    // The previous test left the vault with nothing beyond what was already owed
+    try {
+      await unstake(false);
+      assert.fail("Unstaked while rewards were unpaid");
+    } catch (e) {
+      assert.include(String(e), "StakeRewardsUnpaid");
+    }
+
+    await unstake(true);
+    assert.isNull(await connection.getAccountInfo(stakeEntry));
+    assert.equal((await program.account.stakePool.fetch(pool)).unpaidRewards.toNumber(), 0);
+  });
+});
@@ -0,0 +1,148 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { getAccount, getOrCreateAssociatedTokenAccount } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, createNft } from "./utils";
+
+describe("swaps", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  let root: anchor.web3.Keypair;
+  let maker: anchor.web3.Keypair;
+  let taker: anchor.web3.Keypair;
+
+  const findOffer = (id: number) => findProgramAddressSync(
+    [Buffer.from("SWAP"), maker.publicKey.toBuffer(), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+    program.programId,
+  )[0];
+
+  const findEscrow = (offer: anchor.web3.PublicKey, mint: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from("SWAP_ESCROW"), offer.toBuffer(), mint.toBuffer()],
+    program.programId,
+  )[0];
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    maker = anchor.web3.Keypair.generate();
+    taker = anchor.web3.Keypair.generate();
+    await airdrop(connection, maker.publicKey);
+    await airdrop(connection, taker.publicKey);
+  });
+
+  it('Swap NFT plus SOL for NFT atomically', async () => {
+    const makerNft = await createNft(connection, root, maker.publicKey);
+    const takerNft = await createNft(connection, root, taker.publicKey);
+    const offer = findOffer(1);
+    const escrow = findEscrow(offer, makerNft.mint);
+    const sweetener = LAMPORTS_PER_SOL / 10;
+
+    await program.methods.createSwapOffer(
+      new anchor.BN(1),
+      [{ mint: makerNft.mint, amount: new anchor.BN(1) }],
+      new anchor.BN(sweetener),
+      [{ mint: takerNft.mint, amount: new anchor.BN(1) }],
+      new anchor.BN(0),
+      taker.publicKey,
+      null,
+    ).accounts({
+      maker: maker.publicKey,
+      offer,
+    }).signers([maker]).rpc();
+
+    await program.methods.depositSwapItem().accounts({
+      maker: maker.publicKey,
+      offer,
+      mint: makerNft.mint,
+      makerTokenAccount: makerNft.tokenAccount,
+      escrowTokenAccount: escrow,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([maker]).rpc();
+
+    const takerReceiveAccount = await getOrCreateAssociatedTokenAccount(connection, taker, makerNft.mint, taker.publicKey);
+    const makerReceiveAccount = await getOrCreateAssociatedTokenAccount(connection, maker, takerNft.mint, maker.publicKey);
+    const takerBefore = await connection.getBalance(taker.publicKey);
+
+    const tx = await program.methods.acceptSwap().accounts({
+      taker: taker.publicKey,
+      maker: maker.publicKey,
+      offer,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).remainingAccounts([
+      { pubkey: escrow, isWritable: true, isSigner: false },
+      { pubkey: takerReceiveAccount.address, isWritable: true, isSigner: false },
+      { pubkey: takerNft.tokenAccount, isWritable: true, isSigner: false },
+      { pubkey: makerReceiveAccount.address, isWritable: true, isSigner: false },
+    ]).signers([taker]).rpc();
+    console.log(`Accept swap tx:`, tx);
+
+    assert.equal(Number((await getAccount(connection, takerReceiveAccount.address)).amount), 1);
+    assert.equal(Number((await getAccount(connection, makerReceiveAccount.address)).amount), 1);
+    assert.equal(await connection.getBalance(taker.publicKey) - takerBefore, sweetener);
+    assert.isNull(await connection.getAccountInfo(offer));
+  });
+
+  it('Cancel refunds the maker', async () => {
+    const makerNft = await createNft(connection, root, maker.publicKey);
+    const offer = findOffer(2);
+    const escrow = findEscrow(offer, makerNft.mint);
+
+    await program.methods.createSwapOffer(
+      new anchor.BN(2),
+      [{ mint: makerNft.mint, amount: new anchor.BN(1) }],
+      new anchor.BN(0),
+      [],
+      new anchor.BN(LAMPORTS_PER_SOL),
+      null,
+      null,
+    ).accounts({
+      maker: maker.publicKey,
+      offer,
+    }).signers([maker]).rpc();
+
+    await program.methods.depositSwapItem().accounts({
+      maker: maker.publicKey,
+      offer,
+      mint: makerNft.mint,
+      makerTokenAccount: makerNft.tokenAccount,
+      escrowTokenAccount: escrow,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([maker]).rpc();
+
+    try {
+      await program.methods.cancelSwapOffer().accounts({
+        authority: taker.publicKey,
+        maker: maker.publicKey,
+        offer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).remainingAccounts([
+        { pubkey: escrow, isWritable: true, isSigner: false },
+        { pubkey: makerNft.tokenAccount, isWritable: true, isSigner: false },
+      ]).signers([taker]).rpc();
+      assert.fail("Non-maker cancelled a live offer");
+    } catch (e) {
+      assert.include(String(e), "SwapNotExpired");
+    }
+
+    const tx = await program.methods.cancelSwapOffer().accounts({
+      authority: maker.publicKey,
+      maker: maker.publicKey,
+      offer,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).remainingAccounts([
+      { pubkey: escrow, isWritable: true, isSigner: false },
+      { pubkey: makerNft.tokenAccount, isWritable: true, isSigner: false },
+    ]).signers([maker]).rpc();
+    console.log(`Cancel swap offer tx:`, tx);
+
+    assert.equal(Number((await getAccount(connection, makerNft.tokenAccount)).amount), 1);
+    assert.isNull(await connection.getAccountInfo(offer));
+  });
+});
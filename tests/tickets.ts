@@ -0,0 +1,201 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import {
+  airdrop, createNft, findMasterEditionAddress, findMetadataAddress, TestNft, TOKEN_METADATA_PROGRAM_ID,
+} from "./utils";
+
+describe("tickets", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const PRICE = LAMPORTS_PER_SOL / 10;
+
+  let root: anchor.web3.Keypair;
+  let organizer: anchor.web3.Keypair;
+  let staff: anchor.web3.Keypair;
+  let attendee: anchor.web3.Keypair;
+  let master: TestNft;
+  let event: anchor.web3.PublicKey;
+  let tierVault: anchor.web3.PublicKey;
+
+  const findEditionMark = (edition: number) => findProgramAddressSync(
+    [
+      Buffer.from('metadata'),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      master.mint.toBuffer(),
+      Buffer.from('edition'),
+      Buffer.from(Math.floor(edition / 248).toString()),
+    ],
+    TOKEN_METADATA_PROGRAM_ID,
+  )[0];
+
+  const findTicket = (mint: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from("TICKET"), mint.toBuffer()],
+    program.programId,
+  )[0];
+
+  const purchase = async (edition: number) => {
+    const editionMint = await createMint(connection, attendee, attendee.publicKey, attendee.publicKey, 0);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, attendee, editionMint, attendee.publicKey);
+    await mintTo(connection, attendee, editionMint, tokenAccount.address, attendee, 1);
+
+    const tx = await program.methods.purchaseTicket(0, new anchor.BN(edition)).accounts({
+      buyer: attendee.publicKey,
+      organizer: organizer.publicKey,
+      event,
+      ticket: findTicket(editionMint),
+      editionMetadataAccount: findMetadataAddress(editionMint),
+      editionAccount: findMasterEditionAddress(editionMint),
+      editionMarkPda: findEditionMark(edition),
+      editionMint,
+      masterMint: master.mint,
+      masterEditionAccount: master.masterEdition,
+      masterMetadataAccount: master.metadata,
+      tierVault,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([attendee]).rpc();
+    console.log(`Purchase ticket tx:`, tx);
+
+    return { editionMint, tokenAccount: tokenAccount.address };
+  };
+
+  const checkIn = (editionMint: anchor.web3.PublicKey, tokenAccount: anchor.web3.PublicKey, gateStaff: anchor.web3.Keypair, freeze: boolean) =>
+    program.methods.checkInTicket(freeze).accounts({
+      gateStaff: gateStaff.publicKey,
+      holder: attendee.publicKey,
+      event,
+      ticket: findTicket(editionMint),
+      editionMint,
+      holderTokenAccount: tokenAccount,
+      editionAccount: findMasterEditionAddress(editionMint),
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([gateStaff, attendee]).rpc();
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    organizer = anchor.web3.Keypair.generate();
+    staff = anchor.web3.Keypair.generate();
+    attendee = anchor.web3.Keypair.generate();
+    await airdrop(connection, organizer.publicKey);
+    await airdrop(connection, attendee.publicKey);
+
+    master = await createNft(connection, root, organizer.publicKey, 0, null, null, 10);
+    event = findProgramAddressSync(
+      [Buffer.from("TICKET_EVENT"), organizer.publicKey.toBuffer(), new anchor.BN(1).toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    )[0];
+    tierVault = findProgramAddressSync(
+      [Buffer.from("TICKET_TIER_VAULT"), event.toBuffer(), master.mint.toBuffer()],
+      program.programId,
+    )[0];
+
+    await program.methods.createTicketEvent(
+      new anchor.BN(1),
+      2,
+      new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+      [staff.publicKey],
+    ).accounts({
+      organizer: organizer.publicKey,
+      event,
+    }).signers([organizer]).rpc();
+
+    await program.methods.addTicketTier(new anchor.BN(PRICE)).accounts({
+      organizer: organizer.publicKey,
+      event,
+      masterMint: master.mint,
+      masterEditionAccount: master.masterEdition,
+      organizerTokenAccount: master.tokenAccount,
+      tierVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([organizer]).rpc();
+  });
+
+  it('Purchase prints an edition and check-in marks it used', async () => {
+    const organizerBefore = await connection.getBalance(organizer.publicKey);
+    const { editionMint, tokenAccount } = await purchase(1);
+    assert.equal(await connection.getBalance(organizer.publicKey) - organizerBefore, PRICE);
+
+    try {
+      await checkIn(editionMint, tokenAccount, attendee, false);
+      assert.fail("Attendee checked in their own ticket");
+    } catch (e) {
+      assert.include(String(e), "NotGateStaff");
+    }
+
+    await checkIn(editionMint, tokenAccount, staff, true);
+    const ticket = await program.account.ticket.fetch(findTicket(editionMint));
+    assert.isTrue(ticket.checkedIn);
+    assert.isTrue((await getAccount(connection, tokenAccount)).isFrozen);
+
+    try {
+      await checkIn(editionMint, tokenAccount, staff, false);
+      assert.fail("Checked in a ticket twice");
+    } catch (e) {
+      assert.include(String(e), "TicketAlreadyUsed");
+    }
+  });
+
+  it('Post-event mode blocks check-ins and releases frozen tickets', async () => {
+    const { editionMint, tokenAccount } = await purchase(2);
+
+    try {
+      await purchase(3);
+      assert.fail("Sold beyond venue capacity");
+    } catch (e) {
+      assert.include(String(e), "EventSoldOut");
+    }
+
+    await program.methods.endTicketEvent().accounts({
+      organizer: organizer.publicKey,
+      event,
+    }).signers([organizer]).rpc();
+
+    try {
+      await checkIn(editionMint, tokenAccount, staff, false);
+      assert.fail("Checked in after the event ended");
+    } catch (e) {
+      assert.include(String(e), "TicketEventEnded");
+    }
+
+    const firstTicket = (await program.account.ticket.all([
+      { memcmp: { offset: 8, bytes: event.toBase58() } },
+    ])).find(({ account }) => account.frozenTokenAccount !== null);
+    const frozenTokenAccount = firstTicket.account.frozenTokenAccount;
+    await program.methods.releaseTicket().accounts({
+      holder: attendee.publicKey,
+      event,
+      ticket: firstTicket.publicKey,
+      editionMint: firstTicket.account.editionMint,
+      holderTokenAccount: frozenTokenAccount,
+      editionAccount: findMasterEditionAddress(firstTicket.account.editionMint),
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([attendee]).rpc();
+    assert.isFalse((await getAccount(connection, frozenTokenAccount)).isFrozen);
+  });
+
+  it('Organizer withdraws the tier master edition after the event', async () => {
+    await program.methods.withdrawTicketTier(0).accounts({
+      organizer: organizer.publicKey,
+      event,
+      masterMint: master.mint,
+      organizerTokenAccount: master.tokenAccount,
+      tierVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([organizer]).rpc();
+
+    assert.equal(Number((await getAccount(connection, master.tokenAccount)).amount), 1);
+    assert.isNull(await connection.getAccountInfo(tierVault));
+  });
+});
@@ -0,0 +1,140 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import {
+  createMint, getOrCreateAssociatedTokenAccount,
+} from '@solana/spl-token';
+import { SystemProgram } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+
+describe("treasury", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const treasury = findProgramAddressSync([Buffer.from("TREASURY")], program.programId)[0];
+  const BPF_UPGRADEABLE_LOADER_ID = new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+  const MINT_FEE = 10_000_000;
+  const REFERRAL_BASIS_POINTS = 2_500;
+
+  let root: anchor.web3.Keypair;
+  let referrer: anchor.web3.Keypair;
+
+  const feeConfig = (mintFee: number, referralBasisPoints: number) => ({
+    feeMint: null,
+    mintFee: new anchor.BN(mintFee),
+    masterEditionFee: new anchor.BN(0),
+    printFee: new anchor.BN(0),
+    referralBasisPoints,
+  });
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    referrer = anchor.web3.Keypair.generate();
+
+    if (await connection.getAccountInfo(treasury) === null) {
+      await program.methods.initializeTreasury(feeConfig(0, 0)).accounts({
+        treasury,
+        admin: root.publicKey,
+        program: program.programId,
+        programData: findProgramAddressSync([program.programId.toBuffer()], BPF_UPGRADEABLE_LOADER_ID)[0],
+      }).signers([root]).rpc();
+    }
+  });
+
+  it('Charge mint fee with referral split', async () => {
+    await program.methods.updateTreasury(feeConfig(MINT_FEE, REFERRAL_BASIS_POINTS), null).accounts({
+      treasury,
+      admin: root.publicKey,
+    }).signers([root]).rpc();
+
+    const mint = await createMint(connection, root, root.publicKey, root.publicKey, 0);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, root, mint, root.publicKey);
+
+    const treasuryBefore = await connection.getBalance(treasury);
+    const tx = await program.methods.mintToken(new anchor.BN(1)).accounts({
+      mint,
+      authority: root.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenAccount: tokenAccount.address,
+      payer: root.publicKey,
+      fee: {
+        treasury,
+        payerTokenAccount: null,
+        treasuryTokenAccount: null,
+        referrer: referrer.publicKey,
+        referrerTokenAccount: null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+    }).signers([root]).rpc();
+    console.log(`Mint token with fee tx:`, tx);
+
+    const referralAmount = MINT_FEE * REFERRAL_BASIS_POINTS / 10_000;
+    assert.equal(await connection.getBalance(referrer.publicKey), referralAmount);
+    assert.equal(await connection.getBalance(treasury) - treasuryBefore, MINT_FEE - referralAmount);
+  });
+
+  it('Reject self-referral', async () => {
+    const mint = await createMint(connection, root, root.publicKey, root.publicKey, 0);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, root, mint, root.publicKey);
+    try {
+      await program.methods.mintToken(new anchor.BN(1)).accounts({
+        mint,
+        authority: root.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenAccount: tokenAccount.address,
+        payer: root.publicKey,
+        fee: {
+          treasury,
+          payerTokenAccount: null,
+          treasuryTokenAccount: null,
+          referrer: root.publicKey,
+          referrerTokenAccount: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }).signers([root]).rpc();
+      assert.fail("Payer referred themselves");
+    } catch (e) {
+      assert.include(String(e), "SelfReferral");
+    }
+  });
+
+  it('Withdraw lamports as admin', async () => {
+    const recipient = anchor.web3.Keypair.generate();
+    const tx = await program.methods.withdrawTreasuryLamports(new anchor.BN(MINT_FEE / 2)).accounts({
+      treasury,
+      admin: root.publicKey,
+      recipient: recipient.publicKey,
+    }).signers([root]).rpc();
+    console.log(`Withdraw treasury lamports tx:`, tx);
+
+    assert.equal(await connection.getBalance(recipient.publicKey), MINT_FEE / 2);
+  });
+
+  it('Reject withdraw from non-admin', async () => {
+    const intruder = anchor.web3.Keypair.generate();
+    try {
+      await program.methods.withdrawTreasuryLamports(new anchor.BN(1)).accounts({
+        treasury,
+        admin: intruder.publicKey,
+        recipient: intruder.publicKey,
+      }).signers([intruder]).rpc();
+      assert.fail("Non-admin withdrew from the treasury");
+    } catch (e) {
+      assert.include(String(e), "ConstraintHasOne");
+    }
+  });
+
+  after(async () => {
+    await program.methods.updateTreasury(feeConfig(0, 0), null).accounts({
+      treasury,
+      admin: root.publicKey,
+    }).signers([root]).rpc();
+  });
+});
@@ -0,0 +1,129 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { SolanaConfigService } from '@coin98/solana-support-library/config'
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { createMint, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { Metadata } from '@metaplex-foundation/mpl-token-metadata';
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, findMetadataAddress, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+describe("uses", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const TOTAL_USES = 3;
+
+  let root: anchor.web3.Keypair;
+  let holder: anchor.web3.Keypair;
+  let gate: anchor.web3.Keypair;
+  let mint: anchor.web3.PublicKey;
+  let tokenAccount: anchor.web3.PublicKey;
+  let metadata: anchor.web3.PublicKey;
+
+  const findUseAuthorityRecord = (user: anchor.web3.PublicKey) => findProgramAddressSync(
+    [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from('user'), user.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID,
+  )[0];
+
+  const burner = findProgramAddressSync(
+    [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), Buffer.from('burn')],
+    TOKEN_METADATA_PROGRAM_ID,
+  )[0];
+
+  before(async () => {
+    root = await SolanaConfigService.getDefaultAccount();
+    holder = anchor.web3.Keypair.generate();
+    gate = anchor.web3.Keypair.generate();
+    await airdrop(connection, holder.publicKey);
+    await airdrop(connection, gate.publicKey);
+
+    mint = await createMint(connection, root, root.publicKey, root.publicKey, 0);
+    tokenAccount = (await getOrCreateAssociatedTokenAccount(connection, root, mint, holder.publicKey)).address;
+    await mintTo(connection, root, mint, tokenAccount, root, 1);
+    metadata = findMetadataAddress(mint);
+  });
+
+  it('Reject Single use method with more than one use', async () => {
+    try {
+      await program.methods.createTokenMetadataAccount(
+        [{ address: root.publicKey, verified: true, share: 100 }],
+        "Voucher",
+        "VCH",
+        "https://raw.githubusercontent.com/Coding-and-Crypto/Solana-NFT-Marketplace/master/assets/example.json",
+        { useMethod: { single: {} }, total: new anchor.BN(2) },
+      ).accounts({
+        metadataAccount: metadata,
+        mint,
+        mintAuthority: root.publicKey,
+        payer: root.publicKey,
+        updateAuthority: root.publicKey,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      }).signers([root]).rpc();
+      assert.fail("Accepted a Single use NFT with two uses");
+    } catch (e) {
+      assert.include(String(e), "InvalidUses");
+    }
+  });
+
+  it('Use an NFT as owner and through a use authority', async () => {
+    await program.methods.createTokenMetadataAccount(
+      [{ address: root.publicKey, verified: true, share: 100 }],
+      "Voucher",
+      "VCH",
+      "https://raw.githubusercontent.com/Coding-and-Crypto/Solana-NFT-Marketplace/master/assets/example.json",
+      { useMethod: { multiple: {} }, total: new anchor.BN(TOTAL_USES) },
+    ).accounts({
+      metadataAccount: metadata,
+      mint,
+      mintAuthority: root.publicKey,
+      payer: root.publicKey,
+      updateAuthority: root.publicKey,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+    }).signers([root]).rpc();
+
+    const tx = await program.methods.useNft(new anchor.BN(1)).accounts({
+      useAuthority: holder.publicKey,
+      owner: holder.publicKey,
+      nftMint: mint,
+      tokenAccount,
+      metadataAccount: metadata,
+      useAuthorityRecord: null,
+      burner: null,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([holder]).rpc();
+    console.log(`Use NFT tx:`, tx);
+
+    const useAuthorityRecord = findUseAuthorityRecord(gate.publicKey);
+    await program.methods.approveNftUseAuthority(new anchor.BN(1)).accounts({
+      owner: holder.publicKey,
+      user: gate.publicKey,
+      nftMint: mint,
+      ownerTokenAccount: tokenAccount,
+      metadataAccount: metadata,
+      useAuthorityRecord,
+      burner,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([holder]).rpc();
+
+    await program.methods.useNft(new anchor.BN(1)).accounts({
+      useAuthority: gate.publicKey,
+      owner: holder.publicKey,
+      nftMint: mint,
+      tokenAccount,
+      metadataAccount: metadata,
+      useAuthorityRecord,
+      burner: null,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([gate]).rpc();
+
+    const { uses } = await Metadata.fromAccountAddress(connection, metadata);
+    assert.equal(Number(uses.remaining), TOTAL_USES - 2);
+  });
+});
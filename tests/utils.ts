@@ -0,0 +1,184 @@
+import * as anchor from "@project-serum/anchor";
+import {
+  createMint, getOrCreateAssociatedTokenAccount, mintTo,
+} from '@solana/spl-token';
+import {
+  createCreateMasterEditionV3Instruction,
+  createCreateMetadataAccountV3Instruction,
+  createMintNewEditionFromMasterEditionViaTokenInstruction,
+  createVerifyCollectionInstruction,
+  PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID,
+} from '@metaplex-foundation/mpl-token-metadata';
+import { LAMPORTS_PER_SOL, Transaction } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { keccak_256 } from "@noble/hashes/sha3";
+
+export { TOKEN_METADATA_PROGRAM_ID };
+
+export const findMetadataAddress = (mint: anchor.web3.PublicKey) => findProgramAddressSync(
+  [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+  TOKEN_METADATA_PROGRAM_ID,
+)[0];
+
+export const findMasterEditionAddress = (mint: anchor.web3.PublicKey) => findProgramAddressSync(
+  [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from('edition')],
+  TOKEN_METADATA_PROGRAM_ID,
+)[0];
+
+export const findEditionMarkAddress = (masterMint: anchor.web3.PublicKey, edition: number) => findProgramAddressSync(
+  [
+    Buffer.from('metadata'),
+    TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+    masterMint.toBuffer(),
+    Buffer.from('edition'),
+    Buffer.from(Math.floor(edition / 248).toString()),
+  ],
+  TOKEN_METADATA_PROGRAM_ID,
+)[0];
+
+// Mirrors `utils::merkle_leaf`: keccak256(0x00 || parts)
+export const merkleLeaf = (...parts: Buffer[]) => Buffer.from(keccak_256(Buffer.concat([Buffer.from([0x00]), ...parts])));
+
+// Sorted-pair keccak256 tree with 0x01-prefixed inner nodes, matching
+// `utils::verify_merkle_proof` and `airdrop tree`. Leaves come from `merkleLeaf`.
+export const buildMerkleTree = (leaves: Buffer[]) => {
+  const hashPair = (a: Buffer, b: Buffer) =>
+    Buffer.from(keccak_256(Buffer.concat([Buffer.from([0x01]), ...(Buffer.compare(a, b) <= 0 ? [a, b] : [b, a])])));
+
+  const layers = [leaves];
+  while (layers[layers.length - 1].length > 1) {
+    const layer = layers[layers.length - 1];
+    const next = [];
+    for (let i = 0; i < layer.length; i += 2) {
+      next.push(i + 1 < layer.length ? hashPair(layer[i], layer[i + 1]) : layer[i]);
+    }
+    layers.push(next);
+  }
+  const proof = (index: number) => {
+    const siblings = [];
+    for (const layer of layers.slice(0, -1)) {
+      const sibling = index ^ 1;
+      if (sibling < layer.length) siblings.push([...layer[sibling]]);
+      index = Math.floor(index / 2);
+    }
+    return siblings;
+  };
+  return { root: [...layers[layers.length - 1][0]], proof };
+};
+
+export const airdrop = async (connection: anchor.web3.Connection, owner: anchor.web3.PublicKey, sol = 1) => {
+  const signature = await connection.requestAirdrop(owner, sol * LAMPORTS_PER_SOL);
+  await connection.confirmTransaction(signature);
+};
+
+export interface TestNft {
+  mint: anchor.web3.PublicKey;
+  tokenAccount: anchor.web3.PublicKey;
+  metadata: anchor.web3.PublicKey;
+  masterEdition: anchor.web3.PublicKey;
+}
+
+// Mints a 1/1 master edition NFT to `owner` with the given royalty and creators.
+// When `collection` is a master edition created by `payer`, membership is verified.
+// `maxSupply` caps the number of prints (null for unlimited).
+export const createNft = async (
+  connection: anchor.web3.Connection,
+  payer: anchor.web3.Keypair,
+  owner: anchor.web3.PublicKey,
+  sellerFeeBasisPoints = 0,
+  creators: { address: anchor.web3.PublicKey, verified: boolean, share: number }[] = null,
+  collection: TestNft = null,
+  maxSupply: number | null = 0,
+): Promise<TestNft> => {
+  const mint = await createMint(connection, payer, payer.publicKey, payer.publicKey, 0);
+  const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, payer, mint, owner);
+  await mintTo(connection, payer, mint, tokenAccount.address, payer, 1);
+
+  const metadata = findMetadataAddress(mint);
+  const masterEdition = findMasterEditionAddress(mint);
+
+  const tx = new Transaction().add(
+    createCreateMetadataAccountV3Instruction({
+      metadata,
+      mint,
+      mintAuthority: payer.publicKey,
+      payer: payer.publicKey,
+      updateAuthority: payer.publicKey,
+    }, {
+      createMetadataAccountArgsV3: {
+        data: {
+          name: "Test NFT",
+          symbol: "TEST",
+          uri: "https://raw.githubusercontent.com/Coding-and-Crypto/Solana-NFT-Marketplace/master/assets/example.json",
+          sellerFeeBasisPoints,
+          creators: creators ?? [{ address: payer.publicKey, verified: true, share: 100 }],
+          collection: collection ? { key: collection.mint, verified: false } : null,
+          uses: null,
+        },
+        isMutable: true,
+        collectionDetails: null,
+      },
+    }),
+    createCreateMasterEditionV3Instruction({
+      edition: masterEdition,
+      mint,
+      updateAuthority: payer.publicKey,
+      mintAuthority: payer.publicKey,
+      payer: payer.publicKey,
+      metadata,
+    }, {
+      createMasterEditionArgs: { maxSupply },
+    }),
+  );
+  if (collection) {
+    tx.add(createVerifyCollectionInstruction({
+      metadata,
+      collectionAuthority: payer.publicKey,
+      payer: payer.publicKey,
+      collectionMint: collection.mint,
+      collection: collection.metadata,
+      collectionMasterEditionAccount: collection.masterEdition,
+    }));
+  }
+  await anchor.web3.sendAndConfirmTransaction(connection, tx, [payer]);
+
+  return { mint, tokenAccount: tokenAccount.address, metadata, masterEdition };
+};
+
+// Prints `edition` of `master` to `owner`. `payer` must hold the master edition token.
+// The returned `masterEdition` is the print's edition account.
+export const createPrint = async (
+  connection: anchor.web3.Connection,
+  payer: anchor.web3.Keypair,
+  master: TestNft,
+  owner: anchor.web3.PublicKey,
+  edition: number,
+): Promise<TestNft> => {
+  const mint = await createMint(connection, payer, payer.publicKey, payer.publicKey, 0);
+  const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, payer, mint, owner);
+  await mintTo(connection, payer, mint, tokenAccount.address, payer, 1);
+
+  const metadata = findMetadataAddress(mint);
+  const printEdition = findMasterEditionAddress(mint);
+
+  const tx = new Transaction().add(
+    createMintNewEditionFromMasterEditionViaTokenInstruction({
+      newMetadata: metadata,
+      newEdition: printEdition,
+      masterEdition: master.masterEdition,
+      newMint: mint,
+      editionMarkPda: findEditionMarkAddress(master.mint, edition),
+      newMintAuthority: payer.publicKey,
+      payer: payer.publicKey,
+      tokenAccountOwner: payer.publicKey,
+      tokenAccount: master.tokenAccount,
+      newMetadataUpdateAuthority: payer.publicKey,
+      metadata: master.metadata,
+    }, {
+      mintNewEditionFromMasterEditionViaTokenArgs: { edition },
+    }),
+  );
+  await anchor.web3.sendAndConfirmTransaction(connection, tx, [payer]);
+
+  return { mint, tokenAccount: tokenAccount.address, metadata, masterEdition: printEdition };
+};
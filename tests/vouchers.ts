@@ -0,0 +1,105 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { MintNft } from "../target/types/mint_nft";
+import { TOKEN_PROGRAM_ID } from "@project-serum/anchor/dist/cjs/utils/token";
+import { createMint, getOrCreateAssociatedTokenAccount, mintTo } from '@solana/spl-token';
+import { Metadata } from '@metaplex-foundation/mpl-token-metadata';
+import { Ed25519Program, LAMPORTS_PER_SOL, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
+import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { assert } from "chai";
+import { airdrop, findMasterEditionAddress, findMetadataAddress, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+describe("vouchers", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.MintNft as Program<MintNft>;
+  const connection = anchor.getProvider().connection;
+
+  const PRICE = LAMPORTS_PER_SOL / 10;
+
+  let creator: anchor.web3.Keypair;
+  let buyer: anchor.web3.Keypair;
+  let noncePage: anchor.web3.PublicKey;
+
+  const voucher = (nonce: number, signer = creator) => ({
+    creator: signer.publicKey,
+    name: "Lazy NFT",
+    symbol: "LAZY",
+    uri: "https://raw.githubusercontent.com/Coding-and-Crypto/Solana-NFT-Marketplace/master/assets/example.json",
+    sellerFeeBasisPoints: 500,
+    maxSupply: new anchor.BN(0),
+    price: new anchor.BN(PRICE),
+    recipient: null,
+    expiresAt: null,
+    nonce: new anchor.BN(nonce),
+  });
+
+  const redeem = async (signed: ReturnType<typeof voucher>, signer: anchor.web3.Keypair) => {
+    const mint = await createMint(connection, buyer, buyer.publicKey, buyer.publicKey, 0);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(connection, buyer, mint, buyer.publicKey);
+    await mintTo(connection, buyer, mint, tokenAccount.address, buyer, 1);
+
+    const message = program.coder.types.encode("MintVoucher", signed);
+    const signatureIx = Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message });
+
+    await program.methods.redeemMintVoucher(signed).accounts({
+      buyer: buyer.publicKey,
+      creator: creator.publicKey,
+      noncePage,
+      lazyMintAuthority: findProgramAddressSync([Buffer.from("LAZY_MINT_AUTHORITY"), creator.publicKey.toBuffer()], program.programId)[0],
+      mint,
+      metadataAccount: findMetadataAddress(mint),
+      masterEditionAccount: findMasterEditionAddress(mint),
+      instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).preInstructions([signatureIx]).signers([buyer]).rpc();
+
+    return mint;
+  };
+
+  before(async () => {
+    creator = anchor.web3.Keypair.generate();
+    buyer = anchor.web3.Keypair.generate();
+    await airdrop(connection, buyer.publicKey);
+
+    noncePage = findProgramAddressSync(
+      [Buffer.from("VOUCHER_NONCE"), creator.publicKey.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    )[0];
+    await program.methods.initializeVoucherNoncePage(new anchor.BN(0)).accounts({
+      payer: buyer.publicKey,
+      creator: creator.publicKey,
+      noncePage,
+    }).signers([buyer]).rpc();
+  });
+
+  it('Redeem a signed voucher and hand update authority to the creator', async () => {
+    const mint = await redeem(voucher(1), creator);
+
+    assert.equal(await connection.getBalance(creator.publicKey), PRICE);
+    const metadata = await Metadata.fromAccountAddress(connection, findMetadataAddress(mint));
+    assert.isTrue(metadata.updateAuthority.equals(creator.publicKey));
+    assert.equal(metadata.data.sellerFeeBasisPoints, 500);
+  });
+
+  it('Reject a replayed nonce', async () => {
+    try {
+      await redeem(voucher(1), creator);
+      assert.fail("Redeemed the same voucher twice");
+    } catch (e) {
+      assert.include(String(e), "VoucherAlreadyRedeemed");
+    }
+  });
+
+  it('Reject a voucher signed by someone else', async () => {
+    const forger = anchor.web3.Keypair.generate();
+    const forged = { ...voucher(2), creator: creator.publicKey };
+    try {
+      await redeem(forged, forger);
+      assert.fail("Accepted a forged voucher");
+    } catch (e) {
+      assert.include(String(e), "InvalidVoucherSignature");
+    }
+  });
+});